@@ -0,0 +1,70 @@
+//! Compares `write()` throughput against the previous char-by-char
+//! splitting. Run with `cargo bench`.
+//!
+//! Both loops store every line through the same per-line bookkeeping, such
+//! as level detection and metadata, so the ratio shows what bulk splitting
+//! saves on top of it rather than a raw splitting speedup.
+
+use std::hint::black_box;
+use std::io::Write;
+use std::time::{Duration, Instant};
+use test_log_collector::TestLogCollector;
+
+/// The splitting loop `write()` used before bulk copying. Lines are stored
+/// through `Extend`, which does the same per-line work as `write()`.
+fn char_by_char(buf: &[u8], collector: &mut TestLogCollector, current: &mut String) {
+    for ch in String::from_utf8_lossy(buf).chars() {
+        if ch == '\n' {
+            collector.extend([std::mem::take(current)]);
+        } else {
+            current.push(ch);
+        }
+    }
+}
+
+fn time(mut f: impl FnMut()) -> Duration {
+    let runs = 5;
+    let start = Instant::now();
+    for _ in 0..runs {
+        f();
+    }
+    start.elapsed() / runs
+}
+
+fn main() {
+    // Long lines, as in dumped payloads, so there are many bytes to split
+    // per line stored.
+    let line = format!("INFO payload={}\n", "µs=1234 ".repeat(512));
+    let capture = line.repeat(4 * 1024);
+    let chunks: Vec<&[u8]> = capture.as_bytes().chunks(8192).collect();
+    let megabytes = capture.len() as f64 / (1024.0 * 1024.0);
+
+    let baseline = time(|| {
+        let mut collector = TestLogCollector::new();
+        let mut current = String::new();
+        for chunk in &chunks {
+            char_by_char(black_box(chunk), &mut collector, &mut current);
+        }
+        black_box(collector.count());
+    });
+    let bulk = time(|| {
+        let mut collector = TestLogCollector::new();
+        for chunk in &chunks {
+            collector.write_all(black_box(chunk)).unwrap();
+        }
+        black_box(collector.count());
+    });
+
+    for (name, elapsed) in [("char-by-char", baseline), ("write()", bulk)] {
+        println!(
+            "{:<14} {:>8.2} ms  {:>8.1} MiB/s",
+            name,
+            elapsed.as_secs_f64() * 1000.0,
+            megabytes / elapsed.as_secs_f64()
+        );
+    }
+    println!(
+        "speedup: {:.1}x",
+        baseline.as_secs_f64() / bulk.as_secs_f64()
+    );
+}
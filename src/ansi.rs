@@ -0,0 +1,55 @@
+use crate::TestLogCollector;
+
+/// Removes ANSI escape sequences: CSI sequences such as colors and cursor
+/// movement, OSC sequences such as hyperlinks and window titles, and
+/// two-character escapes.
+pub(crate) fn strip_ansi(line: &str) -> String {
+    let mut stripped = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch != '\x1b' {
+            stripped.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('[') => {
+                // Parameter and intermediate bytes, then one final byte.
+                for ch in chars.by_ref() {
+                    if ('\x40'..='\x7e').contains(&ch) {
+                        break;
+                    }
+                }
+            }
+            Some(']') => {
+                // Terminated by BEL or ST (`ESC \`).
+                while let Some(ch) = chars.next() {
+                    if ch == '\x07' || (ch == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    stripped
+}
+
+impl TestLogCollector {
+    /// Removes ANSI escape sequences, such as colors from a terminal-aware
+    /// logger, from each line before it is stored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new().with_ansi_stripping();
+    /// writeln!(collector, "\x1b[1;31mERROR\x1b[0m disk full").unwrap();
+    ///
+    /// assert_eq!(collector, ["ERROR disk full"]);
+    /// ```
+    pub fn with_ansi_stripping(self) -> Self {
+        self.with_named_scrubber("ANSI stripping", strip_ansi)
+    }
+}
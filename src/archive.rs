@@ -0,0 +1,263 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Seek, Write};
+use std::iter::Take;
+use std::path::PathBuf;
+use std::slice;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::TestLogCollector;
+
+static NEXT_ARCHIVE_FILE: AtomicU64 = AtomicU64::new(0);
+
+/// The temporary file holding the oldest lines of a collector, one escaped
+/// line per file line. The file is deleted when the collector is dropped.
+pub(crate) struct Archive {
+    threshold: usize,
+    path: PathBuf,
+    file: Option<BufWriter<File>>,
+    count: usize,
+}
+
+impl Archive {
+    /// Closes the file after a failed write, so later lines stay in memory.
+    fn stop(&mut self, error: &io::Error) {
+        eprintln!(
+            "test_log_collector: failed to archive lines to {}, keeping them in memory: {}",
+            self.path.display(),
+            error
+        );
+        self.file = None;
+    }
+}
+
+impl Drop for Archive {
+    fn drop(&mut self) {
+        // Close the file first so it can be removed on every platform.
+        self.file.take();
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Escapes backslashes and line breaks so each line occupies one file line.
+fn escape(line: &str) -> String {
+    line.replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+fn unescape(line: &str) -> String {
+    let mut unescaped = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            unescaped.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(other) => unescaped.push(other),
+            None => {}
+        }
+    }
+    unescaped
+}
+
+/// Iterates over every line of a collector, including those archived to
+/// disk, oldest first.
+///
+/// Returned by [`TestLogCollector::all_lines`].
+pub struct AllLines<'a> {
+    archived: Option<Take<io::Lines<BufReader<File>>>>,
+    memory: slice::Iter<'a, String>,
+}
+
+impl Iterator for AllLines<'_> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(archived) = &mut self.archived {
+            match archived.next() {
+                Some(line) => return Some(line.map(|line| unescape(&line))),
+                None => self.archived = None,
+            }
+        }
+        self.memory.next().map(|line| Ok(line.clone()))
+    }
+}
+
+impl TestLogCollector {
+    /// Archives the oldest lines to a temporary file once more than
+    /// `threshold` lines are held in memory, keeping the most recent
+    /// `threshold / 2`, so soak tests can capture more output than fits in
+    /// memory.
+    ///
+    /// Archived lines leave the collector: [`lines`](Self::lines),
+    /// iteration, searches and assertions only see the lines in memory.
+    /// Only [`all_lines`](Self::all_lines) and [`grep_all`](Self::grep_all)
+    /// read the archive back, as text without metadata such as levels,
+    /// timestamps, sections or provenance. The file is
+    /// deleted when the collector is dropped. If writing to it fails, the
+    /// error is reported on stderr and the collector stops archiving,
+    /// keeping every later line in memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the temporary file cannot be created.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new().with_disk_archive(100).unwrap();
+    /// for i in 0..1_000 {
+    ///     writeln!(collector, "soak iteration {}", i).unwrap();
+    /// }
+    ///
+    /// assert!(collector.count() <= 100);
+    /// assert_eq!(collector.archived_count() + collector.count(), 1_000);
+    /// assert_eq!(collector.grep_all("iteration 7").unwrap()[0], (7, "soak iteration 7".to_string()));
+    /// ```
+    pub fn with_disk_archive(mut self, threshold: usize) -> io::Result<Self> {
+        let path = std::env::temp_dir().join(format!(
+            "test_log_collector-{}-{}.archive",
+            std::process::id(),
+            NEXT_ARCHIVE_FILE.fetch_add(1, Ordering::Relaxed)
+        ));
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(true)
+            .write(true)
+            .open(&path)?;
+        self.archive = Some(Archive {
+            threshold,
+            path,
+            file: Some(BufWriter::new(file)),
+            count: 0,
+        });
+        Ok(self)
+    }
+
+    /// Returns the number of lines moved to disk by
+    /// [`with_disk_archive`](Self::with_disk_archive).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// assert_eq!(TestLogCollector::new().archived_count(), 0);
+    /// ```
+    pub fn archived_count(&self) -> usize {
+        self.archive.as_ref().map_or(0, |archive| archive.count)
+    }
+
+    /// Iterates over every collected line, reading lines archived to disk
+    /// back before the lines in memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the archive file cannot be opened; the iterator
+    /// yields an error if reading it fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new().with_disk_archive(2).unwrap();
+    /// write!(collector, "one\ntwo\nthree\nfour\n").unwrap();
+    ///
+    /// let all: Vec<String> = collector.all_lines().unwrap().collect::<Result<_, _>>().unwrap();
+    /// assert_eq!(all, ["one", "two", "three", "four"]);
+    /// ```
+    pub fn all_lines(&self) -> io::Result<AllLines<'_>> {
+        let archived = match &self.archive {
+            Some(archive) => {
+                let file = File::open(&archive.path)?;
+                Some(BufReader::new(file).lines().take(archive.count))
+            }
+            None => None,
+        };
+        Ok(AllLines {
+            archived,
+            memory: self.lines.iter(),
+        })
+    }
+
+    /// Returns the index and text of each line containing `pattern`,
+    /// including lines archived to disk. Indices count from the first line
+    /// ever collected.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the archived lines cannot be read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new().with_disk_archive(2).unwrap();
+    /// write!(collector, "ERROR early\nok\nok\nok\nERROR late\n").unwrap();
+    ///
+    /// let errors = collector.grep_all("ERROR").unwrap();
+    /// assert_eq!(errors, [(0, "ERROR early".to_string()), (4, "ERROR late".to_string())]);
+    /// ```
+    pub fn grep_all(&self, pattern: &str) -> io::Result<Vec<(usize, String)>> {
+        let mut matches = Vec::new();
+        for (index, line) in self.all_lines()?.enumerate() {
+            let line = line?;
+            if line.contains(pattern) {
+                matches.push((index, line));
+            }
+        }
+        Ok(matches)
+    }
+
+    pub(crate) fn archive_if_needed(&mut self) {
+        let Some(archive) = &mut self.archive else {
+            return;
+        };
+        if self.lines.len() <= archive.threshold {
+            return;
+        }
+        let Some(file) = &mut archive.file else {
+            return;
+        };
+        let archived = self.lines.len() - archive.threshold / 2;
+        let result = self.lines[..archived]
+            .iter()
+            .try_for_each(|line| writeln!(file, "{}", escape(line)))
+            .and_then(|()| file.flush());
+        if let Err(error) = result {
+            // Lines after the first `count` are never read back, so a
+            // partial write leaves the archived ones intact.
+            archive.stop(&error);
+            return;
+        }
+        archive.count += archived;
+        self.lines.drain(..archived);
+        self.meta.drain(..archived);
+        self.reindex();
+    }
+
+    pub(crate) fn clear_archive(&mut self) {
+        if let Some(archive) = &mut self.archive {
+            if let Some(file) = &mut archive.file {
+                let result = file
+                    .flush()
+                    .and_then(|()| file.get_mut().set_len(0))
+                    .and_then(|()| file.rewind());
+                if let Err(error) = result {
+                    archive.stop(&error);
+                }
+            }
+            archive.count = 0;
+        }
+    }
+}
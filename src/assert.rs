@@ -0,0 +1,9 @@
+//! Matching lines and reporting failures.
+//!
+//! The assertions themselves are methods on
+//! [`TestLogCollector`](crate::TestLogCollector), such as
+//! [`verify`](crate::TestLogCollector::verify) and
+//! [`assert_no_errors`](crate::TestLogCollector::assert_no_errors).
+
+pub use crate::matcher::Matcher;
+pub use crate::panic_dump::PanicDumpGuard;
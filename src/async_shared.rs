@@ -0,0 +1,325 @@
+use std::cell::UnsafeCell;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+use crate::TestLogCollector;
+
+/// The pending [`LockFuture`]s in the order they started waiting, one
+/// waker each.
+#[derive(Default)]
+struct Waiters {
+    next_id: u64,
+    queue: VecDeque<(u64, Waker)>,
+}
+
+impl Waiters {
+    /// Adds the waiter `id`, or updates its waker if it is still queued.
+    fn register(&mut self, id: &mut Option<u64>, waker: &Waker) {
+        if let Some(queued) = id.and_then(|id| self.queue.iter_mut().find(|(q, _)| *q == id)) {
+            if !queued.1.will_wake(waker) {
+                queued.1 = waker.clone();
+            }
+            return;
+        }
+        let new_id = self.next_id;
+        self.next_id += 1;
+        self.queue.push_back((new_id, waker.clone()));
+        *id = Some(new_id);
+    }
+
+    /// Removes the waiter `id`, returning `false` if it was already woken.
+    fn remove(&mut self, id: u64) -> bool {
+        match self.queue.iter().position(|(queued, _)| *queued == id) {
+            Some(pos) => {
+                self.queue.remove(pos);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Dequeues the longest-waiting waiter, to be woken once the lock on
+    /// the queue is released.
+    fn next(&mut self) -> Option<Waker> {
+        self.queue.pop_front().map(|(_, waker)| waker)
+    }
+}
+
+struct AsyncMutex {
+    locked: AtomicBool,
+    waiters: Mutex<Waiters>,
+    collector: UnsafeCell<TestLogCollector>,
+}
+
+// SAFETY: the collector, which is `Send` and `Sync`, is only reachable
+// through an `AsyncCollectorGuard`, and `locked` ensures at most one guard
+// exists at a time.
+unsafe impl Sync for AsyncMutex {}
+
+impl AsyncMutex {
+    fn try_acquire(&self) -> bool {
+        self.locked
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+    }
+
+    fn waiters(&self) -> std::sync::MutexGuard<'_, Waiters> {
+        self.waiters
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// A collector shared between async tasks, locked with `.await` instead of
+/// blocking the thread.
+///
+/// Created with [`TestLogCollector::new_shared_async`]. Unlike a
+/// [`SharedCollector`](crate::SharedCollector), its guard may be held across
+/// `.await` points without blocking the runtime's worker thread, and it is
+/// `Send`, so such tasks can be spawned. It works with any executor.
+/// Cloning the handle shares the collector.
+#[derive(Clone)]
+pub struct AsyncSharedCollector {
+    inner: Arc<AsyncMutex>,
+}
+
+impl AsyncSharedCollector {
+    /// Returns a future that resolves to a guard once the collector is
+    /// available.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    /// #     let mut future = std::pin::pin!(future);
+    /// #     let mut context = std::task::Context::from_waker(std::task::Waker::noop());
+    /// #     loop {
+    /// #         if let std::task::Poll::Ready(output) = future.as_mut().poll(&mut context) {
+    /// #             return output;
+    /// #         }
+    /// #     }
+    /// # }
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let collector = TestLogCollector::new_shared_async();
+    /// block_on(async {
+    ///     let mut logs = collector.lock().await;
+    ///     writeln!(logs, "written while holding the lock").unwrap();
+    /// });
+    ///
+    /// assert_eq!(block_on(collector.count()), 1);
+    /// ```
+    pub fn lock(&self) -> LockFuture<'_> {
+        LockFuture {
+            mutex: &self.inner,
+            waiter: None,
+        }
+    }
+
+    /// Locks the collector if it is available right away.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let collector = TestLogCollector::new_shared_async();
+    /// let guard = collector.try_lock().unwrap();
+    /// assert!(collector.try_lock().is_none());
+    /// drop(guard);
+    /// assert!(collector.try_lock().is_some());
+    /// ```
+    pub fn try_lock(&self) -> Option<AsyncCollectorGuard<'_>> {
+        self.inner
+            .try_acquire()
+            .then(|| AsyncCollectorGuard { mutex: &self.inner })
+    }
+
+    /// Returns the number of complete lines collected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    /// #     let mut future = std::pin::pin!(future);
+    /// #     let mut context = std::task::Context::from_waker(std::task::Waker::noop());
+    /// #     loop {
+    /// #         if let std::task::Poll::Ready(output) = future.as_mut().poll(&mut context) {
+    /// #             return output;
+    /// #         }
+    /// #     }
+    /// # }
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let collector = TestLogCollector::new_shared_async();
+    /// assert_eq!(block_on(collector.count()), 0);
+    /// ```
+    pub async fn count(&self) -> usize {
+        self.lock().await.count()
+    }
+
+    /// Returns `true` if any collected line contains `pattern`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    /// #     let mut future = std::pin::pin!(future);
+    /// #     let mut context = std::task::Context::from_waker(std::task::Waker::noop());
+    /// #     loop {
+    /// #         if let std::task::Poll::Ready(output) = future.as_mut().poll(&mut context) {
+    /// #             return output;
+    /// #         }
+    /// #     }
+    /// # }
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let collector = TestLogCollector::new_shared_async();
+    /// writeln!(collector.try_lock().unwrap(), "handshake complete").unwrap();
+    ///
+    /// assert!(block_on(collector.contains("handshake")));
+    /// ```
+    pub async fn contains(&self, pattern: &str) -> bool {
+        self.lock().await.contains(pattern)
+    }
+
+    /// Returns a clone of all collected lines.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    /// #     let mut future = std::pin::pin!(future);
+    /// #     let mut context = std::task::Context::from_waker(std::task::Waker::noop());
+    /// #     loop {
+    /// #         if let std::task::Poll::Ready(output) = future.as_mut().poll(&mut context) {
+    /// #             return output;
+    /// #         }
+    /// #     }
+    /// # }
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let collector = TestLogCollector::new_shared_async();
+    /// writeln!(collector.try_lock().unwrap(), "a").unwrap();
+    ///
+    /// assert_eq!(block_on(collector.clone_lines()), vec!["a"]);
+    /// ```
+    pub async fn clone_lines(&self) -> Vec<String> {
+        self.lock().await.clone_lines()
+    }
+}
+
+/// The future returned by [`AsyncSharedCollector::lock`].
+#[must_use = "futures do nothing unless awaited"]
+pub struct LockFuture<'a> {
+    mutex: &'a AsyncMutex,
+    /// This future's place in the queue of waiters, once it has waited.
+    waiter: Option<u64>,
+}
+
+impl<'a> LockFuture<'a> {
+    fn acquired(&mut self) -> Poll<AsyncCollectorGuard<'a>> {
+        if let Some(id) = self.waiter.take() {
+            self.mutex.waiters().remove(id);
+        }
+        Poll::Ready(AsyncCollectorGuard { mutex: self.mutex })
+    }
+}
+
+impl<'a> Future for LockFuture<'a> {
+    type Output = AsyncCollectorGuard<'a>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        if this.mutex.try_acquire() {
+            return this.acquired();
+        }
+        this.mutex.waiters().register(&mut this.waiter, cx.waker());
+        // The guard may have been dropped before the waker was registered.
+        if this.mutex.try_acquire() {
+            return this.acquired();
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for LockFuture<'_> {
+    fn drop(&mut self) {
+        if let Some(id) = self.waiter.take() {
+            let mut waiters = self.mutex.waiters();
+            // Woken but cancelled before locking: pass the wakeup on.
+            let next = if waiters.remove(id) {
+                None
+            } else {
+                waiters.next()
+            };
+            drop(waiters);
+            if let Some(waker) = next {
+                waker.wake();
+            }
+        }
+    }
+}
+
+/// Exclusive access to an [`AsyncSharedCollector`]; unlocks when dropped.
+#[must_use = "the collector is only locked while the guard is alive"]
+pub struct AsyncCollectorGuard<'a> {
+    mutex: &'a AsyncMutex,
+}
+
+impl Deref for AsyncCollectorGuard<'_> {
+    type Target = TestLogCollector;
+
+    fn deref(&self) -> &TestLogCollector {
+        // SAFETY: holding the guard means holding the lock.
+        unsafe { &*self.mutex.collector.get() }
+    }
+}
+
+impl DerefMut for AsyncCollectorGuard<'_> {
+    fn deref_mut(&mut self) -> &mut TestLogCollector {
+        // SAFETY: holding the guard means holding the lock, and `&mut self`
+        // makes this the only reference derived from it.
+        unsafe { &mut *self.mutex.collector.get() }
+    }
+}
+
+impl Drop for AsyncCollectorGuard<'_> {
+    fn drop(&mut self) {
+        self.mutex.locked.store(false, Ordering::Release);
+        let next = self.mutex.waiters().next();
+        if let Some(waker) = next {
+            waker.wake();
+        }
+    }
+}
+
+impl TestLogCollector {
+    /// Creates a new collector for async tests, locked with `.await`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let collector = TestLogCollector::new_shared_async();
+    /// assert_eq!(collector.try_lock().unwrap().count(), 0);
+    /// ```
+    pub fn new_shared_async() -> AsyncSharedCollector {
+        AsyncSharedCollector {
+            inner: Arc::new(AsyncMutex {
+                locked: AtomicBool::new(false),
+                waiters: Mutex::default(),
+                collector: UnsafeCell::new(TestLogCollector::new()),
+            }),
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use crate::{NewlinePolicy, TestLogCollector};
+
+/// Configures a [`TestLogCollector`] option by option.
+///
+/// Returned by [`TestLogCollector::builder`]. Each option corresponds to one
+/// of the collector's `with_*` methods.
+#[must_use = "the builder does nothing until `build` is called"]
+pub struct TestLogCollectorBuilder {
+    collector: TestLogCollector,
+}
+
+impl TestLogCollector {
+    /// Returns a builder for a collector with several options.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::{NewlinePolicy, TestLogCollector};
+    ///
+    /// let mut collector = TestLogCollector::builder()
+    ///     .capacity(1_000)
+    ///     .max_line_len(80)
+    ///     .newline_policy(NewlinePolicy::StripTrailingCr)
+    ///     .strip_ansi(true)
+    ///     .build();
+    /// write!(collector, "\x1b[32mready\x1b[0m\r\n").unwrap();
+    ///
+    /// assert_eq!(collector, ["ready"]);
+    /// ```
+    pub fn builder() -> TestLogCollectorBuilder {
+        TestLogCollectorBuilder {
+            collector: TestLogCollector::new(),
+        }
+    }
+}
+
+impl TestLogCollectorBuilder {
+    /// Preallocates room for `lines` lines.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let collector = TestLogCollector::builder().capacity(10_000).build();
+    /// assert_eq!(collector.count(), 0);
+    /// ```
+    pub fn capacity(mut self, lines: usize) -> Self {
+        self.collector.lines.reserve(lines);
+        self.collector.meta.reserve(lines);
+        self
+    }
+
+    /// Keeps only the most recent `max` lines. See
+    /// [`TestLogCollector::with_max_lines`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::builder().max_lines(1).build();
+    /// write!(collector, "old\nnew\n").unwrap();
+    ///
+    /// assert_eq!(collector, ["new"]);
+    /// ```
+    pub fn max_lines(mut self, max: usize) -> Self {
+        self.collector = self.collector.with_max_lines(max);
+        self
+    }
+
+    /// Truncates lines longer than `max` bytes. See
+    /// [`TestLogCollector::with_max_line_len`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::builder().max_line_len(3).build();
+    /// writeln!(collector, "abcdef").unwrap();
+    ///
+    /// assert_eq!(collector, ["abc…[truncated 3 bytes]"]);
+    /// ```
+    pub fn max_line_len(mut self, max: usize) -> Self {
+        self.collector = self.collector.with_max_line_len(max);
+        self
+    }
+
+    /// Sets how carriage returns are treated. See
+    /// [`TestLogCollector::with_newline_policy`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::{NewlinePolicy, TestLogCollector};
+    ///
+    /// let mut collector = TestLogCollector::builder()
+    ///     .newline_policy(NewlinePolicy::CrTerminates)
+    ///     .build();
+    /// write!(collector, "1/2\r2/2\n").unwrap();
+    ///
+    /// assert_eq!(collector, ["1/2", "2/2"]);
+    /// ```
+    pub fn newline_policy(mut self, policy: NewlinePolicy) -> Self {
+        self.collector = self.collector.with_newline_policy(policy);
+        self
+    }
+
+    /// Removes ANSI escape sequences from each line if `strip` is true. See
+    /// [`TestLogCollector::with_ansi_stripping`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::builder().strip_ansi(true).build();
+    /// writeln!(collector, "\x1b[33mWARN\x1b[0m").unwrap();
+    ///
+    /// assert_eq!(collector, ["WARN"]);
+    /// ```
+    pub fn strip_ansi(mut self, strip: bool) -> Self {
+        if strip {
+            self.collector = self.collector.with_ansi_stripping();
+        }
+        self
+    }
+
+    /// Adds a scrubber that rewrites each line before it is stored. See
+    /// [`TestLogCollector::with_scrubber`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::builder()
+    ///     .scrubber(|line| line.replace("secret", "<REDACTED>"))
+    ///     .build();
+    /// writeln!(collector, "token=secret").unwrap();
+    ///
+    /// assert_eq!(collector, ["token=<REDACTED>"]);
+    /// ```
+    pub fn scrubber<F>(mut self, scrubber: F) -> Self
+    where
+        F: Fn(&str) -> String + Send + Sync + 'static,
+    {
+        self.collector = self.collector.with_scrubber(scrubber);
+        self
+    }
+
+    /// Records when each line was completed if `enabled` is true, which is
+    /// the default. See [`TestLogCollector::without_timestamps`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use std::time::SystemTime;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::builder().timestamps(false).build();
+    /// writeln!(collector, "untimed").unwrap();
+    ///
+    /// assert_eq!(collector.lines_timestamped()[0].0, SystemTime::UNIX_EPOCH);
+    /// ```
+    pub fn timestamps(mut self, enabled: bool) -> Self {
+        self.collector.timestamps = enabled && crate::HAS_CLOCK;
+        self
+    }
+
+    /// Returns the configured collector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let collector = TestLogCollector::builder().build();
+    /// assert_eq!(collector.count(), 0);
+    /// ```
+    pub fn build(self) -> TestLogCollector {
+        self.collector
+    }
+}
@@ -0,0 +1,70 @@
+use crate::TestLogCollector;
+
+impl TestLogCollector {
+    /// Keeps the original bytes of each line alongside the decoded text, so
+    /// output that is not valid UTF-8 can be inspected with
+    /// [`raw_lines`](Self::raw_lines) instead of only its lossy decoding.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new().with_raw_bytes();
+    /// collector.write_all(b"binary \xff\xfe\n").unwrap();
+    ///
+    /// assert_eq!(collector, ["binary \u{fffd}\u{fffd}"]);
+    /// assert_eq!(collector.raw_lines(), [b"binary \xff\xfe"]);
+    /// ```
+    pub fn with_raw_bytes(mut self) -> Self {
+        self.raw_bytes = true;
+        self
+    }
+
+    /// Makes `write()` return an [`InvalidData`](std::io::ErrorKind::InvalidData)
+    /// error for bytes that are not valid UTF-8, collecting nothing from that
+    /// call, instead of replacing them with `U+FFFD`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{ErrorKind, Write};
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new().with_strict_utf8();
+    /// let error = collector.write(b"ok \xff\n").unwrap_err();
+    ///
+    /// assert_eq!(error.kind(), ErrorKind::InvalidData);
+    /// assert_eq!(collector.count(), 0);
+    /// ```
+    pub fn with_strict_utf8(mut self) -> Self {
+        self.strict_utf8 = true;
+        self
+    }
+
+    /// Returns the bytes of each collected line as written, before lossy
+    /// decoding and scrubbing.
+    ///
+    /// Lines collected without [`with_raw_bytes`](Self::with_raw_bytes), or
+    /// added without being written (e.g. through `Extend`), return the
+    /// bytes of their text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "text").unwrap();
+    /// assert_eq!(collector.raw_lines(), [b"text"]);
+    /// ```
+    pub fn raw_lines(&self) -> Vec<&[u8]> {
+        self.lines
+            .iter()
+            .zip(&self.meta)
+            .map(|(line, meta)| meta.raw.as_deref().unwrap_or(line.as_bytes()))
+            .collect()
+    }
+}
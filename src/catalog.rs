@@ -0,0 +1,174 @@
+use std::collections::BTreeMap;
+
+use crate::{Matcher, TestLogCollector};
+
+/// Maps canonical message ids to their text in each locale.
+///
+/// Translations may contain `{placeholder}` segments, which match any text.
+/// A line matches a message if it contains the translation for any locale.
+///
+/// # Examples
+///
+/// ```
+/// use test_log_collector::MessageCatalog;
+///
+/// let catalog = MessageCatalog::new()
+///     .with_message("connected", "en", "Connected to {host}")
+///     .with_message("connected", "de", "Verbunden mit {host}");
+///
+/// let matcher = catalog.matcher("connected");
+/// assert!(matcher.matches("INFO Verbunden mit db.local"));
+/// assert!(matcher.matches("INFO Connected to db.local"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MessageCatalog {
+    messages: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl MessageCatalog {
+    /// Creates an empty catalog.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the `locale` translation of message `id`.
+    pub fn with_message(
+        mut self,
+        id: impl Into<String>,
+        locale: impl Into<String>,
+        text: impl Into<String>,
+    ) -> Self {
+        self.messages
+            .entry(id.into())
+            .or_default()
+            .insert(locale.into(), text.into());
+        self
+    }
+
+    /// Returns the translation of message `id` for `locale`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::MessageCatalog;
+    ///
+    /// let catalog = MessageCatalog::new().with_message("bye", "fr", "Au revoir");
+    /// assert_eq!(catalog.translation("bye", "fr"), Some("Au revoir"));
+    /// assert_eq!(catalog.translation("bye", "en"), None);
+    /// ```
+    pub fn translation(&self, id: &str, locale: &str) -> Option<&str> {
+        self.messages.get(id)?.get(locale).map(String::as_str)
+    }
+
+    /// Returns a matcher for lines containing any translation of `id`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the catalog has no message `id`.
+    #[track_caller]
+    pub fn matcher(&self, id: &str) -> Matcher {
+        let translations: Vec<String> = self
+            .messages
+            .get(id)
+            .unwrap_or_else(|| panic!("message catalog has no message {:?}", id))
+            .values()
+            .cloned()
+            .collect();
+        Matcher::new(format!("is message {:?}", id), move |line| {
+            translations
+                .iter()
+                .any(|template| template_matches(template, line))
+        })
+    }
+}
+
+/// Returns `true` if `line` contains the literal parts of `template`, in
+/// order, with `{placeholder}` segments matching any text.
+fn template_matches(template: &str, line: &str) -> bool {
+    let mut literals = Vec::new();
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        match rest[open..].find('}') {
+            Some(close) => {
+                literals.push(&rest[..open]);
+                rest = &rest[open + close + 1..];
+            }
+            None => break,
+        }
+    }
+    literals.push(rest);
+
+    let mut search_from = 0;
+    for literal in literals.into_iter().filter(|literal| !literal.is_empty()) {
+        match line[search_from..].find(literal) {
+            Some(pos) => search_from += pos + literal.len(),
+            None => return false,
+        }
+    }
+    true
+}
+
+impl TestLogCollector {
+    /// Sets the catalog used by the message id queries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::{MessageCatalog, TestLogCollector};
+    ///
+    /// let catalog = MessageCatalog::new()
+    ///     .with_message("saved", "en", "File saved")
+    ///     .with_message("saved", "ja", "ファイルを保存しました");
+    /// let mut collector = TestLogCollector::new().with_catalog(catalog);
+    /// writeln!(collector, "ファイルを保存しました").unwrap();
+    ///
+    /// assert!(collector.contains_message("saved"));
+    /// ```
+    pub fn with_catalog(mut self, catalog: MessageCatalog) -> Self {
+        self.catalog = catalog;
+        self
+    }
+
+    /// Returns `true` if any line contains message `id` in any locale.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the catalog has no message `id`.
+    #[track_caller]
+    pub fn contains_message(&self, id: &str) -> bool {
+        let matcher = self.catalog.matcher(id);
+        self.lines.iter().any(|line| matcher.matches(line))
+    }
+
+    /// Returns the lines containing message `id` in any locale.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the catalog has no message `id`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::{MessageCatalog, TestLogCollector};
+    ///
+    /// let catalog = MessageCatalog::new()
+    ///     .with_message("retry", "en", "retrying in {secs}s")
+    ///     .with_message("retry", "es", "reintentando en {secs}s");
+    /// let mut collector = TestLogCollector::new().with_catalog(catalog);
+    /// writeln!(collector, "reintentando en 5s").unwrap();
+    /// writeln!(collector, "conectado").unwrap();
+    ///
+    /// assert_eq!(collector.message_lines("retry"), vec!["reintentando en 5s"]);
+    /// ```
+    #[track_caller]
+    pub fn message_lines(&self, id: &str) -> Vec<&str> {
+        let matcher = self.catalog.matcher(id);
+        self.lines
+            .iter()
+            .map(String::as_str)
+            .filter(|line| matcher.matches(line))
+            .collect()
+    }
+}
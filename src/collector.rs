@@ -0,0 +1,21 @@
+//! The collector itself and the types describing what it captured.
+
+pub use crate::archive::AllLines;
+pub use crate::async_shared::{AsyncCollectorGuard, AsyncSharedCollector, LockFuture};
+pub use crate::builder::TestLogCollectorBuilder;
+pub use crate::catalog::MessageCatalog;
+pub use crate::diff::{LineChange, LineDiff};
+pub use crate::error_report::ErrorReport;
+pub use crate::fields::Fields;
+pub use crate::hook::HookId;
+pub use crate::iter::Iter;
+pub use crate::level::{Level, MaxLevelGuard, ParseLevelError};
+pub use crate::lock::SharedCollectorExt;
+pub use crate::newline::NewlinePolicy;
+pub use crate::normalize::{Preset, Presets};
+pub use crate::reader::LogReader;
+pub use crate::sampling::Sampling;
+pub use crate::summary::Summary;
+pub use crate::syslog::SyslogRecord;
+pub use crate::tail::TailIter;
+pub use crate::{SharedCollector, TestLogCollector, ThreadTag};
@@ -0,0 +1,262 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::diff::{edit_script, Edit};
+use crate::TestLogCollector;
+
+impl TestLogCollector {
+    /// Panics unless the collected lines equal `expected`, showing a line
+    /// diff: `-` marks expected lines that are missing, `+` collected lines
+    /// that were not expected. The diff is colored unless the `NO_COLOR`
+    /// environment variable is set.
+    ///
+    /// `assert_eq!(collector, ["a", "b"])` also works, but prints both sides
+    /// in full instead of a diff.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "a").unwrap();
+    /// writeln!(collector, "b").unwrap();
+    ///
+    /// collector.assert_lines_eq(&["a", "b"]);
+    /// assert_eq!(collector, ["a", "b"]);
+    /// ```
+    #[track_caller]
+    pub fn assert_lines_eq(&self, expected: &[&str]) {
+        if *self != expected {
+            panic!(
+                "collected lines differ from expected (-expected +collected):\n{}",
+                line_diff(
+                    expected,
+                    &self.lines,
+                    std::env::var_os("NO_COLOR").is_none()
+                )
+            );
+        }
+    }
+
+    /// Returns how similar the collected lines are to `expected`, from
+    /// `0.0` to `1.0`: twice the number of lines in their longest common
+    /// subsequence divided by the total number of lines. Two empty logs are
+    /// identical.
+    ///
+    /// Very large logs are compared line by line by position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// write!(collector, "a\nb\nc\nd\n").unwrap();
+    ///
+    /// assert_eq!(collector.similarity(&["a", "b", "c", "d"]), 1.0);
+    /// assert_eq!(collector.similarity(&["a", "c", "b", "d"]), 0.75);
+    /// ```
+    pub fn similarity(&self, expected: &[&str]) -> f64 {
+        let total = expected.len() + self.lines.len();
+        if total == 0 {
+            return 1.0;
+        }
+        let common = match edit_script(expected, &self.lines) {
+            Some(script) => script
+                .iter()
+                .filter(|edit| matches!(edit, Edit::Keep(..)))
+                .count(),
+            None => expected
+                .iter()
+                .zip(&self.lines)
+                .filter(|(e, c)| *e == c)
+                .count(),
+        };
+        (2 * common) as f64 / total as f64
+    }
+
+    /// Panics unless the collected lines are at least `threshold` similar to
+    /// `expected`, as measured by [`similarity`](Self::similarity). Use it
+    /// when a few independent lines may legitimately vary in order but the
+    /// log as a whole should match a reference. The panic message shows the
+    /// similarity and a line diff.
+    ///
+    /// # Panics
+    ///
+    /// Also panics if `threshold` is not between `0.0` and `1.0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// write!(collector, "start\nworker 2 ready\nworker 1 ready\nserving\n").unwrap();
+    ///
+    /// collector.assert_similar(&["start", "worker 1 ready", "worker 2 ready", "serving"], 0.7);
+    /// ```
+    #[track_caller]
+    pub fn assert_similar(&self, expected: &[&str], threshold: f64) {
+        assert!(
+            (0.0..=1.0).contains(&threshold),
+            "similarity threshold {} is not between 0.0 and 1.0",
+            threshold
+        );
+        let similarity = self.similarity(expected);
+        if similarity < threshold {
+            panic!(
+                "collected lines are {:.2} similar to expected, below {:.2} (-expected +collected):\n{}",
+                similarity,
+                threshold,
+                line_diff(
+                    expected,
+                    &self.lines,
+                    std::env::var_os("NO_COLOR").is_none()
+                )
+            );
+        }
+    }
+
+    /// Panics unless the collected lines equal `expected` in any order,
+    /// counting repeated lines, e.g. for output from parallel workers whose
+    /// interleaving is nondeterministic. The panic message lists the
+    /// expected lines that are missing and the collected lines that are
+    /// extra.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// write!(collector, "worker 2 done\nworker 1 done\nworker 2 done\n").unwrap();
+    ///
+    /// collector.assert_same_lines_any_order(&["worker 1 done", "worker 2 done", "worker 2 done"]);
+    /// ```
+    #[track_caller]
+    pub fn assert_same_lines_any_order(&self, expected: &[&str]) {
+        let mut unmatched: HashMap<&str, usize> = HashMap::new();
+        for line in &self.lines {
+            *unmatched.entry(line).or_default() += 1;
+        }
+        let mut missing = Vec::new();
+        for &line in expected {
+            match unmatched.get_mut(line) {
+                Some(count) if *count > 0 => *count -= 1,
+                _ => missing.push(line),
+            }
+        }
+        let extra: Vec<&str> = self
+            .lines
+            .iter()
+            .filter(|line| match unmatched.get_mut(line.as_str()) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    true
+                }
+                _ => false,
+            })
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() || !extra.is_empty() {
+            let mut message = String::from("collected lines differ from expected in any order:\n");
+            for line in missing {
+                message.push_str(&format!("missing: {}\n", line));
+            }
+            for line in extra {
+                message.push_str(&format!("extra: {}\n", line));
+            }
+            panic!("{}", message);
+        }
+    }
+}
+
+fn line_diff(expected: &[&str], collected: &[String], color: bool) -> String {
+    let (red, green, reset) = if color {
+        ("\x1b[31m", "\x1b[32m", "\x1b[0m")
+    } else {
+        ("", "", "")
+    };
+    let mut out = String::new();
+    let Some(script) = edit_script(expected, collected) else {
+        let i = expected
+            .iter()
+            .zip(collected)
+            .position(|(e, c)| e != c)
+            .unwrap_or(expected.len().min(collected.len()));
+        out.push_str(&format!("  first difference at line {}:\n", i));
+        if let Some(line) = expected.get(i) {
+            out.push_str(&format!("{}- {}{}\n", red, line, reset));
+        }
+        if let Some(line) = collected.get(i) {
+            out.push_str(&format!("{}+ {}{}\n", green, line, reset));
+        }
+        return out;
+    };
+
+    for edit in script {
+        match edit {
+            Edit::Keep(i, _) => out.push_str(&format!("  {}\n", expected[i])),
+            Edit::Remove(i) => out.push_str(&format!("{}- {}{}\n", red, expected[i], reset)),
+            Edit::Add(j) => out.push_str(&format!("{}+ {}{}\n", green, collected[j], reset)),
+        }
+    }
+    out
+}
+
+impl PartialEq<[&str]> for TestLogCollector {
+    fn eq(&self, other: &[&str]) -> bool {
+        self.lines.len() == other.len() && self.lines.iter().zip(other).all(|(a, b)| a == b)
+    }
+}
+
+impl PartialEq<&[&str]> for TestLogCollector {
+    fn eq(&self, other: &&[&str]) -> bool {
+        *self == **other
+    }
+}
+
+impl<const N: usize> PartialEq<[&str; N]> for TestLogCollector {
+    fn eq(&self, other: &[&str; N]) -> bool {
+        *self == other[..]
+    }
+}
+
+impl PartialEq<Vec<&str>> for TestLogCollector {
+    fn eq(&self, other: &Vec<&str>) -> bool {
+        *self == other[..]
+    }
+}
+
+impl PartialEq<Vec<String>> for TestLogCollector {
+    fn eq(&self, other: &Vec<String>) -> bool {
+        self.lines == *other
+    }
+}
+
+/// Number of lines `Debug` shows before it shows only the most recent ones.
+const DEBUG_LINES: usize = 10;
+
+impl fmt::Debug for TestLogCollector {
+    /// Shows the collected lines and the partial line. Larger collectors
+    /// show their line count and only the last few lines, so a failing
+    /// `assert_eq!` does not print the entire buffer.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("TestLogCollector");
+        if self.lines.len() <= DEBUG_LINES {
+            debug.field("lines", &self.lines);
+        } else {
+            debug
+                .field("count", &self.lines.len())
+                .field("last_lines", &self.tail(DEBUG_LINES));
+        }
+        debug
+            .field("pending", &self.current_line)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,155 @@
+use std::io::{self, Write};
+use std::sync::atomic::Ordering;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use crate::{TestLogCollector, NEXT_SEQUENCE};
+
+/// Complete lines sent by one write, tagged with their sequence number.
+type Chunk = (u64, Vec<u8>);
+
+/// A collector that many threads feed without taking a lock.
+///
+/// Each [`ConcurrentWriter`] buffers its own partial line and sends complete
+/// lines, tagged with a process-wide sequence number, over a lock-free
+/// channel. [`consolidate`](Self::consolidate) moves the lines received so
+/// far into a [`TestLogCollector`] in sequence order. Unlike a
+/// [`SharedCollector`](crate::SharedCollector), writers never serialize on
+/// a mutex, so stress tests keep their timing.
+///
+/// Lines written concurrently with `consolidate` may be consolidated after
+/// lines with higher sequence numbers; consolidate once the writers are done
+/// for a total order.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Write;
+/// use std::thread;
+/// use test_log_collector::ConcurrentLogCollector;
+///
+/// let mut logs = ConcurrentLogCollector::new();
+/// let handles: Vec<_> = (0..4)
+///     .map(|worker| {
+///         let mut writer = logs.writer();
+///         thread::spawn(move || {
+///             for i in 0..100 {
+///                 writeln!(writer, "worker {} step {}", worker, i).unwrap();
+///             }
+///         })
+///     })
+///     .collect();
+/// for handle in handles {
+///     handle.join().unwrap();
+/// }
+///
+/// assert_eq!(logs.consolidate().count(), 400);
+/// ```
+pub struct ConcurrentLogCollector {
+    sender: Sender<Chunk>,
+    receiver: Receiver<Chunk>,
+    collector: TestLogCollector,
+}
+
+impl ConcurrentLogCollector {
+    /// Creates a concurrent collector consolidating into a new empty
+    /// collector.
+    pub fn new() -> Self {
+        Self::with_collector(TestLogCollector::new())
+    }
+
+    /// Creates a concurrent collector consolidating into `collector`, which
+    /// applies its configuration to the consolidated lines.
+    pub fn with_collector(collector: TestLogCollector) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            sender,
+            receiver,
+            collector,
+        }
+    }
+
+    /// Creates a writer for one producer thread.
+    pub fn writer(&self) -> ConcurrentWriter {
+        ConcurrentWriter {
+            sender: self.sender.clone(),
+            partial: Vec::new(),
+        }
+    }
+
+    /// Moves every line received so far into the collector, ordered by
+    /// sequence number, and returns the collector.
+    pub fn consolidate(&mut self) -> &mut TestLogCollector {
+        let mut chunks: Vec<Chunk> = self.receiver.try_iter().collect();
+        chunks.sort_unstable_by_key(|&(seq, _)| seq);
+        for (_, bytes) in chunks {
+            let _ = self.collector.write_all(&bytes);
+        }
+        &mut self.collector
+    }
+
+    /// Consolidates every line received so far and returns the collector.
+    ///
+    /// Writers still alive can no longer deliver lines afterwards; their
+    /// writes fail with `BrokenPipe`.
+    pub fn into_collector(mut self) -> TestLogCollector {
+        self.consolidate();
+        self.collector
+    }
+}
+
+impl Default for ConcurrentLogCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A producer feeding a [`ConcurrentLogCollector`].
+///
+/// Created with [`ConcurrentLogCollector::writer`]. Lines are sent once they
+/// are complete; flushing or dropping the writer completes its partial line.
+pub struct ConcurrentWriter {
+    sender: Sender<Chunk>,
+    partial: Vec<u8>,
+}
+
+impl ConcurrentWriter {
+    fn send(&mut self, bytes: Vec<u8>) -> io::Result<()> {
+        let seq = NEXT_SEQUENCE.fetch_add(1, Ordering::Relaxed);
+        self.sender.send((seq, bytes)).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "concurrent collector was dropped",
+            )
+        })
+    }
+}
+
+impl Write for ConcurrentWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match buf.iter().rposition(|&byte| byte == b'\n') {
+            Some(end) => {
+                let mut lines = std::mem::take(&mut self.partial);
+                lines.extend_from_slice(&buf[..=end]);
+                self.partial.extend_from_slice(&buf[end + 1..]);
+                self.send(lines)?;
+            }
+            None => self.partial.extend_from_slice(buf),
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.partial.is_empty() {
+            return Ok(());
+        }
+        let mut line = std::mem::take(&mut self.partial);
+        line.push(b'\n');
+        self.send(line)
+    }
+}
+
+impl Drop for ConcurrentWriter {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
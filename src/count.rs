@@ -0,0 +1,143 @@
+use std::fmt::Write as _;
+
+use crate::expect::lines_noun;
+use crate::search::context_ranges;
+use crate::{Matcher, TestLogCollector};
+
+/// Lines of context shown around each match in a failure message.
+const CONTEXT: usize = 2;
+
+impl TestLogCollector {
+    /// Asserts that exactly `expected` lines match `pattern`.
+    ///
+    /// # Panics
+    ///
+    /// Panics listing every matching line with its index and the two lines
+    /// around it, or the last lines collected if nothing matched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "WARN retrying").unwrap();
+    /// writeln!(collector, "INFO connected").unwrap();
+    /// writeln!(collector, "WARN retrying").unwrap();
+    ///
+    /// collector.assert_count("retrying", 2);
+    /// ```
+    #[track_caller]
+    pub fn assert_count(&self, pattern: impl Into<Matcher>, expected: usize) {
+        let matcher = pattern.into();
+        let matches = self.matching_indices(&matcher);
+        if matches.len() != expected {
+            self.count_failure("exactly", expected, &matcher, &matches);
+        }
+    }
+
+    /// Asserts that at least `min` lines match `pattern`.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the same report as [`assert_count`](Self::assert_count).
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "INFO connected").unwrap();
+    ///
+    /// collector.assert_count_at_least("heartbeat", 1);
+    /// ```
+    #[track_caller]
+    pub fn assert_count_at_least(&self, pattern: impl Into<Matcher>, min: usize) {
+        let matcher = pattern.into();
+        let matches = self.matching_indices(&matcher);
+        if matches.len() < min {
+            self.count_failure("at least", min, &matcher, &matches);
+        }
+    }
+
+    /// Asserts that at most `max` lines match `pattern`.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the same report as [`assert_count`](Self::assert_count).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "WARN retrying").unwrap();
+    ///
+    /// collector.assert_count_at_most("retrying", 3);
+    /// collector.assert_count_at_most("ERROR", 0);
+    /// ```
+    #[track_caller]
+    pub fn assert_count_at_most(&self, pattern: impl Into<Matcher>, max: usize) {
+        let matcher = pattern.into();
+        let matches = self.matching_indices(&matcher);
+        if matches.len() > max {
+            self.count_failure("at most", max, &matcher, &matches);
+        }
+    }
+
+    pub(crate) fn matching_indices(&self, matcher: &Matcher) -> Vec<usize> {
+        self.lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| matcher.matches(line))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Panics with the matching lines marked `>` among their context, with
+    /// `--` between separate groups as in `grep -C`.
+    #[track_caller]
+    fn count_failure(&self, bound: &str, expected: usize, matcher: &Matcher, matches: &[usize]) {
+        let mut report = format!(
+            "expected {} {} {} that {}, found {}",
+            bound,
+            expected,
+            lines_noun(expected),
+            matcher,
+            matches.len()
+        );
+        if matches.is_empty() {
+            let tail = self.lines.len().saturating_sub(2 * CONTEXT + 1);
+            if tail == self.lines.len() {
+                report.push_str("; no lines were collected");
+            } else {
+                report.push_str("; last lines collected:");
+                for index in tail..self.lines.len() {
+                    let _ = write!(report, "\n    [{}] {}", index, self.lines[index]);
+                }
+            }
+            panic!("{}", report);
+        }
+        report.push(':');
+        let ranges = context_ranges(matches, CONTEXT, CONTEXT, self.lines.len());
+        for (group, range) in ranges.into_iter().enumerate() {
+            if group > 0 {
+                report.push_str("\n  --");
+            }
+            for index in range {
+                let marker = if matches.binary_search(&index).is_ok() {
+                    '>'
+                } else {
+                    ' '
+                };
+                let _ = write!(report, "\n  {} [{}] {}", marker, index, self.lines[index]);
+            }
+        }
+        panic!("{}", report);
+    }
+}
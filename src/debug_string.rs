@@ -0,0 +1,275 @@
+use std::ffi::c_void;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+
+use crate::provenance::Label;
+use crate::{SharedCollector, TestLogCollector};
+
+type Handle = *mut c_void;
+
+#[link(name = "kernel32")]
+extern "system" {
+    fn CreateEventW(
+        attributes: *mut c_void,
+        manual_reset: i32,
+        initial: i32,
+        name: *const u16,
+    ) -> Handle;
+    fn CreateFileMappingW(
+        file: Handle,
+        attributes: *mut c_void,
+        protect: u32,
+        size_high: u32,
+        size_low: u32,
+        name: *const u16,
+    ) -> Handle;
+    fn MapViewOfFile(
+        mapping: Handle,
+        access: u32,
+        offset_high: u32,
+        offset_low: u32,
+        bytes: usize,
+    ) -> *mut c_void;
+    fn UnmapViewOfFile(address: *const c_void) -> i32;
+    fn WaitForSingleObject(handle: Handle, milliseconds: u32) -> u32;
+    fn SetEvent(handle: Handle) -> i32;
+    fn CloseHandle(handle: Handle) -> i32;
+    fn GetLastError() -> u32;
+}
+
+const INVALID_HANDLE_VALUE: Handle = -1isize as Handle;
+const PAGE_READWRITE: u32 = 0x04;
+const FILE_MAP_READ: u32 = 0x04;
+const WAIT_OBJECT_0: u32 = 0;
+const ERROR_ALREADY_EXISTS: u32 = 183;
+/// Size of the shared `DBWIN_BUFFER`: a process id followed by the message.
+const BUFFER_SIZE: usize = 4096;
+/// How often the listener checks whether the capture was dropped.
+const POLL_MS: u32 = 50;
+
+/// Only one listener may own the debug-string buffer at a time.
+static CAPTURE_LOCK: Mutex<()> = Mutex::new(());
+
+fn wide(name: &str) -> Vec<u16> {
+    name.encode_utf16().chain(Some(0)).collect()
+}
+
+/// Checks the result of creating a named object: only senders open these
+/// objects without creating them, so finding one means another listener,
+/// such as DebugView or a test in another process, owns the channel.
+///
+/// # Safety
+///
+/// Must be called right after the creating call, before anything else can
+/// overwrite the thread's last error.
+unsafe fn check_created(handle: Handle, name: &str) -> io::Result<Handle> {
+    if handle.is_null() {
+        return Err(io::Error::last_os_error());
+    }
+    if GetLastError() == ERROR_ALREADY_EXISTS {
+        CloseHandle(handle);
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} exists: another debug output listener is running", name),
+        ));
+    }
+    Ok(handle)
+}
+
+/// The kernel objects of the `DBWIN` protocol that debuggers such as
+/// DebugView use to receive `OutputDebugString` messages.
+struct Listener {
+    buffer_ready: Handle,
+    data_ready: Handle,
+    mapping: Handle,
+    view: *mut c_void,
+}
+
+// SAFETY: the handles and the read-only view are process-wide and usable
+// from any thread; only the listener thread touches them after creation.
+unsafe impl Send for Listener {}
+
+impl Listener {
+    fn new() -> io::Result<Self> {
+        let mut listener = Self {
+            buffer_ready: std::ptr::null_mut(),
+            data_ready: std::ptr::null_mut(),
+            mapping: std::ptr::null_mut(),
+            view: std::ptr::null_mut(),
+        };
+        // SAFETY: plain kernel32 calls with valid, NUL-terminated names;
+        // every failure is checked and `Drop` releases what was created.
+        unsafe {
+            listener.buffer_ready = check_created(
+                CreateEventW(
+                    std::ptr::null_mut(),
+                    0,
+                    0,
+                    wide("DBWIN_BUFFER_READY").as_ptr(),
+                ),
+                "DBWIN_BUFFER_READY",
+            )?;
+            listener.data_ready = check_created(
+                CreateEventW(
+                    std::ptr::null_mut(),
+                    0,
+                    0,
+                    wide("DBWIN_DATA_READY").as_ptr(),
+                ),
+                "DBWIN_DATA_READY",
+            )?;
+            listener.mapping = check_created(
+                CreateFileMappingW(
+                    INVALID_HANDLE_VALUE,
+                    std::ptr::null_mut(),
+                    PAGE_READWRITE,
+                    0,
+                    BUFFER_SIZE as u32,
+                    wide("DBWIN_BUFFER").as_ptr(),
+                ),
+                "DBWIN_BUFFER",
+            )?;
+            listener.view = MapViewOfFile(listener.mapping, FILE_MAP_READ, 0, 0, BUFFER_SIZE);
+            if listener.view.is_null() {
+                return Err(io::Error::last_os_error());
+            }
+        }
+        Ok(listener)
+    }
+
+    /// Hands each message to `collector` until `stop` is set.
+    fn run(self, stop: &AtomicBool, collector: &SharedCollector) {
+        let mut label: Option<(u32, Label)> = None;
+        while !stop.load(Ordering::Acquire) {
+            // SAFETY: both events are valid for the listener's lifetime.
+            let signaled = unsafe {
+                SetEvent(self.buffer_ready);
+                WaitForSingleObject(self.data_ready, POLL_MS) == WAIT_OBJECT_0
+            };
+            if !signaled {
+                continue;
+            }
+            // SAFETY: the view maps `BUFFER_SIZE` readable bytes, and the
+            // writer does not touch them until `buffer_ready` is set again.
+            let buffer = unsafe { std::slice::from_raw_parts(self.view as *const u8, BUFFER_SIZE) };
+            let pid = u32::from_ne_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]);
+            let text = &buffer[4..];
+            let text = &text[..text
+                .iter()
+                .position(|&byte| byte == 0)
+                .unwrap_or(text.len())];
+            let text = text.strip_suffix(b"\n").unwrap_or(text);
+            let text = text.strip_suffix(b"\r").unwrap_or(text);
+
+            // Messages tend to come in runs from one process, so its label
+            // is only rebuilt when the sender changes.
+            if label.as_ref().map(|(cached, _)| *cached) != Some(pid) {
+                label = Some((
+                    pid,
+                    Label::from(format!("via OutputDebugString pid {}", pid)),
+                ));
+            }
+            let Some((_, source)) = &label else {
+                unreachable!("the label was set above")
+            };
+            let mut collector = collector
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            let _ = collector.write_all_via(source, text);
+            let _ = collector.write_all_via(source, b"\n");
+        }
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        // SAFETY: each object is released once, and only if it was created.
+        unsafe {
+            if !self.view.is_null() {
+                UnmapViewOfFile(self.view);
+            }
+            for handle in [self.mapping, self.data_ready, self.buffer_ready] {
+                if !handle.is_null() {
+                    CloseHandle(handle);
+                }
+            }
+        }
+    }
+}
+
+/// Routes `OutputDebugString` messages into a collector while alive.
+///
+/// Returned by [`TestLogCollector::capture_debug_output`]. Dropping the
+/// guard stops listening and releases the debug-string buffer.
+#[must_use = "debug output is only captured while the guard is alive"]
+pub struct DebugOutputCapture {
+    stop: Arc<AtomicBool>,
+    listener: Option<JoinHandle<()>>,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl Drop for DebugOutputCapture {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Release);
+        if let Some(listener) = self.listener.take() {
+            let _ = listener.join();
+        }
+    }
+}
+
+impl TestLogCollector {
+    /// Collects the messages any process passes to `OutputDebugString` into
+    /// `collector` until the returned guard is dropped, for components that
+    /// log through the Windows debug channel rather than stdio.
+    ///
+    /// Each message becomes a line, without its trailing newline, with
+    /// `OutputDebugString pid N` in its [`provenance`](Self::provenance).
+    /// Messages from a process running under a debugger go to the debugger
+    /// instead. Only one listener can own the debug-string buffer in a
+    /// session, so the capture fails while a tool like DebugView is
+    /// listening, and a second capture in this process blocks until the
+    /// first guard is dropped.
+    ///
+    /// Only available on Windows.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the debug-string buffer or its events cannot be
+    /// created, with [`ErrorKind::AlreadyExists`](io::ErrorKind::AlreadyExists)
+    /// if another listener owns them.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let collector = TestLogCollector::new_shared();
+    /// {
+    ///     let _capture = TestLogCollector::capture_debug_output(&collector).unwrap();
+    ///     // ... exercise a component that calls OutputDebugStringA ...
+    /// }
+    ///
+    /// assert!(collector.lock().unwrap().contains("component started"));
+    /// ```
+    pub fn capture_debug_output(collector: &SharedCollector) -> io::Result<DebugOutputCapture> {
+        let lock = CAPTURE_LOCK
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let listener = Listener::new()?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let collector = collector.clone();
+        let listener = {
+            let stop = stop.clone();
+            thread::Builder::new()
+                .name("test_log_collector OutputDebugString".into())
+                .spawn(move || listener.run(&stop, &collector))?
+        };
+        Ok(DebugOutputCapture {
+            stop,
+            listener: Some(listener),
+            _lock: lock,
+        })
+    }
+}
@@ -0,0 +1,69 @@
+use crate::TestLogCollector;
+
+/// The default delimiter set, holding only `\n`.
+pub(crate) const NEWLINE: u128 = 1 << b'\n';
+
+/// Returns the set holding only the ASCII byte `delimiter`.
+pub(crate) fn mask(delimiter: u8) -> u128 {
+    1 << delimiter
+}
+
+pub(crate) fn contains(set: u128, byte: u8) -> bool {
+    byte < 128 && set & mask(byte) != 0
+}
+
+impl TestLogCollector {
+    /// Ends lines at `delimiter` instead of `\n`, e.g. `b'\0'` for
+    /// `find -print0`-style output. `\n` then becomes part of the line.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `delimiter` is not ASCII, as it could then be part of a
+    /// multi-byte character.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new().with_delimiter(b'\0');
+    /// collector.write_all(b"file one.txt\0multi\nline.txt\0").unwrap();
+    ///
+    /// assert_eq!(collector, ["file one.txt", "multi\nline.txt"]);
+    /// ```
+    pub fn with_delimiter(self, delimiter: u8) -> Self {
+        self.with_delimiters(&[delimiter])
+    }
+
+    /// Ends lines at any of the `delimiters` instead of `\n`, e.g. the ASCII
+    /// record and unit separators `0x1e` and `0x1f`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `delimiters` is empty or holds a byte that is not ASCII.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new().with_delimiters(&[0x1e, b'\n']);
+    /// collector.write_all(b"first\x1esecond\nthird\x1e").unwrap();
+    ///
+    /// assert_eq!(collector, ["first", "second", "third"]);
+    /// ```
+    pub fn with_delimiters(mut self, delimiters: &[u8]) -> Self {
+        assert!(!delimiters.is_empty(), "at least one delimiter is required");
+        self.delimiters = delimiters.iter().fold(0, |set, &delimiter| {
+            assert!(
+                delimiter.is_ascii(),
+                "delimiter {:#04x} is not ASCII",
+                delimiter
+            );
+            set | mask(delimiter)
+        });
+        self
+    }
+}
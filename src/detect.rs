@@ -0,0 +1,142 @@
+use crate::{Level, SyslogRecord, TestLogCollector};
+
+/// Keys whose value names the level, as in `lvl=debug` or `level="warn"`.
+const LEVEL_KEYS: [&str; 4] = ["level", "lvl", "severity", "loglevel"];
+
+/// Parses a level name as loggers spell it, ignoring case, including common
+/// abbreviations and the syslog-style `fatal` and `critical`.
+fn loose_level(name: &str) -> Option<Level> {
+    match name.to_ascii_lowercase().as_str() {
+        "error" | "err" | "fatal" | "crit" | "critical" => Some(Level::Error),
+        "warn" | "warning" => Some(Level::Warn),
+        "info" | "notice" => Some(Level::Info),
+        "debug" | "dbg" => Some(Level::Debug),
+        "trace" => Some(Level::Trace),
+        _ => None,
+    }
+}
+
+impl Level {
+    /// Detects the level of a free-form line more leniently than
+    /// [`detect`](Self::detect): when there is no upper-case token such as
+    /// `WARN`, it uses the severity of a syslog `<PRI>` header, and
+    /// recognizes level names in any case inside brackets (`[info]`,
+    /// `<warning>`) and as the value of a `level=`, `lvl=`, `severity=` or
+    /// `loglevel=` field.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::Level;
+    ///
+    /// assert_eq!(Level::detect_heuristic("[info] listening"), Some(Level::Info));
+    /// assert_eq!(Level::detect_heuristic("ts=1 lvl=debug msg=hi"), Some(Level::Debug));
+    /// assert_eq!(Level::detect_heuristic("level=\"err\" code=5"), Some(Level::Error));
+    /// assert_eq!(Level::detect_heuristic("an error occurred"), None);
+    /// ```
+    pub fn detect_heuristic(line: &str) -> Option<Level> {
+        Level::detect(line)
+            .or_else(|| SyslogRecord::parse(line).map(|record| record.level()))
+            .or_else(|| bracketed_level(line))
+            .or_else(|| field_level(line))
+    }
+}
+
+/// Finds a level name enclosed in `[]`, `<>` or `()`.
+fn bracketed_level(line: &str) -> Option<Level> {
+    let mut rest = line;
+    while let Some(open) = rest.find(['[', '<', '(']) {
+        let close = match rest.as_bytes()[open] {
+            b'[' => ']',
+            b'<' => '>',
+            _ => ')',
+        };
+        rest = &rest[open + 1..];
+        if let Some(end) = rest.find(close) {
+            if let Some(level) = loose_level(rest[..end].trim()) {
+                return Some(level);
+            }
+        }
+    }
+    None
+}
+
+/// Finds a level name as the value of a `level=`-style field.
+fn field_level(line: &str) -> Option<Level> {
+    for field in line.split_whitespace() {
+        if let Some((key, value)) = field.split_once(['=', ':']) {
+            if LEVEL_KEYS.iter().any(|k| key.eq_ignore_ascii_case(k)) {
+                let value = value.trim_matches(|ch: char| !ch.is_ascii_alphabetic());
+                if let Some(level) = loose_level(value) {
+                    return Some(level);
+                }
+            }
+        }
+    }
+    None
+}
+
+impl TestLogCollector {
+    /// Switches level detection to [`Level::detect_heuristic`], which also
+    /// recognizes `[info]`, `lvl=debug` and similar spellings, for lines
+    /// captured through the plain `Write` path from loggers that don't use
+    /// upper-case level tokens. Lines already collected are classified
+    /// again; reclassification rules still take precedence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// write!(collector, "[warn] retrying\nlvl=error msg=gave up\n").unwrap();
+    /// assert!(collector.errors().is_empty());
+    ///
+    /// collector.detect_levels();
+    /// assert_eq!(collector.errors(), ["lvl=error msg=gave up"]);
+    /// assert_eq!(collector.warnings(), ["[warn] retrying"]);
+    /// ```
+    pub fn detect_levels(&mut self) {
+        self.heuristic_levels = true;
+        let levels: Vec<_> = self.lines.iter().map(|line| self.classify(line)).collect();
+        for (meta, (level, original_level)) in self.meta.iter_mut().zip(levels) {
+            meta.level = level;
+            meta.original_level = original_level;
+        }
+    }
+
+    /// Returns the lines at [`Level::Error`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// write!(collector, "INFO ok\nERROR boom\n").unwrap();
+    ///
+    /// assert_eq!(collector.errors(), ["ERROR boom"]);
+    /// ```
+    pub fn errors(&self) -> Vec<&str> {
+        self.lines_at_level(Level::Error)
+    }
+
+    /// Returns the lines at [`Level::Warn`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// write!(collector, "WARNING slow\nINFO ok\n").unwrap();
+    ///
+    /// assert_eq!(collector.warnings(), ["WARNING slow"]);
+    /// ```
+    pub fn warnings(&self) -> Vec<&str> {
+        self.lines_at_level(Level::Warn)
+    }
+}
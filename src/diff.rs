@@ -0,0 +1,279 @@
+use std::fmt;
+
+use crate::TestLogCollector;
+
+/// Largest `old × new` table the line diff computes; beyond it lines are
+/// compared by position instead.
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// One step of an edit script turning `old` into `new`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Edit {
+    Keep(usize, usize),
+    Remove(usize),
+    Add(usize),
+}
+
+/// Returns a shortest edit script from `old` to `new`, preferring removals
+/// before additions, or `None` if the inputs exceed [`MAX_DIFF_CELLS`].
+pub(crate) fn edit_script<A, B>(old: &[A], new: &[B]) -> Option<Vec<Edit>>
+where
+    A: AsRef<str>,
+    B: AsRef<str>,
+{
+    let (n, m) = (old.len(), new.len());
+    if (n + 1).saturating_mul(m + 1) > MAX_DIFF_CELLS {
+        return None;
+    }
+    // lcs[i][j] is the length of the longest common subsequence of
+    // old[i..] and new[j..].
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i].as_ref() == new[j].as_ref() {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    let mut script = Vec::with_capacity(n.max(m));
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && old[i].as_ref() == new[j].as_ref() {
+            script.push(Edit::Keep(i, j));
+            i += 1;
+            j += 1;
+        } else if j == m || (i < n && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            script.push(Edit::Remove(i));
+            i += 1;
+        } else {
+            script.push(Edit::Add(j));
+            j += 1;
+        }
+    }
+    Some(script)
+}
+
+/// A line that differs between two collectors, as reported by
+/// [`LineDiff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LineChange {
+    /// A line only the other collector has.
+    Added(String),
+    /// A line only this collector has.
+    Removed(String),
+    /// A line of this collector replaced by one of the other collector.
+    Changed {
+        /// The line in this collector.
+        from: String,
+        /// The line in the other collector.
+        to: String,
+    },
+}
+
+/// The differences between two collectors, returned by
+/// [`TestLogCollector::diff`].
+///
+/// Changes are in log order. A run of removed lines directly followed by
+/// added lines is paired up into [`LineChange::Changed`] entries. The
+/// `Display` rendering shows `-` for this collector's lines and `+` for the
+/// other's.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LineDiff {
+    changes: Vec<LineChange>,
+}
+
+impl LineDiff {
+    /// Returns `true` if both collectors have the same lines.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Returns every change in log order.
+    pub fn changes(&self) -> &[LineChange] {
+        &self.changes
+    }
+
+    /// Returns the lines only the other collector has.
+    pub fn added(&self) -> Vec<&str> {
+        self.changes
+            .iter()
+            .filter_map(|change| match change {
+                LineChange::Added(line) => Some(line.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the lines only this collector has.
+    pub fn removed(&self) -> Vec<&str> {
+        self.changes
+            .iter()
+            .filter_map(|change| match change {
+                LineChange::Removed(line) => Some(line.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the pairs of lines that were replaced, as `(from, to)`.
+    pub fn changed(&self) -> Vec<(&str, &str)> {
+        self.changes
+            .iter()
+            .filter_map(|change| match change {
+                LineChange::Changed { from, to } => Some((from.as_str(), to.as_str())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn push_run(&mut self, removed: &mut Vec<String>, added: &mut Vec<String>) {
+        let paired = removed.len().min(added.len());
+        let mut removed = removed.drain(..);
+        let mut added = added.drain(..);
+        for _ in 0..paired {
+            if let (Some(from), Some(to)) = (removed.next(), added.next()) {
+                self.changes.push(LineChange::Changed { from, to });
+            }
+        }
+        self.changes.extend(removed.map(LineChange::Removed));
+        self.changes.extend(added.map(LineChange::Added));
+    }
+}
+
+impl fmt::Display for LineDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            match change {
+                LineChange::Added(line) => writeln!(f, "+ {}", line)?,
+                LineChange::Removed(line) => writeln!(f, "- {}", line)?,
+                LineChange::Changed { from, to } => {
+                    writeln!(f, "- {}", from)?;
+                    writeln!(f, "+ {}", to)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TestLogCollector {
+    /// Adds the lines of `other`, interleaved with this collector's lines in
+    /// the order they were completed, e.g. to read the logs of a client and
+    /// a server as one timeline. Levels, timestamps and other per-line
+    /// details are kept.
+    ///
+    /// Lines archived to disk on either side are not merged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut client = TestLogCollector::new();
+    /// let mut server = TestLogCollector::new();
+    /// writeln!(client, "client: connect").unwrap();
+    /// writeln!(server, "server: accept").unwrap();
+    /// writeln!(client, "client: send").unwrap();
+    ///
+    /// client.merge(&server);
+    /// assert_eq!(client, ["client: connect", "server: accept", "client: send"]);
+    /// ```
+    pub fn merge(&mut self, other: &TestLogCollector) {
+        let lines = std::mem::take(&mut self.lines);
+        let meta = std::mem::take(&mut self.meta);
+        self.lines.reserve(lines.len() + other.lines.len());
+        self.meta.reserve(meta.len() + other.meta.len());
+        let mut ours = lines.into_iter().zip(meta).peekable();
+        let mut theirs = other
+            .lines
+            .iter()
+            .cloned()
+            .zip(other.meta.iter().cloned())
+            .peekable();
+        loop {
+            let take_ours = match (ours.peek(), theirs.peek()) {
+                (Some((_, a)), Some((_, b))) => a.seq <= b.seq,
+                (Some(_), None) => true,
+                (None, Some(_)) => false,
+                (None, None) => break,
+            };
+            let next = if take_ours {
+                ours.next()
+            } else {
+                theirs.next()
+            };
+            if let Some((line, meta)) = next {
+                self.lines.push(line);
+                self.meta.push_back(meta);
+            }
+        }
+        if let Some(max) = self.max_lines {
+            let excess = self.lines.len().saturating_sub(max);
+            self.lines.drain(..excess);
+            self.meta.drain(..excess);
+        }
+        self.reindex();
+    }
+
+    /// Compares the lines with those of `other`, e.g. to check that two
+    /// implementations of a protocol log the same thing.
+    ///
+    /// Lines only this collector has are removed, lines only `other` has
+    /// are added. Very large logs are compared line by line by position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut reference = TestLogCollector::new();
+    /// let mut candidate = TestLogCollector::new();
+    /// write!(reference, "HELLO\nAUTH plain\nBYE\n").unwrap();
+    /// write!(candidate, "HELLO\nAUTH login\nBYE\nQUIT\n").unwrap();
+    ///
+    /// let diff = reference.diff(&candidate);
+    /// assert_eq!(diff.changed(), [("AUTH plain", "AUTH login")]);
+    /// assert_eq!(diff.added(), ["QUIT"]);
+    /// assert!(diff.removed().is_empty());
+    /// assert_eq!(diff.to_string(), "- AUTH plain\n+ AUTH login\n+ QUIT\n");
+    /// ```
+    pub fn diff(&self, other: &TestLogCollector) -> LineDiff {
+        let (old, new) = (&self.lines, &other.lines);
+        let script = edit_script(old, new).unwrap_or_else(|| {
+            let common = old.len().min(new.len());
+            let mut script: Vec<Edit> = (0..common)
+                .flat_map(|i| {
+                    if old[i] == new[i] {
+                        vec![Edit::Keep(i, i)]
+                    } else {
+                        vec![Edit::Remove(i), Edit::Add(i)]
+                    }
+                })
+                .collect();
+            script.extend((common..old.len()).map(Edit::Remove));
+            script.extend((common..new.len()).map(Edit::Add));
+            script
+        });
+
+        let mut diff = LineDiff::default();
+        let (mut removed, mut added) = (Vec::new(), Vec::new());
+        for edit in script {
+            match edit {
+                Edit::Keep(..) => diff.push_run(&mut removed, &mut added),
+                Edit::Remove(i) => {
+                    if !added.is_empty() {
+                        diff.push_run(&mut removed, &mut added);
+                    }
+                    removed.push(old[i].clone());
+                }
+                Edit::Add(j) => added.push(new[j].clone()),
+            }
+        }
+        diff.push_run(&mut removed, &mut added);
+        diff
+    }
+}
@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+
+use crate::TestLogCollector;
+
+impl TestLogCollector {
+    /// Collapses consecutive identical lines into one entry with a repeat
+    /// count, so retry loops don't drown the useful output.
+    ///
+    /// Queries see each run once; [`repeat_count`](Self::repeat_count) and
+    /// [`expanded_lines`](Self::expanded_lines) recover the repetitions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new().with_collapsed_duplicates();
+    /// write!(collector, "retrying\nretrying\nretrying\nconnected\n").unwrap();
+    ///
+    /// assert_eq!(collector, ["retrying", "connected"]);
+    /// assert_eq!(collector.repeat_count(0), 3);
+    /// ```
+    pub fn with_collapsed_duplicates(mut self) -> Self {
+        self.collapse_duplicates = true;
+        self
+    }
+
+    /// Returns how many consecutive times the line at `index` was collected:
+    /// 1 unless [`with_collapsed_duplicates`](Self::with_collapsed_duplicates)
+    /// folded repetitions into it, and 0 if `index` is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// write!(collector, "same\nsame\n").unwrap();
+    ///
+    /// assert_eq!(collector.repeat_count(0), 1);
+    /// assert_eq!(collector.repeat_count(2), 0);
+    /// ```
+    pub fn repeat_count(&self, index: usize) -> usize {
+        self.meta.get(index).map_or(0, |meta| meta.repeats)
+    }
+
+    /// Returns the lines with collapsed repetitions expanded again, as they
+    /// were written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new().with_collapsed_duplicates();
+    /// write!(collector, "tick\ntick\ndone\n").unwrap();
+    ///
+    /// assert_eq!(collector.expanded_lines(), ["tick", "tick", "done"]);
+    /// ```
+    pub fn expanded_lines(&self) -> Vec<&str> {
+        self.lines
+            .iter()
+            .zip(&self.meta)
+            .flat_map(|(line, meta)| std::iter::repeat_n(line.as_str(), meta.repeats))
+            .collect()
+    }
+
+    /// Returns the lines collected more than `min` times in total, whether
+    /// consecutively or not, with their counts, in order of first appearance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// write!(collector, "poll\nbusy\npoll\npoll\nbusy\nidle\n").unwrap();
+    ///
+    /// assert_eq!(collector.duplicates(1), [("poll", 3), ("busy", 2)]);
+    /// assert_eq!(collector.duplicates(2), [("poll", 3)]);
+    /// ```
+    pub fn duplicates(&self, min: usize) -> Vec<(&str, usize)> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        let mut order = Vec::new();
+        for (line, meta) in self.lines.iter().zip(&self.meta) {
+            let count = counts.entry(line).or_insert_with(|| {
+                order.push(line.as_str());
+                0
+            });
+            *count += meta.repeats;
+        }
+        order
+            .into_iter()
+            .map(|line| (line, counts[line]))
+            .filter(|&(_, count)| count > min)
+            .collect()
+    }
+}
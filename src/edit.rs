@@ -0,0 +1,64 @@
+use crate::TestLogCollector;
+
+impl TestLogCollector {
+    /// Keeps only the lines for which `keep` returns true, e.g. to prune
+    /// health-check noise before a batch of assertions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// write!(collector, "GET /health\nGET /users\nGET /health\n").unwrap();
+    /// collector.retain(|line| !line.ends_with("/health"));
+    ///
+    /// assert_eq!(collector, ["GET /users"]);
+    /// ```
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&str) -> bool,
+    {
+        let lines = std::mem::take(&mut self.lines);
+        let meta = std::mem::take(&mut self.meta);
+        for (line, meta) in lines.into_iter().zip(meta) {
+            if keep(&line) {
+                self.lines.push(line);
+                self.meta.push_back(meta);
+            }
+        }
+        self.reindex();
+    }
+
+    /// Replaces each collected line with what `map` returns for it, e.g. to
+    /// normalize content once instead of in every assertion. Levels are
+    /// detected again from the new lines.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// write!(collector, "took 12ms\ntook 7ms\n").unwrap();
+    /// collector.map_in_place(|line| line.trim_end_matches(char::is_alphanumeric).to_string());
+    ///
+    /// assert_eq!(collector, ["took ", "took "]);
+    /// ```
+    pub fn map_in_place<F>(&mut self, mut map: F)
+    where
+        F: FnMut(&str) -> String,
+    {
+        for index in 0..self.lines.len() {
+            let line = map(&self.lines[index]);
+            let (level, original_level) = self.classify(&line);
+            let meta = &mut self.meta[index];
+            meta.level = level;
+            meta.original_level = original_level;
+            self.lines[index] = line;
+        }
+        self.reindex();
+    }
+}
@@ -0,0 +1,113 @@
+use std::error::Error;
+
+use crate::TestLogCollector;
+
+/// An error chain recorded with [`TestLogCollector::record_error`].
+///
+/// Each cause in the chain is stored as its `Display` rendering, starting
+/// with the outermost error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorReport {
+    causes: Vec<String>,
+}
+
+impl ErrorReport {
+    fn from_error(error: &(dyn Error + 'static)) -> Self {
+        let mut causes = Vec::new();
+        let mut current = Some(error);
+        while let Some(error) = current {
+            causes.push(error.to_string());
+            current = error.source();
+        }
+        Self { causes }
+    }
+
+    /// Returns the message of the outermost error.
+    pub fn message(&self) -> &str {
+        &self.causes[0]
+    }
+
+    /// Returns every message in the chain, outermost first.
+    pub fn causes(&self) -> &[String] {
+        &self.causes
+    }
+
+    /// Returns the message of the innermost error.
+    pub fn root_cause(&self) -> &str {
+        &self.causes[self.causes.len() - 1]
+    }
+}
+
+impl TestLogCollector {
+    /// Records an error and its full `source()` chain as an [`ErrorReport`].
+    ///
+    /// `anyhow::Error` and `eyre::Report` can be passed with `.as_ref()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// let error = io::Error::other("connection refused");
+    /// collector.record_error(&error);
+    ///
+    /// assert_eq!(collector.error_reports()[0].message(), "connection refused");
+    /// ```
+    pub fn record_error(&mut self, error: &(dyn Error + 'static)) {
+        self.errors.push(ErrorReport::from_error(error));
+    }
+
+    /// Returns the error chains recorded with [`record_error`](Self::record_error).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let collector = TestLogCollector::new();
+    /// assert!(collector.error_reports().is_empty());
+    /// ```
+    pub fn error_reports(&self) -> &[ErrorReport] {
+        &self.errors
+    }
+
+    /// Asserts that some cause of some recorded error contains `needle`.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the recorded chains if no cause contains `needle`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// let error = io::Error::other("connection refused");
+    /// collector.record_error(&error);
+    ///
+    /// collector.assert_error_chain_contains("refused");
+    /// ```
+    #[track_caller]
+    pub fn assert_error_chain_contains(&self, needle: &str) {
+        let found = self
+            .errors
+            .iter()
+            .flat_map(|report| report.causes())
+            .any(|cause| cause.contains(needle));
+        if !found {
+            let chains: Vec<String> = self
+                .errors
+                .iter()
+                .map(|report| report.causes().join(": "))
+                .collect();
+            panic!(
+                "no recorded error chain contains {:?}; recorded chains: {:#?}",
+                needle, chains
+            );
+        }
+    }
+}
@@ -0,0 +1,294 @@
+use crate::{Matcher, TestLogCollector};
+
+/// An expectation evaluated incrementally as each line is completed.
+#[derive(Clone)]
+pub(crate) enum Expectation {
+    Count {
+        matcher: Matcher,
+        bound: Bound,
+        seen: usize,
+    },
+    Sequence {
+        matchers: Vec<Matcher>,
+        progress: usize,
+    },
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum Bound {
+    Exactly(usize),
+    AtLeast(usize),
+    AtMost(usize),
+}
+
+impl Bound {
+    fn count(self) -> usize {
+        match self {
+            Bound::Exactly(n) | Bound::AtLeast(n) | Bound::AtMost(n) => n,
+        }
+    }
+}
+
+/// Returns "line" or "lines" to follow a count of `n`.
+pub(crate) fn lines_noun(n: usize) -> &'static str {
+    if n == 1 {
+        "line"
+    } else {
+        "lines"
+    }
+}
+
+impl Expectation {
+    pub(crate) fn observe(&mut self, line: &str) {
+        match self {
+            Expectation::Count { matcher, seen, .. } => {
+                if matcher.matches(line) {
+                    *seen += 1;
+                }
+            }
+            Expectation::Sequence { matchers, progress } => {
+                if matchers
+                    .get(*progress)
+                    .is_some_and(|matcher| matcher.matches(line))
+                {
+                    *progress += 1;
+                }
+            }
+        }
+    }
+
+    pub(crate) fn reset(&mut self) {
+        match self {
+            Expectation::Count { seen, .. } => *seen = 0,
+            Expectation::Sequence { progress, .. } => *progress = 0,
+        }
+    }
+
+    /// Returns a description of the failure, or `None` if the expectation
+    /// is met.
+    fn failure(&self) -> Option<String> {
+        match self {
+            Expectation::Count {
+                matcher,
+                bound,
+                seen,
+            } => {
+                let (met, wanted) = match *bound {
+                    Bound::Exactly(n) => (*seen == n, format!("exactly {}", n)),
+                    Bound::AtLeast(n) => (*seen >= n, format!("at least {}", n)),
+                    Bound::AtMost(n) => (*seen <= n, format!("at most {}", n)),
+                };
+                (!met).then(|| {
+                    format!(
+                        "expected {} {} that {}, saw {}",
+                        wanted,
+                        lines_noun(bound.count()),
+                        matcher,
+                        seen
+                    )
+                })
+            }
+            Expectation::Sequence { matchers, progress } => {
+                matchers.get(*progress).map(|missing| {
+                    format!(
+                        "sequence stalled at step {} of {}, waiting for a line that {}",
+                        progress + 1,
+                        matchers.len(),
+                        missing
+                    )
+                })
+            }
+        }
+    }
+}
+
+impl TestLogCollector {
+    /// Expects exactly `expected` lines to match `matcher`.
+    ///
+    /// Expectations are evaluated as each line is completed, so
+    /// [`verify`](Self::verify) does not rescan the capture. Lines collected
+    /// before the expectation was registered are evaluated immediately.
+    ///
+    /// The state is not recomputed afterwards: lines removed or rewritten by
+    /// [`retain`](Self::retain), [`drain`](Self::drain) or
+    /// [`map_in_place`](Self::map_in_place) still count as first seen, and
+    /// lines already archived to disk by
+    /// [`with_disk_archive`](Self::with_disk_archive) are not evaluated
+    /// when the expectation is registered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// collector.expect_count("retrying", 2);
+    ///
+    /// writeln!(collector, "retrying").unwrap();
+    /// writeln!(collector, "retrying").unwrap();
+    /// writeln!(collector, "connected").unwrap();
+    ///
+    /// collector.verify();
+    /// ```
+    pub fn expect_count(&mut self, matcher: impl Into<Matcher>, expected: usize) {
+        self.add_expectation(Expectation::Count {
+            matcher: matcher.into(),
+            bound: Bound::Exactly(expected),
+            seen: 0,
+        });
+    }
+
+    /// Expects at least `min` lines to match `matcher`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// collector.expect_at_least("heartbeat", 1);
+    /// writeln!(collector, "heartbeat").unwrap();
+    ///
+    /// collector.verify();
+    /// ```
+    pub fn expect_at_least(&mut self, matcher: impl Into<Matcher>, min: usize) {
+        self.add_expectation(Expectation::Count {
+            matcher: matcher.into(),
+            bound: Bound::AtLeast(min),
+            seen: 0,
+        });
+    }
+
+    /// Expects at most `max` lines to match `matcher`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// collector.expect_at_most("ERROR", 0);
+    /// writeln!(collector, "INFO all good").unwrap();
+    ///
+    /// collector.verify();
+    /// ```
+    pub fn expect_at_most(&mut self, matcher: impl Into<Matcher>, max: usize) {
+        self.add_expectation(Expectation::Count {
+            matcher: matcher.into(),
+            bound: Bound::AtMost(max),
+            seen: 0,
+        });
+    }
+
+    /// Expects lines matching each of `matchers` to appear in order.
+    ///
+    /// Other lines may appear in between.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// collector.expect_sequence(["starting", "listening", "stopped"]);
+    ///
+    /// writeln!(collector, "starting server").unwrap();
+    /// writeln!(collector, "listening on :8080").unwrap();
+    /// writeln!(collector, "request handled").unwrap();
+    /// writeln!(collector, "stopped").unwrap();
+    ///
+    /// collector.verify();
+    /// ```
+    pub fn expect_sequence<I>(&mut self, matchers: I)
+    where
+        I: IntoIterator,
+        I::Item: Into<Matcher>,
+    {
+        self.add_expectation(Expectation::Sequence {
+            matchers: matchers.into_iter().map(Into::into).collect(),
+            progress: 0,
+        });
+    }
+
+    /// Stops storing lines; they are only evaluated against expectations.
+    ///
+    /// This bounds memory for enormous captures where only the registered
+    /// expectations matter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new().with_evaluate_and_drop();
+    /// collector.expect_count("tick", 10_000);
+    /// for _ in 0..10_000 {
+    ///     writeln!(collector, "tick").unwrap();
+    /// }
+    ///
+    /// assert_eq!(collector.count(), 0);
+    /// collector.verify();
+    /// ```
+    pub fn with_evaluate_and_drop(mut self) -> Self {
+        self.evaluate_and_drop = true;
+        self
+    }
+
+    /// Returns a description of each registered expectation that is not met.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// collector.expect_count("ready", 1);
+    ///
+    /// assert_eq!(
+    ///     collector.unmet_expectations(),
+    ///     vec![r#"expected exactly 1 line that contains "ready", saw 0"#]
+    /// );
+    /// ```
+    pub fn unmet_expectations(&self) -> Vec<String> {
+        self.expectations
+            .iter()
+            .filter_map(Expectation::failure)
+            .collect()
+    }
+
+    /// Asserts that every registered expectation is met.
+    ///
+    /// # Panics
+    ///
+    /// Panics listing every unmet expectation.
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// collector.expect_at_least("ready", 1);
+    ///
+    /// collector.verify(); // panics: nothing was written
+    /// ```
+    #[track_caller]
+    pub fn verify(&self) {
+        let failures = self.unmet_expectations();
+        if !failures.is_empty() {
+            panic!("unmet log expectations:\n  {}", failures.join("\n  "));
+        }
+    }
+
+    fn add_expectation(&mut self, mut expectation: Expectation) {
+        for line in &self.lines {
+            expectation.observe(line);
+        }
+        self.expectations.push(expectation);
+    }
+}
@@ -0,0 +1,81 @@
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::{Matcher, SharedCollector, TestLogCollector};
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "<non-string panic payload>"
+    }
+}
+
+impl TestLogCollector {
+    /// Runs `f`, asserting that it panics with a message matching `on_panic`
+    /// and that a line matching `on_logs` was collected before the panic.
+    ///
+    /// This combines `#[should_panic(expected = ...)]` with a log assertion,
+    /// while letting the rest of the test continue afterwards. A pending
+    /// partial line counts as collected.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `f` does not panic, if the panic message does not match, or
+    /// if no collected line matches `on_logs`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let logs = TestLogCollector::new_shared();
+    /// TestLogCollector::expect_panic_with_logs(&logs, "invalid port", "parsing config", || {
+    ///     writeln!(logs.lock().unwrap(), "parsing config").unwrap();
+    ///     panic!("invalid port: 99999");
+    /// });
+    /// ```
+    #[track_caller]
+    pub fn expect_panic_with_logs<F>(
+        collector: &SharedCollector,
+        on_panic: impl Into<Matcher>,
+        on_logs: impl Into<Matcher>,
+        f: F,
+    ) where
+        F: FnOnce(),
+    {
+        let on_panic = on_panic.into();
+        let on_logs = on_logs.into();
+
+        let payload = match panic::catch_unwind(AssertUnwindSafe(f)) {
+            Ok(()) => panic!(
+                "expected a panic whose message {}, but none occurred",
+                on_panic
+            ),
+            Err(payload) => payload,
+        };
+        let message = panic_message(&*payload);
+        if !on_panic.matches(message) {
+            panic!(
+                "expected a panic whose message {}, got {:?}",
+                on_panic, message
+            );
+        }
+
+        let collector = collector
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut lines = collector.lines.iter().map(String::as_str);
+        let logged = lines.any(|line| on_logs.matches(line))
+            || (!collector.current_line.is_empty() && on_logs.matches(&collector.current_line));
+        if !logged {
+            panic!(
+                "panicked with {:?} as expected, but no line logged before the panic {}; collected: {:#?}",
+                message, on_logs, collector.lines
+            );
+        }
+    }
+}
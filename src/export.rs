@@ -0,0 +1,321 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::thread::ThreadId;
+use std::time::UNIX_EPOCH;
+
+use crate::json::write_json_string;
+use crate::{Level, LineMeta, TestLogCollector};
+
+fn timestamp_ms(meta: &LineMeta) -> u128 {
+    meta.timestamp
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis())
+}
+
+fn timestamp_us(meta: &LineMeta) -> u128 {
+    meta.timestamp
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_micros())
+}
+
+fn timestamp_ns(meta: &LineMeta) -> u128 {
+    meta.timestamp
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos())
+}
+
+/// The OpenTelemetry severity number of the first, most common, value in
+/// each level's range.
+fn severity_number(level: Level) -> u8 {
+    match level {
+        Level::Trace => 1,
+        Level::Debug => 5,
+        Level::Info => 9,
+        Level::Warn => 13,
+        Level::Error => 17,
+    }
+}
+
+/// Appends an OTLP JSON attribute with a string value.
+fn push_otlp_attribute(out: &mut String, key: &str, value: &str) {
+    if !out.ends_with('[') {
+        out.push(',');
+    }
+    out.push_str("{\"key\":");
+    let _ = write_json_string(out, key);
+    out.push_str(",\"value\":{\"stringValue\":");
+    let _ = write_json_string(out, value);
+    out.push_str("}}");
+}
+
+fn write_csv_field(out: &mut String, value: &str) {
+    if value.contains([',', '"', '\n', '\r']) {
+        out.push('"');
+        out.push_str(&value.replace('"', "\"\""));
+        out.push('"');
+    } else {
+        out.push_str(value);
+    }
+}
+
+impl TestLogCollector {
+    /// Writes one JSON object per collected line, for `jq` or CI dashboards.
+    ///
+    /// Each object has the fields `line` (the index), `seq`, `timestamp_ms`
+    /// (milliseconds since the Unix epoch), `thread` (the thread name or
+    /// `null`), `level` (or `null`) and `message`.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from `writer`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "WARN low disk").unwrap();
+    ///
+    /// let mut out = Vec::new();
+    /// collector.export_jsonl(&mut out).unwrap();
+    /// let out = String::from_utf8(out).unwrap();
+    /// assert!(out.starts_with("{\"line\":0,\"seq\":"));
+    /// assert!(out.ends_with(",\"level\":\"WARN\",\"message\":\"WARN low disk\"}\n"));
+    /// ```
+    pub fn export_jsonl(&self, mut writer: impl Write) -> io::Result<()> {
+        for (i, (line, meta)) in self.lines.iter().zip(&self.meta).enumerate() {
+            let mut record = format!(
+                "{{\"line\":{},\"seq\":{},\"timestamp_ms\":{},\"thread\":",
+                i,
+                meta.seq,
+                timestamp_ms(meta)
+            );
+            match meta.thread.name() {
+                Some(name) => {
+                    let _ = write_json_string(&mut record, name);
+                }
+                None => record.push_str("null"),
+            }
+            record.push_str(",\"level\":");
+            match meta.level {
+                Some(level) => {
+                    let _ = write_json_string(&mut record, level.as_str());
+                }
+                None => record.push_str("null"),
+            }
+            record.push_str(",\"message\":");
+            let _ = write_json_string(&mut record, line);
+            record.push_str("}\n");
+            writer.write_all(record.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Writes the collected lines as CSV with a header row.
+    ///
+    /// The columns are `line`, `seq`, `timestamp_ms`, `thread`, `level` and
+    /// `message`, matching [`export_jsonl`](Self::export_jsonl). Missing
+    /// values are empty, and fields are quoted as in RFC 4180 when needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from `writer`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "a, b").unwrap();
+    ///
+    /// let mut out = Vec::new();
+    /// collector.export_csv(&mut out).unwrap();
+    /// let out = String::from_utf8(out).unwrap();
+    /// assert!(out.starts_with("line,seq,timestamp_ms,thread,level,message\n0,"));
+    /// assert!(out.ends_with(",,\"a, b\"\n"));
+    /// ```
+    pub fn export_csv(&self, mut writer: impl Write) -> io::Result<()> {
+        writer.write_all(b"line,seq,timestamp_ms,thread,level,message\n")?;
+        for (i, (line, meta)) in self.lines.iter().zip(&self.meta).enumerate() {
+            let mut record = format!("{},{},{},", i, meta.seq, timestamp_ms(meta));
+            write_csv_field(&mut record, meta.thread.name().unwrap_or(""));
+            record.push(',');
+            record.push_str(meta.level.map_or("", |level| level.as_str()));
+            record.push(',');
+            write_csv_field(&mut record, line);
+            record.push('\n');
+            writer.write_all(record.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Writes the collected lines as a Chrome trace, to view a slow test on
+    /// a timeline in `chrome://tracing` or [Perfetto](https://ui.perfetto.dev).
+    ///
+    /// Each line becomes an instant event on the track of the thread that
+    /// wrote it, named after the line and categorized by its level (or
+    /// `log`). Each run of consecutive lines in the same
+    /// [section](Self::begin_section) becomes a duration event spanning
+    /// them. Threads are labeled with their names.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from `writer`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// collector.begin_section("setup");
+    /// writeln!(collector, "INFO starting database").unwrap();
+    /// collector.end_section();
+    ///
+    /// let mut out = Vec::new();
+    /// collector.export_chrome_trace(&mut out).unwrap();
+    /// let out = String::from_utf8(out).unwrap();
+    /// assert!(out.starts_with("{\"traceEvents\":["));
+    /// assert!(out.contains("{\"name\":\"setup\",\"cat\":\"section\",\"ph\":\"X\","));
+    /// assert!(out.contains("{\"name\":\"INFO starting database\",\"cat\":\"INFO\",\"ph\":\"i\","));
+    /// ```
+    pub fn export_chrome_trace(&self, mut writer: impl Write) -> io::Result<()> {
+        let mut tids: HashMap<ThreadId, usize> = HashMap::new();
+        let mut events = Vec::new();
+        for meta in &self.meta {
+            if tids.contains_key(&meta.thread.id()) {
+                continue;
+            }
+            let tid = tids.len() + 1;
+            tids.insert(meta.thread.id(), tid);
+            let mut event = format!(
+                "{{\"name\":\"thread_name\",\"ph\":\"M\",\"pid\":1,\"tid\":{},\"args\":{{\"name\":",
+                tid
+            );
+            let _ = write_json_string(&mut event, meta.thread.name().unwrap_or("unnamed"));
+            event.push_str("}}");
+            events.push(event);
+        }
+
+        let mut start = 0;
+        while start < self.meta.len() {
+            let section = &self.meta[start].section;
+            let end = start
+                + self
+                    .meta
+                    .range(start..)
+                    .take_while(|meta| meta.section == *section)
+                    .count();
+            if let Some(section) = section {
+                let first = timestamp_us(&self.meta[start]);
+                let last = timestamp_us(&self.meta[end - 1]);
+                let mut event = String::from("{\"name\":");
+                let _ = write_json_string(&mut event, section);
+                event.push_str(&format!(
+                    ",\"cat\":\"section\",\"ph\":\"X\",\"ts\":{},\"dur\":{},\"pid\":1,\"tid\":{}}}",
+                    first,
+                    last.saturating_sub(first),
+                    tids[&self.meta[start].thread.id()]
+                ));
+                events.push(event);
+            }
+            for i in start..end {
+                let meta = &self.meta[i];
+                let mut event = String::from("{\"name\":");
+                let _ = write_json_string(&mut event, &self.lines[i]);
+                event.push_str(",\"cat\":");
+                let _ =
+                    write_json_string(&mut event, meta.level.map_or("log", |level| level.as_str()));
+                event.push_str(&format!(
+                    ",\"ph\":\"i\",\"s\":\"t\",\"ts\":{},\"pid\":1,\"tid\":{},\"args\":{{\"line\":{},\"seq\":{}}}}}",
+                    timestamp_us(meta),
+                    tids[&meta.thread.id()],
+                    i,
+                    meta.seq
+                ));
+                events.push(event);
+            }
+            start = end;
+        }
+
+        writer.write_all(b"{\"traceEvents\":[\n")?;
+        writer.write_all(events.join(",\n").as_bytes())?;
+        writer.write_all(b"\n],\"displayTimeUnit\":\"ms\"}\n")
+    }
+
+    /// Writes the collected lines as an OTLP/JSON logs export request, the
+    /// body of a `POST /v1/logs` to an OpenTelemetry collector, so test
+    /// captures can be compared with staging logs in the same tooling.
+    ///
+    /// Each line becomes a log record with the line as its string body,
+    /// its completion time, the level as severity text and number, and the
+    /// thread name (`thread.name`), target (`log.target`) and section
+    /// (`log.section`) as attributes when present. `service_name` becomes
+    /// the `service.name` resource attribute.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from `writer`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "WARN cache cold").unwrap();
+    ///
+    /// let mut out = Vec::new();
+    /// collector.export_otlp_json("checkout-tests", &mut out).unwrap();
+    /// let out = String::from_utf8(out).unwrap();
+    /// assert!(out.starts_with("{\"resourceLogs\":[{\"resource\":{\"attributes\":[{\"key\":\"service.name\""));
+    /// assert!(out.contains("\"severityNumber\":13,\"severityText\":\"WARN\",\"body\":{\"stringValue\":\"WARN cache cold\"}"));
+    /// ```
+    pub fn export_otlp_json(&self, service_name: &str, mut writer: impl Write) -> io::Result<()> {
+        let mut out = String::from("{\"resourceLogs\":[{\"resource\":{\"attributes\":[");
+        push_otlp_attribute(&mut out, "service.name", service_name);
+        out.push_str(&format!(
+            "]}},\"scopeLogs\":[{{\"scope\":{{\"name\":\"{}\",\"version\":\"{}\"}},\"logRecords\":[",
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION")
+        ));
+        for (i, (line, meta)) in self.lines.iter().zip(&self.meta).enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            let time = timestamp_ns(meta);
+            out.push_str(&format!(
+                "{{\"timeUnixNano\":\"{}\",\"observedTimeUnixNano\":\"{}\"",
+                time, time
+            ));
+            if let Some(level) = meta.level {
+                out.push_str(&format!(
+                    ",\"severityNumber\":{},\"severityText\":\"{}\"",
+                    severity_number(level),
+                    level.as_str()
+                ));
+            }
+            out.push_str(",\"body\":{\"stringValue\":");
+            let _ = write_json_string(&mut out, line);
+            out.push_str("},\"attributes\":[");
+            if let Some(name) = meta.thread.name() {
+                push_otlp_attribute(&mut out, "thread.name", name);
+            }
+            if let Some(target) = &meta.target {
+                push_otlp_attribute(&mut out, "log.target", target);
+            }
+            if let Some(section) = &meta.section {
+                push_otlp_attribute(&mut out, "log.section", section);
+            }
+            out.push_str("]}");
+        }
+        out.push_str("]}]}]}\n");
+        writer.write_all(out.as_bytes())
+    }
+}
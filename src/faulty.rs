@@ -0,0 +1,201 @@
+use std::io::{self, ErrorKind, Write};
+
+use crate::provenance::Label;
+use crate::TestLogCollector;
+
+/// A writer that injects I/O failures while collecting what it accepts.
+///
+/// `FaultyCollector` forwards the bytes it accepts to an inner
+/// [`TestLogCollector`], so lines that were successfully written can still be
+/// asserted on after a failure. Use it to test how a logging layer reacts to
+/// a misbehaving sink.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::{ErrorKind, Write};
+/// use test_log_collector::FaultyCollector;
+///
+/// let mut writer = FaultyCollector::new().fail_on_write(2, ErrorKind::BrokenPipe);
+///
+/// writer.write_all(b"first\n").unwrap();
+/// let error = writer.write_all(b"second\n").unwrap_err();
+///
+/// assert_eq!(error.kind(), ErrorKind::BrokenPipe);
+/// assert_eq!(writer.collector().clone_lines(), vec!["first"]);
+/// ```
+pub struct FaultyCollector {
+    collector: TestLogCollector,
+    write_count: usize,
+    failures: Vec<(usize, ErrorKind)>,
+    transient: Option<(usize, ErrorKind)>,
+    max_write: Option<usize>,
+}
+
+impl FaultyCollector {
+    /// Creates a faulty collector around a new empty collector.
+    ///
+    /// With no faults configured it behaves like a plain collector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::FaultyCollector;
+    ///
+    /// let writer = FaultyCollector::new();
+    /// assert_eq!(writer.write_count(), 0);
+    /// ```
+    pub fn new() -> Self {
+        Self::wrap(TestLogCollector::new())
+    }
+
+    /// Creates a faulty collector around an existing collector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::{FaultyCollector, TestLogCollector};
+    ///
+    /// let collector = TestLogCollector::new().with_replacement("secret", "<REDACTED>");
+    /// let writer = FaultyCollector::wrap(collector);
+    /// assert_eq!(writer.collector().count(), 0);
+    /// ```
+    pub fn wrap(collector: TestLogCollector) -> Self {
+        Self {
+            collector,
+            write_count: 0,
+            failures: Vec::new(),
+            transient: None,
+            max_write: None,
+        }
+    }
+
+    /// Makes the `n`th call to `write()` (counting from 1) fail with `kind`.
+    ///
+    /// Nothing from a failed call is recorded. Can be called several times to
+    /// fail several calls.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{ErrorKind, Write};
+    /// use test_log_collector::FaultyCollector;
+    ///
+    /// let mut writer = FaultyCollector::new().fail_on_write(1, ErrorKind::StorageFull);
+    ///
+    /// assert_eq!(writer.write(b"lost\n").unwrap_err().kind(), ErrorKind::StorageFull);
+    /// assert_eq!(writer.write(b"kept\n").unwrap(), 5);
+    /// assert_eq!(writer.collector().clone_lines(), vec!["kept"]);
+    /// ```
+    pub fn fail_on_write(mut self, n: usize, kind: ErrorKind) -> Self {
+        self.failures.push((n, kind));
+        self
+    }
+
+    /// Makes every `n`th call to `write()` fail with `kind`.
+    ///
+    /// Intended for transient errors such as `Interrupted` or `WouldBlock`
+    /// that a well-behaved caller retries.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{ErrorKind, Write};
+    /// use test_log_collector::FaultyCollector;
+    ///
+    /// let mut writer = FaultyCollector::new().fail_every(2, ErrorKind::Interrupted);
+    ///
+    /// // `write_all` retries on `Interrupted`.
+    /// writer.write_all(b"a\n").unwrap();
+    /// writer.write_all(b"b\n").unwrap();
+    ///
+    /// assert_eq!(writer.write_count(), 3);
+    /// assert_eq!(writer.collector().clone_lines(), vec!["a", "b"]);
+    /// ```
+    pub fn fail_every(mut self, n: usize, kind: ErrorKind) -> Self {
+        assert!(n > 0, "fail_every requires a non-zero interval");
+        self.transient = Some((n, kind));
+        self
+    }
+
+    /// Limits each `write()` call to accepting at most `max_bytes` bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_bytes` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::FaultyCollector;
+    ///
+    /// let mut writer = FaultyCollector::new().short_writes(4);
+    ///
+    /// assert_eq!(writer.write(b"hello\n").unwrap(), 4);
+    /// writer.write_all(b"o\n").unwrap();
+    /// assert_eq!(writer.collector().clone_lines(), vec!["hello"]);
+    /// ```
+    pub fn short_writes(mut self, max_bytes: usize) -> Self {
+        assert!(max_bytes > 0, "short_writes requires a non-zero limit");
+        self.max_write = Some(max_bytes);
+        self
+    }
+
+    /// Returns the number of `write()` calls made so far, including failed
+    /// ones.
+    pub fn write_count(&self) -> usize {
+        self.write_count
+    }
+
+    /// Returns the collector holding everything that was accepted.
+    pub fn collector(&self) -> &TestLogCollector {
+        &self.collector
+    }
+
+    /// Consumes the wrapper, returning the inner collector.
+    pub fn into_inner(self) -> TestLogCollector {
+        self.collector
+    }
+}
+
+impl Default for FaultyCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Write for FaultyCollector {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_count += 1;
+        let call = self.write_count;
+
+        if let Some(&(_, kind)) = self.failures.iter().find(|(n, _)| *n == call) {
+            return Err(io::Error::new(
+                kind,
+                format!("injected failure on write {}", call),
+            ));
+        }
+        if let Some((every, kind)) = self.transient {
+            if call.is_multiple_of(every) {
+                return Err(io::Error::new(
+                    kind,
+                    format!("injected transient failure on write {}", call),
+                ));
+            }
+        }
+
+        let len = self.max_write.map_or(buf.len(), |max| buf.len().min(max));
+        self.collector
+            .write_via(&Label::Static("via faulty collector"), &buf[..len])
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.collector
+            .flush_via(&Label::Static("via faulty collector"))
+    }
+}
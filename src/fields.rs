@@ -0,0 +1,199 @@
+use crate::{Matcher, TestLogCollector};
+
+/// The `key=value` fields of a line, as rendered by `tracing`'s fmt layer
+/// and logfmt-style loggers.
+///
+/// A key is a run of letters, digits, `_`, `.` and `-` at the start of the
+/// line or after whitespace, `{`, `(` or `,`. A value is either
+/// double-quoted, with `\"`, `\\`, `\n` and `\t` escapes, or runs to the next
+/// whitespace, `,`, `}` or `)`. When a key repeats, the first value wins.
+///
+/// # Examples
+///
+/// ```
+/// use test_log_collector::Fields;
+///
+/// let fields = Fields::parse(r#"INFO request{id=7}: retrying attempt=3 peer="10.0.0.1:80" slow=true"#);
+/// assert_eq!(fields.field_u64("id"), Some(7));
+/// assert_eq!(fields.field_u64("attempt"), Some(3));
+/// assert_eq!(fields.field_str("peer"), Some("10.0.0.1:80"));
+/// assert_eq!(fields.field_bool("slow"), Some(true));
+/// assert_eq!(fields.field_u64("peer"), None);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Fields {
+    fields: Vec<(String, String)>,
+}
+
+fn is_key_char(ch: char) -> bool {
+    ch.is_alphanumeric() || matches!(ch, '_' | '.' | '-')
+}
+
+impl Fields {
+    /// Parses the fields of `line`.
+    pub fn parse(line: &str) -> Self {
+        let mut fields = Self::default();
+        let mut chars = line.char_indices().peekable();
+        let mut at_boundary = true;
+        while let Some((start, ch)) = chars.next() {
+            if !at_boundary || !is_key_char(ch) {
+                at_boundary = ch.is_whitespace() || matches!(ch, '{' | '(' | ',');
+                continue;
+            }
+            let mut end = start + ch.len_utf8();
+            while let Some(&(i, ch)) = chars.peek() {
+                if !is_key_char(ch) {
+                    break;
+                }
+                end = i + ch.len_utf8();
+                chars.next();
+            }
+            if chars.next_if(|&(_, ch)| ch == '=').is_none() {
+                at_boundary = false;
+                continue;
+            }
+            let mut value = String::new();
+            if chars.next_if(|&(_, ch)| ch == '"').is_some() {
+                while let Some((_, ch)) = chars.next() {
+                    match ch {
+                        '"' => break,
+                        '\\' => match chars.next().map(|(_, ch)| ch) {
+                            Some('n') => value.push('\n'),
+                            Some('t') => value.push('\t'),
+                            Some(other) => value.push(other),
+                            None => {}
+                        },
+                        _ => value.push(ch),
+                    }
+                }
+            } else {
+                while let Some((_, ch)) =
+                    chars.next_if(|&(_, ch)| !ch.is_whitespace() && !matches!(ch, ',' | '}' | ')'))
+                {
+                    value.push(ch);
+                }
+            }
+            let key = &line[start..end];
+            if fields.field_str(key).is_none() {
+                fields.fields.push((key.to_string(), value));
+            }
+            at_boundary = false;
+        }
+        fields
+    }
+
+    /// Returns the value of `key`.
+    pub fn field_str(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the value of `key` if it is an unsigned integer.
+    pub fn field_u64(&self, key: &str) -> Option<u64> {
+        self.field_str(key)?.parse().ok()
+    }
+
+    /// Returns the value of `key` if it is an integer.
+    pub fn field_i64(&self, key: &str) -> Option<i64> {
+        self.field_str(key)?.parse().ok()
+    }
+
+    /// Returns the value of `key` if it is a number.
+    pub fn field_f64(&self, key: &str) -> Option<f64> {
+        self.field_str(key)?.parse().ok()
+    }
+
+    /// Returns the value of `key` if it is `true` or `false`.
+    pub fn field_bool(&self, key: &str) -> Option<bool> {
+        self.field_str(key)?.parse().ok()
+    }
+
+    /// Returns the fields in the order they appear.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.fields
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    /// Returns the number of fields.
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// Returns `true` if the line has no fields.
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+}
+
+impl Matcher {
+    /// Matches lines whose field `key` equals `value`, as parsed by
+    /// [`Fields`], so assertions can filter on structured values instead of
+    /// their rendering.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::Matcher;
+    ///
+    /// let matcher = Matcher::field("attempt", "3");
+    /// assert!(matcher.matches(r#"WARN retrying attempt=3 peer="a""#));
+    /// assert!(!matcher.matches("WARN retrying attempt=31"));
+    /// ```
+    pub fn field(key: impl Into<String>, value: impl Into<String>) -> Self {
+        let (key, value) = (key.into(), value.into());
+        Self::new(format!("has field {}={:?}", key, value), move |line| {
+            Fields::parse(line).field_str(&key) == Some(value.as_str())
+        })
+    }
+}
+
+impl TestLogCollector {
+    /// Returns the `key=value` fields of the line at `index`, or `None` if
+    /// `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "INFO connected peer=db1 latency_ms=12").unwrap();
+    ///
+    /// let fields = collector.line_fields(0).unwrap();
+    /// assert_eq!(fields.field_str("peer"), Some("db1"));
+    /// assert_eq!(fields.field_u64("latency_ms"), Some(12));
+    /// ```
+    pub fn line_fields(&self, index: usize) -> Option<Fields> {
+        self.lines.get(index).map(|line| Fields::parse(line))
+    }
+
+    /// Returns the lines whose field `key` equals `value`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::{Matcher, TestLogCollector};
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// write!(collector, "WARN retry attempt=1\nWARN retry attempt=2\nINFO ok attempt=2\n").unwrap();
+    ///
+    /// assert_eq!(
+    ///     collector.lines_with_field("attempt", "2"),
+    ///     ["WARN retry attempt=2", "INFO ok attempt=2"]
+    /// );
+    /// collector.expect_count(Matcher::field("attempt", "1"), 1);
+    /// collector.verify();
+    /// ```
+    pub fn lines_with_field(&self, key: &str, value: &str) -> Vec<&str> {
+        self.lines
+            .iter()
+            .filter(|line| Fields::parse(line).field_str(key) == Some(value))
+            .map(String::as_str)
+            .collect()
+    }
+}
@@ -0,0 +1,86 @@
+use std::collections::BTreeMap;
+use std::io;
+
+use crate::json::JsonValue;
+use crate::TestLogCollector;
+
+fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("invalid log fixture: {}", message.into()),
+    )
+}
+
+impl TestLogCollector {
+    /// Serializes the collected lines and the partial line as a JSON
+    /// object, `{"lines":[...],"pending":"..."}`, to store captures as
+    /// fixtures or pass them between processes. Load it with
+    /// [`from_fixture`](Self::from_fixture).
+    ///
+    /// Configuration and per-line metadata are not included; levels are
+    /// detected again on load.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// write!(collector, "done\nwaiting").unwrap();
+    ///
+    /// assert_eq!(collector.to_fixture(), r#"{"lines":["done"],"pending":"waiting"}"#);
+    /// ```
+    pub fn to_fixture(&self) -> String {
+        let lines = self.lines.iter().cloned().map(JsonValue::String).collect();
+        let members = BTreeMap::from([
+            ("lines".to_string(), JsonValue::Array(lines)),
+            (
+                "pending".to_string(),
+                JsonValue::String(self.current_line.clone()),
+            ),
+        ]);
+        JsonValue::Object(members).to_string()
+    }
+
+    /// Loads a collector saved with [`to_fixture`](Self::to_fixture).
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`InvalidData`](io::ErrorKind::InvalidData) error if
+    /// `json` is not a fixture.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let collector =
+    ///     TestLogCollector::from_fixture(r#"{"lines":["ERROR failed"],"pending":""}"#).unwrap();
+    /// assert_eq!(collector, ["ERROR failed"]);
+    ///
+    /// assert!(TestLogCollector::from_fixture("[]").is_err());
+    /// ```
+    pub fn from_fixture(json: &str) -> io::Result<TestLogCollector> {
+        let JsonValue::Object(mut members) = JsonValue::parse(json).map_err(invalid)? else {
+            return Err(invalid("expected an object"));
+        };
+        let Some(JsonValue::Array(lines)) = members.remove("lines") else {
+            return Err(invalid("expected a \"lines\" array"));
+        };
+        let pending = match members.remove("pending") {
+            Some(JsonValue::String(pending)) => pending,
+            None => String::new(),
+            Some(_) => return Err(invalid("expected \"pending\" to be a string")),
+        };
+        let mut collector = TestLogCollector::new();
+        for line in lines {
+            let JsonValue::String(line) = line else {
+                return Err(invalid("expected every line to be a string"));
+            };
+            collector.push_line(line);
+        }
+        collector.current_line = pending;
+        Ok(collector)
+    }
+}
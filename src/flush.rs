@@ -0,0 +1,51 @@
+use crate::TestLogCollector;
+
+impl TestLogCollector {
+    /// Completes the partial line when the collector is dropped, as
+    /// [`flush`](std::io::Write::flush) would, so a last message written
+    /// without a trailing newline still reaches [`on_line`](Self::on_line)
+    /// hooks, subscribers, and the write-ahead log.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new().with_auto_flush_on_drop();
+    /// let lines = collector.subscribe();
+    /// write!(collector, "shutting down").unwrap();
+    /// drop(collector);
+    ///
+    /// assert_eq!(lines.recv().unwrap(), "shutting down");
+    /// ```
+    pub fn with_auto_flush_on_drop(mut self) -> Self {
+        self.auto_flush_on_drop = true;
+        self
+    }
+
+    /// Treats each `write()` call as a complete record, ending the line
+    /// after it even without a trailing newline, for loggers that write
+    /// each message with a single unterminated call.
+    ///
+    /// Newlines inside a write still split it into several lines. An
+    /// incomplete UTF-8 sequence at the end of a write is carried over to
+    /// the next record rather than decoded early.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new().with_record_per_write();
+    /// collector.write_all(b"first").unwrap();
+    /// collector.write_all(b"second\nthird").unwrap();
+    ///
+    /// assert_eq!(collector, ["first", "second", "third"]);
+    /// ```
+    pub fn with_record_per_write(mut self) -> Self {
+        self.record_per_write = true;
+        self
+    }
+}
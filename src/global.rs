@@ -0,0 +1,153 @@
+use std::io::Write;
+use std::sync::{Mutex, MutexGuard};
+use std::thread;
+
+use crate::{SharedCollector, TestLogCollector};
+
+/// The collector installed by the live [`GlobalGuard`], if any.
+static GLOBAL: Mutex<Option<SharedCollector>> = Mutex::new(None);
+
+/// Only one global collector may be installed at a time.
+static INSTALL_LOCK: Mutex<()> = Mutex::new(());
+
+/// Keeps a collector installed as the process-wide collector.
+///
+/// Returned by [`TestLogCollector::set_global`]. Dropping the guard flushes
+/// the collector's partial line, uninstalls it so [`global_collector`]
+/// returns `None` again, and, if requested with
+/// [`verify_on_drop`](Self::verify_on_drop), asserts its expectations.
+#[must_use = "the collector is uninstalled as soon as the guard is dropped"]
+pub struct GlobalGuard {
+    collector: SharedCollector,
+    verify: bool,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl GlobalGuard {
+    /// Asserts the collector's expectations, as
+    /// [`verify`](TestLogCollector::verify) does, when the guard is dropped.
+    /// The check is skipped if the thread is already panicking.
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let guard = TestLogCollector::set_global().verify_on_drop();
+    /// guard.collector().lock().unwrap().expect_at_least("ready", 1);
+    /// drop(guard); // panics: nothing was written
+    /// ```
+    pub fn verify_on_drop(mut self) -> Self {
+        self.verify = true;
+        self
+    }
+
+    /// Returns a handle to the installed collector.
+    pub fn collector(&self) -> SharedCollector {
+        self.collector.clone()
+    }
+}
+
+impl Drop for GlobalGuard {
+    fn drop(&mut self) {
+        GLOBAL
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .take();
+        let mut collector = self
+            .collector
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let _ = collector.flush();
+        if self.verify && !thread::panicking() {
+            collector.verify();
+        }
+    }
+}
+
+/// Returns the collector installed process-wide by the live
+/// [`GlobalGuard`], from any thread.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Write;
+/// use test_log_collector::{global_collector, TestLogCollector};
+///
+/// let guard = TestLogCollector::set_global();
+/// std::thread::spawn(|| {
+///     let logs = global_collector().unwrap();
+///     writeln!(logs.lock().unwrap(), "from a worker thread").unwrap();
+/// })
+/// .join()
+/// .unwrap();
+///
+/// assert_eq!(guard.collector().lock().unwrap().count(), 1);
+/// drop(guard);
+/// assert!(global_collector().is_none());
+/// ```
+pub fn global_collector() -> Option<SharedCollector> {
+    GLOBAL
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+}
+
+impl TestLogCollector {
+    /// Installs a new empty collector process-wide until the returned guard
+    /// is dropped, so a test cannot forget the teardown and leak it into
+    /// later tests in the same binary.
+    ///
+    /// Unlike [`CollectLogsGuard`](crate::CollectLogsGuard), which is
+    /// per-thread, the collector is visible from every thread through
+    /// [`global_collector`], and [`registry::log`](crate::registry::log)
+    /// and [`registry::writer`](crate::registry::writer) route to it from
+    /// threads without a guard of their own. Only one global collector can be installed at
+    /// a time: a second call blocks until the first guard is dropped, which
+    /// serializes the tests that use it. Calling it again on the same thread
+    /// while the guard is alive deadlocks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::{global_collector, TestLogCollector};
+    ///
+    /// let guard = TestLogCollector::set_global();
+    /// write!(global_collector().unwrap().lock().unwrap(), "partial").unwrap();
+    /// let collector = guard.collector();
+    /// drop(guard);
+    ///
+    /// assert_eq!(*collector.lock().unwrap(), ["partial"]);
+    /// ```
+    pub fn set_global() -> GlobalGuard {
+        Self::set_global_collector(Self::new_shared())
+    }
+
+    /// Installs `collector` process-wide until the returned guard is
+    /// dropped, like [`set_global`](Self::set_global).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::{global_collector, TestLogCollector};
+    ///
+    /// let collector = TestLogCollector::new_shared();
+    /// let _guard = TestLogCollector::set_global_collector(collector.clone());
+    ///
+    /// assert!(std::sync::Arc::ptr_eq(&global_collector().unwrap(), &collector));
+    /// ```
+    pub fn set_global_collector(collector: SharedCollector) -> GlobalGuard {
+        let lock = INSTALL_LOCK
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        *GLOBAL
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(collector.clone());
+        GlobalGuard {
+            collector,
+            verify: false,
+            _lock: lock,
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::{Level, TestLogCollector};
+
+impl TestLogCollector {
+    /// Returns the collected lines grouped by level. Every level is present,
+    /// with an empty list if no line is at that level; lines without a level
+    /// are left out.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::{Level, TestLogCollector};
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// write!(collector, "WARN slow\nINFO ok\nWARN slower\nno level\n").unwrap();
+    ///
+    /// let by_level = collector.by_level();
+    /// assert_eq!(by_level[&Level::Warn], ["WARN slow", "WARN slower"]);
+    /// assert!(by_level[&Level::Error].is_empty());
+    /// ```
+    pub fn by_level(&self) -> HashMap<Level, Vec<&str>> {
+        let mut groups: HashMap<Level, Vec<&str>> = Level::ALL
+            .iter()
+            .map(|&level| (level, Vec::new()))
+            .collect();
+        for (line, meta) in self.lines.iter().zip(&self.meta) {
+            if let Some(level) = meta.level {
+                groups.entry(level).or_default().push(line);
+            }
+        }
+        groups
+    }
+
+    /// Groups the collected lines by the key `key_fn` computes for each,
+    /// keeping their order within each group.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// write!(collector, "worker-1: start\nworker-2: start\nworker-1: done\n").unwrap();
+    ///
+    /// let by_worker = collector.group_by(|line| line.split(':').next().unwrap().to_string());
+    /// assert_eq!(by_worker["worker-1"], ["worker-1: start", "worker-1: done"]);
+    /// assert_eq!(by_worker["worker-2"].len(), 1);
+    /// ```
+    pub fn group_by<K, F>(&self, mut key_fn: F) -> HashMap<K, Vec<&str>>
+    where
+        K: Eq + Hash,
+        F: FnMut(&str) -> K,
+    {
+        let mut groups: HashMap<K, Vec<&str>> = HashMap::new();
+        for line in &self.lines {
+            groups.entry(key_fn(line)).or_default().push(line);
+        }
+        groups
+    }
+}
@@ -0,0 +1,122 @@
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+
+use crate::TestLogCollector;
+
+pub(crate) type Hook = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// Identifies a hook registered with [`TestLogCollector::on_line`].
+///
+/// Pass it to [`TestLogCollector::remove_hook`] to unregister the hook.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HookId(u64);
+
+impl TestLogCollector {
+    /// Registers `hook` to be called with every line the moment it is
+    /// collected, so a test can react to a specific line without polling.
+    ///
+    /// Hooks run in registration order on the thread that completed the
+    /// line, while the collector is borrowed, so they must not write back
+    /// into it. Lines that are filtered out, sampled away or written while
+    /// paused do not reach hooks; collapsed duplicates do. Clones of the
+    /// collector start without hooks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    /// use std::sync::Arc;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let ready = Arc::new(AtomicBool::new(false));
+    /// let mut collector = TestLogCollector::new();
+    /// let flag = Arc::clone(&ready);
+    /// collector.on_line(move |line| {
+    ///     if line.contains("listening") {
+    ///         flag.store(true, Ordering::SeqCst);
+    ///     }
+    /// });
+    ///
+    /// writeln!(collector, "server listening on :8080").unwrap();
+    /// assert!(ready.load(Ordering::SeqCst));
+    /// ```
+    pub fn on_line<F>(&mut self, hook: F) -> HookId
+    where
+        F: Fn(&str) + Send + Sync + 'static,
+    {
+        self.next_hook += 1;
+        let id = HookId(self.next_hook);
+        self.hooks.push((id, Arc::new(hook)));
+        id
+    }
+
+    /// Unregisters a hook added with [`on_line`](Self::on_line). Returns
+    /// `false` if it was already removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use std::sync::mpsc;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let (sender, receiver) = mpsc::channel();
+    /// let mut collector = TestLogCollector::new();
+    /// let hook = collector.on_line(move |line| sender.send(line.to_string()).unwrap());
+    ///
+    /// writeln!(collector, "seen").unwrap();
+    /// assert!(collector.remove_hook(hook));
+    /// writeln!(collector, "unseen").unwrap();
+    ///
+    /// assert_eq!(receiver.try_iter().collect::<Vec<_>>(), ["seen"]);
+    /// assert!(!collector.remove_hook(hook));
+    /// ```
+    pub fn remove_hook(&mut self, id: HookId) -> bool {
+        let before = self.hooks.len();
+        self.hooks.retain(|(hook, _)| *hook != id);
+        self.hooks.len() != before
+    }
+
+    /// Returns a receiver that gets a copy of every line as it is collected,
+    /// e.g. to drive a state machine in the test from the live log stream.
+    /// The lines are still stored in the collector.
+    ///
+    /// Delivery follows the same rules as [`on_line`](Self::on_line).
+    /// Dropping the receiver stops delivery.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use std::thread;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let collector = TestLogCollector::new_shared();
+    /// let lines = collector.lock().unwrap().subscribe();
+    ///
+    /// let writer = collector.clone();
+    /// thread::spawn(move || {
+    ///     let mut collector = writer.lock().unwrap();
+    ///     writeln!(collector, "connecting").unwrap();
+    ///     writeln!(collector, "connected").unwrap();
+    /// });
+    ///
+    /// assert_eq!(lines.recv().unwrap(), "connecting");
+    /// assert_eq!(lines.recv().unwrap(), "connected");
+    /// assert_eq!(collector.lock().unwrap().count(), 2);
+    /// ```
+    pub fn subscribe(&mut self) -> Receiver<String> {
+        let (sender, receiver) = mpsc::channel();
+        self.on_line(move |line| {
+            let _ = sender.send(line.to_string());
+        });
+        receiver
+    }
+
+    pub(crate) fn run_hooks(&self, line: &str) {
+        for (_, hook) in &self.hooks {
+            hook(line);
+        }
+    }
+}
@@ -0,0 +1,11 @@
+//! Writers that feed lines into a collector.
+
+pub use crate::concurrent::{ConcurrentLogCollector, ConcurrentWriter};
+pub use crate::faulty::FaultyCollector;
+pub use crate::pipeline::{Pipeline, PipelineWriter, ShutdownError};
+pub use crate::registry::RegistryWriter;
+#[cfg(any(unix, windows))]
+pub use crate::stdio::StdioCapture;
+pub use crate::tee::Tee;
+pub use crate::wrap::CollectingWriter;
+pub use crate::write_ahead::SyncPolicy;
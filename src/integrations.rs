@@ -0,0 +1,8 @@
+//! Hooks into the test harness and CI.
+
+#[cfg(feature = "macros")]
+pub use crate::collect_logs;
+pub use crate::global::{global_collector, GlobalGuard};
+pub use crate::registry;
+pub use crate::report::Report;
+pub use crate::scope::{current_collector, propagate_to_spawned, CollectLogsGuard, Propagated};
@@ -0,0 +1,213 @@
+use std::iter::FusedIterator;
+use std::ops::{Index, Range, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive};
+
+use crate::TestLogCollector;
+
+/// An iterator over the lines of a collector, returned by
+/// [`TestLogCollector::iter`].
+#[derive(Debug, Clone)]
+pub struct Iter<'a> {
+    lines: std::slice::Iter<'a, String>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        self.lines.next().map(String::as_str)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.lines.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Iter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.lines.next_back().map(String::as_str)
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl FusedIterator for Iter<'_> {}
+
+impl TestLogCollector {
+    /// Returns an iterator over the collected lines.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "ERROR one").unwrap();
+    /// writeln!(collector, "INFO two").unwrap();
+    ///
+    /// let errors = collector.iter().filter(|line| line.starts_with("ERROR")).count();
+    /// assert_eq!(errors, 1);
+    /// ```
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            lines: self.lines.iter(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a TestLogCollector {
+    type Item = &'a str;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+impl IntoIterator for TestLogCollector {
+    type Item = String;
+    type IntoIter = std::vec::IntoIter<String>;
+
+    /// Moves the collected lines out of the collector. The partial line is
+    /// discarded; the collector is persisted first if configured to be.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "owned").unwrap();
+    ///
+    /// let lines: Vec<String> = collector.into_iter().collect();
+    /// assert_eq!(lines, vec!["owned"]);
+    /// ```
+    fn into_iter(mut self) -> Self::IntoIter {
+        self.persist_now();
+        self.current_line.clear();
+        self.take_lines().into_iter()
+    }
+}
+
+impl Index<usize> for TestLogCollector {
+    type Output = str;
+
+    /// Returns the line at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "first").unwrap();
+    /// assert_eq!(&collector[0], "first");
+    /// ```
+    fn index(&self, index: usize) -> &str {
+        &self.lines[index]
+    }
+}
+
+macro_rules! index_range {
+    ($($range:ty),*) => {
+        $(
+            impl Index<$range> for TestLogCollector {
+                type Output = [String];
+
+                fn index(&self, range: $range) -> &[String] {
+                    &self.lines[range]
+                }
+            }
+        )*
+    };
+}
+
+index_range!(
+    Range<usize>,
+    RangeFrom<usize>,
+    RangeFull,
+    RangeInclusive<usize>,
+    RangeTo<usize>,
+    RangeToInclusive<usize>
+);
+
+impl Extend<String> for TestLogCollector {
+    /// Appends each item as a complete line, as if written with a trailing
+    /// newline: scrubbers, expectations and level detection all apply. A
+    /// pending partial line is left untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// collector.extend(vec!["ERROR from fixture".to_string()]);
+    /// assert_eq!(collector.lines_at_level(test_log_collector::Level::Error).len(), 1);
+    /// ```
+    fn extend<I: IntoIterator<Item = String>>(&mut self, lines: I) {
+        for line in lines {
+            self.push_line(line);
+        }
+    }
+}
+
+impl<'a> Extend<&'a str> for TestLogCollector {
+    /// Appends each item as a complete line, like `Extend<String>`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// collector.extend("one\ntwo".lines());
+    /// assert_eq!(collector, ["one", "two"]);
+    /// ```
+    fn extend<I: IntoIterator<Item = &'a str>>(&mut self, lines: I) {
+        self.extend(lines.into_iter().map(str::to_string));
+    }
+}
+
+impl FromIterator<String> for TestLogCollector {
+    /// Builds a collector holding prerecorded lines.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let fixture = "started\nready\n";
+    /// let collector: TestLogCollector = fixture.lines().map(String::from).collect();
+    /// assert!(collector.contains("ready"));
+    /// ```
+    fn from_iter<I: IntoIterator<Item = String>>(lines: I) -> Self {
+        let mut collector = TestLogCollector::new();
+        collector.extend(lines);
+        collector
+    }
+}
+
+impl<'a> FromIterator<&'a str> for TestLogCollector {
+    /// Builds a collector holding prerecorded lines.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let collector: TestLogCollector = ["a", "b"].into_iter().collect();
+    /// assert_eq!(collector.count(), 2);
+    /// ```
+    fn from_iter<I: IntoIterator<Item = &'a str>>(lines: I) -> Self {
+        let mut collector = TestLogCollector::new();
+        collector.extend(lines);
+        collector
+    }
+}
@@ -0,0 +1,88 @@
+use std::fmt::Write as _;
+
+use crate::TestLogCollector;
+
+impl TestLogCollector {
+    /// Renders the collected lines as a JUnit XML `<testcase>` whose
+    /// `<system-out>` holds the logs, for CI systems that only display JUnit
+    /// reports.
+    ///
+    /// Each [unmet expectation](TestLogCollector::unmet_expectations) becomes
+    /// a `<failure>` element. Text is escaped, and control characters that
+    /// XML cannot represent (such as ANSI color escapes) are dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "connected to <db>").unwrap();
+    ///
+    /// assert_eq!(
+    ///     collector.to_junit_xml("connects"),
+    ///     "<testcase name=\"connects\">\n\
+    ///      <system-out>connected to &lt;db&gt;\n</system-out>\n\
+    ///      </testcase>\n"
+    /// );
+    /// ```
+    pub fn to_junit_xml(&self, test_name: &str) -> String {
+        let mut out = format!("<testcase name=\"{}\">\n", escape_xml(test_name));
+        for failure in self.unmet_expectations() {
+            let failure = escape_xml(&failure);
+            let _ = writeln!(
+                out,
+                "<failure message=\"{}\">{}</failure>",
+                failure, failure
+            );
+        }
+        out.push_str("<system-out>");
+        for line in &self.lines {
+            let _ = writeln!(out, "{}", escape_xml(line));
+        }
+        out.push_str("</system-out>\n</testcase>\n");
+        out
+    }
+
+    /// Renders the collected lines as a complete JUnit XML document holding
+    /// a single test suite with one test case, ready to be written to a file
+    /// that CI picks up.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let collector = TestLogCollector::new();
+    /// let xml = collector.to_junit_testsuite("logs", "my_test");
+    /// assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n"));
+    /// assert!(xml.contains("<testsuite name=\"logs\" tests=\"1\" failures=\"0\">"));
+    /// ```
+    pub fn to_junit_testsuite(&self, suite_name: &str, test_name: &str) -> String {
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <testsuite name=\"{}\" tests=\"1\" failures=\"{}\">\n{}</testsuite>\n",
+            escape_xml(suite_name),
+            usize::from(!self.unmet_expectations().is_empty()),
+            self.to_junit_xml(test_name)
+        )
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            '\t' | '\n' | '\r' => out.push(c),
+            c if c.is_control() => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
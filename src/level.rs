@@ -0,0 +1,373 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{Matcher, SharedCollector, TestLogCollector};
+
+/// The severity of a log line.
+///
+/// Levels are ordered from most to least severe, as in the `log` crate, so
+/// `Level::Error < Level::Warn`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Level {
+    /// An error.
+    Error,
+    /// A warning.
+    Warn,
+    /// Informational.
+    Info,
+    /// Debugging detail.
+    Debug,
+    /// Very verbose tracing detail.
+    Trace,
+}
+
+impl Level {
+    /// Every level, from most to least severe.
+    pub const ALL: [Level; 5] = [
+        Level::Error,
+        Level::Warn,
+        Level::Info,
+        Level::Debug,
+        Level::Trace,
+    ];
+
+    /// Returns the upper-case name of the level.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        }
+    }
+
+    /// Detects the level of a plain-text line from the first upper-case
+    /// level token it contains, such as `ERROR` or `WARN`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::Level;
+    ///
+    /// assert_eq!(Level::detect("2024-05-01 WARN disk almost full"), Some(Level::Warn));
+    /// assert_eq!(Level::detect("[ERROR] boom"), Some(Level::Error));
+    /// assert_eq!(Level::detect("no level here"), None);
+    /// ```
+    pub fn detect(line: &str) -> Option<Level> {
+        // Level names are ASCII, so scanning bytes finds the same tokens
+        // without decoding characters.
+        line.as_bytes()
+            .split(|byte| !byte.is_ascii_alphabetic())
+            .find_map(|token| match token {
+                b"ERROR" => Some(Level::Error),
+                b"WARN" | b"WARNING" => Some(Level::Warn),
+                b"INFO" => Some(Level::Info),
+                b"DEBUG" => Some(Level::Debug),
+                b"TRACE" => Some(Level::Trace),
+                _ => None,
+            })
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+/// The error returned when parsing an unknown level name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseLevelError(String);
+
+impl fmt::Display for ParseLevelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown log level {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseLevelError {}
+
+impl FromStr for Level {
+    type Err = ParseLevelError;
+
+    /// Parses a level name, ignoring case. `warning` is accepted for `Warn`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "error" => Ok(Level::Error),
+            "warn" | "warning" => Ok(Level::Warn),
+            "info" => Ok(Level::Info),
+            "debug" => Ok(Level::Debug),
+            "trace" => Ok(Level::Trace),
+            _ => Err(ParseLevelError(s.to_string())),
+        }
+    }
+}
+
+impl TestLogCollector {
+    /// Assigns `level` to lines matching `matcher`, overriding the detected
+    /// level.
+    ///
+    /// Rules are applied as lines are captured, and immediately to lines
+    /// already collected. The first matching rule wins. The detected level
+    /// stays available from [`original_level`](Self::original_level). Use this
+    /// to downgrade known-benign third-party errors so they don't trip
+    /// [`assert_no_errors`](Self::assert_no_errors).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::{Level, TestLogCollector};
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// collector.reclassify("ERROR hyper: connection reset", Level::Info);
+    /// writeln!(collector, "ERROR hyper: connection reset by peer").unwrap();
+    ///
+    /// assert_eq!(collector.line_level(0), Some(Level::Info));
+    /// assert_eq!(collector.original_level(0), Some(Level::Error));
+    /// collector.assert_no_errors();
+    /// ```
+    pub fn reclassify(&mut self, matcher: impl Into<Matcher>, level: Level) {
+        self.reclassifications.push((matcher.into(), level));
+        let levels: Vec<_> = self
+            .lines
+            .iter()
+            .map(|line| self.classify(line).0)
+            .collect();
+        for (meta, level) in self.meta.iter_mut().zip(levels) {
+            meta.level = level;
+        }
+    }
+
+    /// Returns the level of the line at `index`, after reclassification.
+    ///
+    /// Returns `None` if the line has no recognizable level or `index` is out
+    /// of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::{Level, TestLogCollector};
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "DEBUG cache miss").unwrap();
+    /// writeln!(collector, "plain").unwrap();
+    ///
+    /// assert_eq!(collector.line_level(0), Some(Level::Debug));
+    /// assert_eq!(collector.line_level(1), None);
+    /// ```
+    pub fn line_level(&self, index: usize) -> Option<Level> {
+        self.meta.get(index)?.level
+    }
+
+    /// Returns the level detected for the line at `index` before any
+    /// reclassification.
+    pub fn original_level(&self, index: usize) -> Option<Level> {
+        self.meta.get(index)?.original_level
+    }
+
+    /// Returns the lines whose level is `level`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::{Level, TestLogCollector};
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "WARN low disk").unwrap();
+    /// writeln!(collector, "INFO started").unwrap();
+    ///
+    /// assert_eq!(collector.lines_at_level(Level::Warn), vec!["WARN low disk"]);
+    /// ```
+    pub fn lines_at_level(&self, level: Level) -> Vec<&str> {
+        self.lines
+            .iter()
+            .zip(&self.meta)
+            .filter(|(_, meta)| meta.level == Some(level))
+            .map(|(line, _)| line.as_str())
+            .collect()
+    }
+
+    /// Asserts that no collected line is at [`Level::Error`].
+    ///
+    /// # Panics
+    ///
+    /// Panics listing every error line with its index.
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "ERROR database unavailable").unwrap();
+    ///
+    /// collector.assert_no_errors();
+    /// ```
+    #[track_caller]
+    pub fn assert_no_errors(&self) {
+        let errors: Vec<String> = self
+            .meta
+            .iter()
+            .enumerate()
+            .filter(|(_, meta)| meta.level == Some(Level::Error))
+            .map(|(index, _)| format!("[{}] {}", index, self.lines[index]))
+            .collect();
+        if !errors.is_empty() {
+            panic!(
+                "expected no error lines, found {}:\n  {}",
+                errors.len(),
+                errors.join("\n  ")
+            );
+        }
+    }
+
+    /// Drops lines more verbose than `level` at capture time, e.g.
+    /// `Level::Info` drops `DEBUG` and `TRACE` lines. Lines without a
+    /// detected level are always kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::{Level, TestLogCollector};
+    ///
+    /// let mut collector = TestLogCollector::new().with_max_level(Level::Info);
+    /// writeln!(collector, "INFO kept").unwrap();
+    /// writeln!(collector, "DEBUG dropped").unwrap();
+    /// assert_eq!(collector.clone_lines(), vec!["INFO kept"]);
+    /// ```
+    pub fn with_max_level(mut self, level: Level) -> Self {
+        self.max_level = Some(level);
+        self
+    }
+
+    /// Changes the most verbose level captured from now on, returning the
+    /// previous one. Lines already collected are not affected.
+    ///
+    /// Use this to raise verbosity around the interesting part of a test, or
+    /// [`scoped_max_level`](Self::scoped_max_level) to restore the previous
+    /// level automatically.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::{Level, TestLogCollector};
+    ///
+    /// let mut collector = TestLogCollector::new().with_max_level(Level::Info);
+    /// writeln!(collector, "TRACE setup").unwrap();
+    /// let previous = collector.set_max_level(Level::Trace);
+    /// writeln!(collector, "TRACE interesting").unwrap();
+    ///
+    /// assert_eq!(previous, Some(Level::Info));
+    /// assert_eq!(collector.clone_lines(), vec!["TRACE interesting"]);
+    /// ```
+    pub fn set_max_level(&mut self, level: Level) -> Option<Level> {
+        self.max_level.replace(level)
+    }
+
+    /// Captures lines at every level again, returning the previous maximum.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::{Level, TestLogCollector};
+    ///
+    /// let mut collector = TestLogCollector::new().with_max_level(Level::Warn);
+    /// assert_eq!(collector.clear_max_level(), Some(Level::Warn));
+    /// assert_eq!(collector.max_level(), None);
+    /// ```
+    pub fn clear_max_level(&mut self) -> Option<Level> {
+        self.max_level.take()
+    }
+
+    /// Returns the most verbose level captured, or `None` if every level is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::{Level, TestLogCollector};
+    ///
+    /// assert_eq!(TestLogCollector::new().max_level(), None);
+    /// ```
+    pub fn max_level(&self) -> Option<Level> {
+        self.max_level
+    }
+
+    /// Sets the maximum level of a shared collector until the returned guard
+    /// is dropped, then restores the previous one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::{Level, TestLogCollector};
+    ///
+    /// let shared = TestLogCollector::new_shared();
+    /// shared.lock().unwrap().set_max_level(Level::Info);
+    /// {
+    ///     let _verbose = TestLogCollector::scoped_max_level(&shared, Level::Trace);
+    ///     writeln!(shared.lock().unwrap(), "DEBUG deep detail").unwrap();
+    /// }
+    /// writeln!(shared.lock().unwrap(), "DEBUG noise").unwrap();
+    ///
+    /// let collector = shared.lock().unwrap();
+    /// assert_eq!(collector.clone_lines(), vec!["DEBUG deep detail"]);
+    /// assert_eq!(collector.max_level(), Some(Level::Info));
+    /// ```
+    pub fn scoped_max_level(shared: &SharedCollector, level: Level) -> MaxLevelGuard {
+        let previous = shared
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .set_max_level(level);
+        MaxLevelGuard {
+            collector: shared.clone(),
+            previous,
+        }
+    }
+
+    pub(crate) fn admits(&self, level: Option<Level>) -> bool {
+        match (level, self.max_level) {
+            (Some(level), Some(max)) => level <= max,
+            _ => true,
+        }
+    }
+
+    pub(crate) fn classify(&self, line: &str) -> (Option<Level>, Option<Level>) {
+        let original = if self.heuristic_levels {
+            Level::detect_heuristic(line)
+        } else {
+            Level::detect(line)
+        };
+        let level = self
+            .reclassifications
+            .iter()
+            .find(|(matcher, _)| matcher.matches(line))
+            .map_or(original, |(_, level)| Some(*level));
+        (level, original)
+    }
+}
+
+/// Restores a shared collector's previous maximum level when dropped.
+///
+/// Returned by [`TestLogCollector::scoped_max_level`].
+#[must_use = "the previous level is restored as soon as the guard is dropped"]
+pub struct MaxLevelGuard {
+    collector: SharedCollector,
+    previous: Option<Level>,
+}
+
+impl Drop for MaxLevelGuard {
+    fn drop(&mut self) {
+        let mut collector = self
+            .collector
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        collector.max_level = self.previous;
+    }
+}
@@ -1,4 +1,134 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, VecDeque};
 use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::{self, ThreadId};
+use std::time::SystemTime;
+
+mod ansi;
+mod archive;
+pub mod assert;
+mod async_shared;
+mod builder;
+mod bytes;
+mod catalog;
+pub mod collector;
+mod compare;
+mod concurrent;
+mod count;
+#[cfg(windows)]
+mod debug_string;
+mod delimiter;
+mod detect;
+mod diff;
+mod duplicates;
+mod edit;
+mod error_report;
+mod expect;
+mod expect_panic;
+mod export;
+mod faulty;
+mod fields;
+mod fixture;
+mod flush;
+mod global;
+mod group;
+mod hook;
+pub mod ingest;
+pub mod integrations;
+mod iter;
+mod json;
+mod junit;
+mod level;
+mod lock;
+mod matcher;
+mod newline;
+mod normalize;
+mod offset;
+mod output;
+mod panic_dump;
+mod pause;
+mod persist;
+mod pipeline;
+pub mod prelude;
+mod provenance;
+mod rate;
+mod reader;
+mod record;
+pub mod registry;
+mod render;
+mod replay;
+mod report;
+mod sampling;
+mod scope;
+mod search;
+mod section;
+mod shared;
+#[cfg(any(unix, windows))]
+mod stdio;
+mod summary;
+mod syslog;
+mod tail;
+mod take;
+mod target;
+mod tee;
+mod template;
+mod terminal;
+mod truncate;
+mod wrap;
+mod write_ahead;
+
+pub use archive::AllLines;
+pub use async_shared::{AsyncCollectorGuard, AsyncSharedCollector, LockFuture};
+pub use builder::TestLogCollectorBuilder;
+pub use catalog::MessageCatalog;
+pub use concurrent::{ConcurrentLogCollector, ConcurrentWriter};
+#[cfg(windows)]
+pub use debug_string::DebugOutputCapture;
+pub use diff::{LineChange, LineDiff};
+pub use error_report::ErrorReport;
+pub use faulty::FaultyCollector;
+pub use fields::Fields;
+pub use global::{global_collector, GlobalGuard};
+pub use hook::HookId;
+pub use iter::Iter;
+pub use level::{Level, MaxLevelGuard, ParseLevelError};
+pub use lock::SharedCollectorExt;
+pub use matcher::Matcher;
+pub use newline::NewlinePolicy;
+pub use normalize::{Preset, Presets};
+pub use panic_dump::PanicDumpGuard;
+pub use pipeline::{Pipeline, PipelineWriter, ShutdownError};
+pub use reader::LogReader;
+pub use report::Report;
+pub use sampling::Sampling;
+pub use scope::{current_collector, propagate_to_spawned, CollectLogsGuard, Propagated};
+#[cfg(any(unix, windows))]
+pub use stdio::StdioCapture;
+pub use summary::Summary;
+pub use syslog::SyslogRecord;
+pub use tail::TailIter;
+pub use tee::Tee;
+pub use wrap::CollectingWriter;
+pub use write_ahead::SyncPolicy;
+
+/// Wraps a test function so its logs are collected and dumped on failure.
+///
+/// Requires the `macros` feature.
+#[cfg(feature = "macros")]
+pub use test_log_collector_macros::collect_logs;
+
+/// A collector shared between threads, as returned by
+/// [`TestLogCollector::new_shared`].
+pub type SharedCollector = Arc<std::sync::Mutex<TestLogCollector>>;
+
+type Scrubber = Arc<dyn Fn(&str) -> String + Send + Sync>;
+
+/// Process-wide counter shared by all collectors so lines can be ordered
+/// across collectors.
+static NEXT_SEQUENCE: AtomicU64 = AtomicU64::new(0);
 
 /// A utility for collecting log messages during testing.
 ///
@@ -20,10 +150,157 @@ use std::io::{self, Write};
 /// ```
 pub struct TestLogCollector {
     lines: Vec<String>,
+    meta: VecDeque<LineMeta>,
     current_line: String,
+    current_raw: Vec<u8>,
+    pending_truncated: usize,
+    max_line_len: Option<usize>,
+    max_lines: Option<usize>,
+    timestamps: bool,
+    collapse_duplicates: bool,
+    paused: bool,
+    suppressed_count: usize,
+    sampling: Option<Sampling>,
+    total_written: usize,
+    sample_rng: u64,
+    archive: Option<archive::Archive>,
+    shared_lines: std::sync::Mutex<Option<std::sync::Weak<[Arc<str>]>>>,
+    section: Option<Arc<str>>,
+    continuation: Option<record::Continuation>,
+    utf8_carry: Vec<u8>,
+    raw_bytes: bool,
+    newline_policy: NewlinePolicy,
+    delimiters: u128,
+    after_cr: bool,
+    strict_utf8: bool,
+    scrubbers: Vec<(provenance::Label, Scrubber)>,
+    sources: Vec<provenance::Label>,
+    target: Option<String>,
+    directives: Option<target::Directives>,
+    allowed_targets: Vec<String>,
+    denied_targets: Vec<String>,
+    unfiltered_count: usize,
+    errors: Vec<ErrorReport>,
+    index: Option<search::TokenIndex>,
+    index_threshold: usize,
+    write_calls: Option<Vec<Vec<u8>>>,
+    max_write: Option<usize>,
+    flush_count: usize,
+    expectations: Vec<expect::Expectation>,
+    evaluate_and_drop: bool,
+    catalog: MessageCatalog,
+    reclassifications: Vec<(Matcher, Level)>,
+    max_level: Option<Level>,
+    persist_path: Option<PathBuf>,
+    write_ahead: Option<write_ahead::WriteAhead>,
+    hooks: Vec<(HookId, hook::Hook)>,
+    next_hook: u64,
+    heuristic_levels: bool,
+    auto_flush_on_drop: bool,
+    record_per_write: bool,
+    terminal: bool,
+    stream_offset: u64,
+    line_offset: u64,
+    next_offset: Option<u64>,
+}
+
+/// Identifies the thread that completed a collected line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThreadTag {
+    id: ThreadId,
+    name: Option<Arc<str>>,
+}
+
+impl ThreadTag {
+    fn current(previous: Option<&ThreadTag>) -> Self {
+        let current = thread::current();
+        match previous {
+            Some(tag) if tag.id == current.id() => tag.clone(),
+            _ => Self {
+                id: current.id(),
+                name: current.name().map(Arc::from),
+            },
+        }
+    }
+
+    /// Returns the id of the thread.
+    pub fn id(&self) -> ThreadId {
+        self.id
+    }
+
+    /// Returns the name of the thread, if it has one.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+}
+
+/// Whether `SystemTime::now` works on this target; it panics on
+/// `wasm32-unknown-unknown`.
+const HAS_CLOCK: bool = !cfg!(all(target_arch = "wasm32", target_os = "unknown"));
+
+#[derive(Clone)]
+struct LineMeta {
+    thread: ThreadTag,
+    seq: u64,
+    timestamp: SystemTime,
+    level: Option<Level>,
+    original_level: Option<Level>,
+    provenance: Vec<provenance::Label>,
+    raw: Option<Box<[u8]>>,
+    original_len: Option<usize>,
+    target: Option<Box<str>>,
+    repeats: usize,
+    section: Option<Arc<str>>,
+    offset: Option<u64>,
+    original: Option<Box<str>>,
+}
+
+/// Returns the length of the unfinished UTF-8 sequence at the end of
+/// `bytes`, which may be completed by the next write.
+fn incomplete_utf8_suffix(bytes: &[u8]) -> usize {
+    (1..=bytes.len().min(3))
+        .find(|&len| {
+            matches!(
+                std::str::from_utf8(&bytes[bytes.len() - len..]),
+                Err(error) if error.valid_up_to() == 0 && error.error_len().is_none()
+            )
+        })
+        .unwrap_or(0)
+}
+
+/// 64-bit FNV-1a. Unlike `DefaultHasher` its output is stable across Rust
+/// releases, so hashes can be compared between runs.
+fn line_hash(line: &str) -> u64 {
+    line.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
 }
 
 impl TestLogCollector {
+    /// Creates an empty collector with room for `lines` lines, and for a
+    /// partial line of `bytes_per_line` bytes, so high-volume tests don't pay
+    /// for repeated reallocation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::with_capacity(100_000, 120);
+    /// for i in 0..100_000 {
+    ///     writeln!(collector, "event {}", i).unwrap();
+    /// }
+    /// assert_eq!(collector.count(), 100_000);
+    /// ```
+    pub fn with_capacity(lines: usize, bytes_per_line: usize) -> Self {
+        let mut collector = Self::new();
+        collector.lines.reserve(lines);
+        collector.meta.reserve(lines);
+        collector.current_line.reserve(bytes_per_line);
+        collector
+    }
+
     /// Creates a new empty collector.
     ///
     /// # Examples
@@ -37,11 +314,253 @@ impl TestLogCollector {
     pub fn new() -> Self {
         Self {
             lines: Vec::new(),
+            meta: VecDeque::new(),
             current_line: String::new(),
+            current_raw: Vec::new(),
+            pending_truncated: 0,
+            max_line_len: None,
+            max_lines: None,
+            timestamps: HAS_CLOCK,
+            collapse_duplicates: false,
+            paused: false,
+            suppressed_count: 0,
+            sampling: None,
+            total_written: 0,
+            sample_rng: sampling::SEED,
+            archive: None,
+            shared_lines: Default::default(),
+            section: None,
+            continuation: None,
+            utf8_carry: Vec::new(),
+            raw_bytes: false,
+            newline_policy: NewlinePolicy::Keep,
+            delimiters: delimiter::NEWLINE,
+            after_cr: false,
+            strict_utf8: false,
+            scrubbers: Vec::new(),
+            sources: Vec::new(),
+            target: None,
+            directives: None,
+            allowed_targets: Vec::new(),
+            denied_targets: Vec::new(),
+            unfiltered_count: 0,
+            errors: Vec::new(),
+            index: None,
+            index_threshold: search::DEFAULT_INDEX_THRESHOLD,
+            write_calls: None,
+            max_write: None,
+            flush_count: 0,
+            expectations: Vec::new(),
+            evaluate_and_drop: false,
+            catalog: MessageCatalog::new(),
+            reclassifications: Vec::new(),
+            max_level: None,
+            persist_path: None,
+            write_ahead: None,
+            hooks: Vec::new(),
+            next_hook: 0,
+            heuristic_levels: false,
+            auto_flush_on_drop: false,
+            record_per_write: false,
+            terminal: false,
+            stream_offset: 0,
+            line_offset: 0,
+            next_offset: None,
         }
     }
 
-    /// Clears all collected lines and partial content.
+    /// Adds a scrubber that rewrites each line before it is stored.
+    ///
+    /// Scrubbers run in the order they were added, each one receiving the
+    /// output of the previous one. Use them to replace unstable or sensitive
+    /// content (timestamps, tokens, ids) so assertions and snapshots stay
+    /// deterministic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new().with_scrubber(|line| {
+    ///     match line.find("Bearer ") {
+    ///         Some(pos) => format!("{}Bearer <REDACTED>", &line[..pos]),
+    ///         None => line.to_string(),
+    ///     }
+    /// });
+    /// writeln!(collector, "auth: Bearer abc123").unwrap();
+    ///
+    /// assert_eq!(collector.clone_lines(), vec!["auth: Bearer <REDACTED>"]);
+    /// ```
+    pub fn with_scrubber<F>(self, scrubber: F) -> Self
+    where
+        F: Fn(&str) -> String + Send + Sync + 'static,
+    {
+        let name = format!("scrubber #{}", self.scrubbers.len() + 1);
+        self.with_named_scrubber(name, scrubber)
+    }
+
+    /// Adds a scrubber that replaces every occurrence of `from` with `to`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new().with_replacement("hunter2", "<REDACTED>");
+    /// writeln!(collector, "password=hunter2").unwrap();
+    ///
+    /// assert_eq!(collector.clone_lines(), vec!["password=<REDACTED>"]);
+    /// ```
+    pub fn with_replacement(self, from: impl Into<String>, to: impl Into<String>) -> Self {
+        let from = from.into();
+        let to = to.into();
+        let name = format!("replace {:?} with {:?}", from, to);
+        self.with_named_scrubber(name, move |line| line.replace(&from, &to))
+    }
+
+    /// Records the bytes passed to every `write()` call.
+    ///
+    /// The recorded calls are available from
+    /// [`write_calls`](Self::write_calls). Recording is off by default because
+    /// it keeps a second copy of everything written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{BufWriter, Write};
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let collector = TestLogCollector::new().with_write_spy();
+    /// let mut writer = BufWriter::with_capacity(8, collector);
+    /// writer.write_all(b"abc").unwrap();
+    /// writer.write_all(b"defghi\n").unwrap();
+    /// let Ok(collector) = writer.into_inner() else {
+    ///     panic!("flush failed");
+    /// };
+    ///
+    /// assert_eq!(collector.write_calls(), [b"abc".to_vec(), b"defghi\n".to_vec()]);
+    /// ```
+    pub fn with_write_spy(mut self) -> Self {
+        self.write_calls = Some(Vec::new());
+        self
+    }
+
+    /// Makes each `write()` call accept at most `max_bytes` bytes and return
+    /// the short count, to test that code under test loops on short writes.
+    /// Lines are built from the accepted bytes only.
+    ///
+    /// [`FaultyCollector::short_writes`] does the same for a wrapped
+    /// collector, combined with injected errors.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_bytes` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new().with_short_writes(4);
+    /// assert_eq!(collector.write(b"hello\n").unwrap(), 4);
+    ///
+    /// collector.write_all(b"o\nworld\n").unwrap();
+    /// assert_eq!(collector, ["hello", "world"]);
+    /// ```
+    pub fn with_short_writes(mut self, max_bytes: usize) -> Self {
+        assert!(max_bytes > 0, "with_short_writes requires a non-zero limit");
+        self.max_write = Some(max_bytes);
+        self
+    }
+
+    /// Keeps only the most recent `max` lines, discarding the oldest ones as
+    /// new lines arrive, to bound memory in long-running tests.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new().with_max_lines(2);
+    /// write!(collector, "one\ntwo\nthree\n").unwrap();
+    ///
+    /// assert_eq!(collector, ["two", "three"]);
+    /// ```
+    pub fn with_max_lines(mut self, max: usize) -> Self {
+        self.max_lines = Some(max);
+        self
+    }
+
+    /// Stops recording when each line was completed, saving a clock read per
+    /// line. [`lines_timestamped`](Self::lines_timestamped) then reports
+    /// `UNIX_EPOCH` for every line.
+    ///
+    /// Timestamps are always off on `wasm32-unknown-unknown`, which has no
+    /// system clock.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use std::time::SystemTime;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new().without_timestamps();
+    /// writeln!(collector, "untimed").unwrap();
+    ///
+    /// assert_eq!(collector.lines_timestamped()[0].0, SystemTime::UNIX_EPOCH);
+    /// ```
+    pub fn without_timestamps(mut self) -> Self {
+        self.timestamps = false;
+        self
+    }
+
+    /// Returns the bytes passed to each `write()` call, in order.
+    ///
+    /// Always empty unless recording was enabled with
+    /// [`with_write_spy`](Self::with_write_spy).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new().with_write_spy();
+    /// write!(collector, "{} {}", "a", "b").unwrap();
+    ///
+    /// assert_eq!(collector.write_calls().concat(), b"a b");
+    /// ```
+    pub fn write_calls(&self) -> &[Vec<u8>] {
+        self.write_calls.as_deref().unwrap_or_default()
+    }
+
+    /// Returns the number of times `flush()` has been called.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// collector.flush().unwrap();
+    /// collector.flush().unwrap();
+    ///
+    /// assert_eq!(collector.flush_count(), 2);
+    /// ```
+    pub fn flush_count(&self) -> usize {
+        self.flush_count
+    }
+
+    /// Clears all collected lines, partial content, recorded errors and
+    /// recorded write and flush calls.
+    ///
+    /// Registered expectations are kept but start counting from scratch.
     ///
     /// # Examples
     ///
@@ -57,7 +576,55 @@ impl TestLogCollector {
     /// ```
     pub fn clear(&mut self) {
         self.lines.clear();
+        self.meta.clear();
         self.current_line.clear();
+        self.current_raw.clear();
+        self.pending_truncated = 0;
+        self.unfiltered_count = 0;
+        self.suppressed_count = 0;
+        self.total_written = 0;
+        self.sample_rng = sampling::SEED;
+        self.clear_archive();
+        self.invalidate_shared_lines();
+        self.utf8_carry.clear();
+        self.after_cr = false;
+        self.stream_offset = 0;
+        self.line_offset = 0;
+        self.errors.clear();
+        self.index = None;
+        if let Some(calls) = &mut self.write_calls {
+            calls.clear();
+        }
+        self.flush_count = 0;
+        for expectation in &mut self.expectations {
+            expectation.reset();
+        }
+    }
+
+    /// Releases memory the collector no longer needs, e.g. after
+    /// [`clear`](Self::clear) in a long-lived shared collector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::with_capacity(10_000, 80);
+    /// writeln!(collector, "kept").unwrap();
+    /// collector.shrink_to_fit();
+    ///
+    /// assert_eq!(collector, ["kept"]);
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        self.lines.shrink_to_fit();
+        self.meta.shrink_to_fit();
+        self.current_line.shrink_to_fit();
+        self.current_raw.shrink_to_fit();
+        self.errors.shrink_to_fit();
+        if let Some(calls) = &mut self.write_calls {
+            calls.shrink_to_fit();
+        }
     }
 
     /// Returns the number of complete lines collected.
@@ -78,6 +645,44 @@ impl TestLogCollector {
         self.lines.len()
     }
 
+    /// Returns the content written since the last newline, which becomes a
+    /// line on the next newline or `flush()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// write!(collector, "Progress: 50%").unwrap();
+    ///
+    /// assert_eq!(collector.pending(), "Progress: 50%");
+    /// assert_eq!(collector.count(), 0);
+    /// ```
+    pub fn pending(&self) -> &str {
+        &self.current_line
+    }
+
+    /// Returns `true` if content has been written since the last newline.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// write!(collector, "partial").unwrap();
+    /// assert!(collector.has_pending());
+    ///
+    /// collector.flush().unwrap();
+    /// assert!(!collector.has_pending());
+    /// ```
+    pub fn has_pending(&self) -> bool {
+        !self.current_line.is_empty()
+    }
+
     /// Returns a reference to the collected lines.
     ///
     /// # Examples
@@ -96,6 +701,78 @@ impl TestLogCollector {
         &self.lines
     }
 
+    /// Returns the first collected line.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// assert_eq!(collector.first(), None);
+    ///
+    /// write!(collector, "starting\nstopped\n").unwrap();
+    /// assert_eq!(collector.first(), Some("starting"));
+    /// ```
+    pub fn first(&self) -> Option<&str> {
+        self.lines.first().map(String::as_str)
+    }
+
+    /// Returns the last collected line. The partial line is not included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// write!(collector, "serving\nshutting down\npartial").unwrap();
+    ///
+    /// assert_eq!(collector.last(), Some("shutting down"));
+    /// ```
+    pub fn last(&self) -> Option<&str> {
+        self.lines.last().map(String::as_str)
+    }
+
+    /// Returns the line at `index`, or `None` if it is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// write!(collector, "a\nb\n").unwrap();
+    ///
+    /// assert_eq!(collector.nth(1), Some("b"));
+    /// assert_eq!(collector.nth(2), None);
+    /// ```
+    pub fn nth(&self, index: usize) -> Option<&str> {
+        self.lines.get(index).map(String::as_str)
+    }
+
+    /// Returns the last `n` collected lines, or all of them if there are
+    /// fewer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// write!(collector, "one\ntwo\nthree\n").unwrap();
+    ///
+    /// assert_eq!(collector.tail(2), ["two", "three"]);
+    /// assert_eq!(collector.tail(10).len(), 3);
+    /// ```
+    pub fn tail(&self, n: usize) -> &[String] {
+        &self.lines[self.lines.len().saturating_sub(n)..]
+    }
+
     /// Returns a clone of all collected lines.
     ///
     /// # Examples
@@ -114,6 +791,208 @@ impl TestLogCollector {
         self.lines.clone()
     }
 
+    /// Returns the lines completed by the thread with the given name.
+    ///
+    /// A line is attributed to the thread whose write completed it, i.e. the
+    /// thread that wrote the terminating newline or called `flush()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use std::thread;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let log_collector = TestLogCollector::new_shared();
+    /// let collector_clone = log_collector.clone();
+    ///
+    /// thread::Builder::new()
+    ///     .name("worker-1".into())
+    ///     .spawn(move || {
+    ///         writeln!(collector_clone.lock().unwrap(), "from worker").unwrap();
+    ///     })
+    ///     .unwrap()
+    ///     .join()
+    ///     .unwrap();
+    ///
+    /// let collector = log_collector.lock().unwrap();
+    /// assert_eq!(collector.lines_from_thread("worker-1"), vec!["from worker"]);
+    /// ```
+    pub fn lines_from_thread(&self, name: &str) -> Vec<&str> {
+        self.lines_tagged()
+            .into_iter()
+            .filter(|(tag, _)| tag.name() == Some(name))
+            .map(|(_, line)| line)
+            .collect()
+    }
+
+    /// Returns each collected line together with the thread that wrote it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use std::thread;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "Test line").unwrap();
+    ///
+    /// let tagged = collector.lines_tagged();
+    /// assert_eq!(tagged[0].0.id(), thread::current().id());
+    /// assert_eq!(tagged[0].1, "Test line");
+    /// ```
+    pub fn lines_tagged(&self) -> Vec<(&ThreadTag, &str)> {
+        self.meta
+            .iter()
+            .zip(&self.lines)
+            .map(|(meta, line)| (&meta.thread, line.as_str()))
+            .collect()
+    }
+
+    /// Returns each collected line with its sequence number.
+    ///
+    /// Sequence numbers come from a single process-wide counter, so they are
+    /// strictly increasing across every collector and can be used to order
+    /// lines captured by different collectors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "first").unwrap();
+    /// writeln!(collector, "second").unwrap();
+    ///
+    /// let sequenced = collector.lines_sequenced();
+    /// assert!(sequenced[0].0 < sequenced[1].0);
+    /// assert_eq!(sequenced[1].1, "second");
+    /// ```
+    pub fn lines_sequenced(&self) -> Vec<(u64, &str)> {
+        self.meta
+            .iter()
+            .zip(&self.lines)
+            .map(|(meta, line)| (meta.seq, line.as_str()))
+            .collect()
+    }
+
+    /// Returns each collected line with the time it was completed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use std::time::SystemTime;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let before = SystemTime::now();
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "stamped").unwrap();
+    ///
+    /// let (time, line) = collector.lines_timestamped()[0];
+    /// assert!(time >= before);
+    /// assert_eq!(line, "stamped");
+    /// ```
+    pub fn lines_timestamped(&self) -> Vec<(SystemTime, &str)> {
+        self.meta
+            .iter()
+            .zip(&self.lines)
+            .map(|(meta, line)| (meta.timestamp, line.as_str()))
+            .collect()
+    }
+
+    /// Merges the lines of several collectors in the order they were written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut stdout = TestLogCollector::new();
+    /// let mut stderr = TestLogCollector::new();
+    /// writeln!(stdout, "starting").unwrap();
+    /// writeln!(stderr, "warning").unwrap();
+    /// writeln!(stdout, "done").unwrap();
+    ///
+    /// assert_eq!(
+    ///     TestLogCollector::interleave(&[&stdout, &stderr]),
+    ///     vec!["starting", "warning", "done"]
+    /// );
+    /// ```
+    pub fn interleave<'a>(collectors: &[&'a TestLogCollector]) -> Vec<&'a str> {
+        let mut merged: Vec<(u64, &str)> = collectors
+            .iter()
+            .flat_map(|collector| collector.lines_sequenced())
+            .collect();
+        merged.sort_by_key(|(seq, _)| *seq);
+        merged.into_iter().map(|(_, line)| line).collect()
+    }
+
+    /// Returns a hash of each collected line.
+    ///
+    /// The hash is 64-bit FNV-1a over the stored line, which is stable across
+    /// runs and platforms, so hashes from different runs can be compared.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "same").unwrap();
+    /// writeln!(collector, "same").unwrap();
+    /// writeln!(collector, "different").unwrap();
+    ///
+    /// let hashes = collector.line_hashes();
+    /// assert_eq!(hashes[0], hashes[1]);
+    /// assert_ne!(hashes[0], hashes[2]);
+    /// ```
+    pub fn line_hashes(&self) -> Vec<u64> {
+        self.lines.iter().map(|line| line_hash(line)).collect()
+    }
+
+    /// Returns the indices of lines that occur more than once, grouped by
+    /// content.
+    ///
+    /// Groups are ordered by the position of their first line.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "retrying").unwrap();
+    /// writeln!(collector, "connected").unwrap();
+    /// writeln!(collector, "retrying").unwrap();
+    ///
+    /// assert_eq!(collector.duplicate_groups(), vec![vec![0, 2]]);
+    /// ```
+    pub fn duplicate_groups(&self) -> Vec<Vec<usize>> {
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        let mut groups_by_hash: HashMap<u64, Vec<usize>> = HashMap::new();
+        for (index, line) in self.lines.iter().enumerate() {
+            let candidates = groups_by_hash.entry(line_hash(line)).or_default();
+            match candidates
+                .iter()
+                .find(|&&group| self.lines[groups[group][0]] == self.lines[index])
+            {
+                Some(&group) => groups[group].push(index),
+                None => {
+                    candidates.push(groups.len());
+                    groups.push(vec![index]);
+                }
+            }
+        }
+        groups.retain(|group| group.len() > 1);
+        groups
+    }
+
     /// Creates a new collector wrapped in `Arc<Mutex<>>` for shared access.
     ///
     /// This is useful for multi-threaded testing scenarios where you need to
@@ -138,11 +1017,247 @@ impl TestLogCollector {
     /// let collector = log_collector.lock().unwrap();
     /// assert_eq!(collector.count(), 0); // No messages written in this example
     /// ```
-    pub fn new_shared() -> std::sync::Arc<std::sync::Mutex<Self>> {
+    pub fn new_shared() -> SharedCollector {
         std::sync::Arc::new(std::sync::Mutex::new(Self::new()))
     }
 }
 
+impl TestLogCollector {
+    fn push_line(&mut self, line: String) {
+        self.push_line_raw(line, None, 0);
+    }
+
+    fn push_line_raw(&mut self, mut line: String, mut raw: Option<Vec<u8>>, truncated: usize) {
+        if self.newline_policy == NewlinePolicy::StripTrailingCr && line.ends_with('\r') {
+            line.pop();
+        }
+        let offset = self.next_offset.take();
+        let original_len = self.truncate_line(&mut line, &mut raw, truncated);
+        let mut provenance = self.sources.clone();
+        let unscrubbed = (!self.scrubbers.is_empty()).then(|| line.clone());
+        let line = self.scrubbers.iter().fold(line, |line, (label, scrubber)| {
+            let scrubbed = scrubber(&line);
+            if scrubbed != line {
+                provenance.push(label.clone());
+            }
+            scrubbed
+        });
+        let original = unscrubbed
+            .filter(|unscrubbed| *unscrubbed != line)
+            .map(String::into_boxed_str);
+        let (level, original_level) = self.classify(&line);
+        self.unfiltered_count += 1;
+        if !self.admits(level) || !self.target_admits(level) {
+            return;
+        }
+        if self.paused {
+            self.suppressed_count += 1;
+            return;
+        }
+        for expectation in &mut self.expectations {
+            expectation.observe(&line);
+        }
+        let replaces = match self.sample() {
+            sampling::Sampled::Dropped => return,
+            _ if self.evaluate_and_drop => return,
+            sampling::Sampled::Kept => None,
+            sampling::Sampled::Replaces(slot) => Some(slot),
+        };
+        if let Some(write_ahead) = &mut self.write_ahead {
+            write_ahead.append(&line);
+        }
+        self.run_hooks(&line);
+        if replaces.is_none() && self.collapse_duplicates && self.lines.last() == Some(&line) {
+            if let Some(meta) = self.meta.back_mut() {
+                meta.repeats += 1;
+            }
+            return;
+        }
+        let thread = ThreadTag::current(self.meta.back().map(|meta| &meta.thread));
+        let seq = NEXT_SEQUENCE.fetch_add(1, Ordering::Relaxed);
+        let meta = LineMeta {
+            thread,
+            seq,
+            timestamp: if self.timestamps {
+                SystemTime::now()
+            } else {
+                SystemTime::UNIX_EPOCH
+            },
+            level,
+            original_level,
+            provenance,
+            raw: raw.map(Vec::into_boxed_slice),
+            original_len,
+            target: self.target.as_deref().map(Box::from),
+            repeats: 1,
+            section: self.section.clone(),
+            offset,
+            original,
+        };
+        if let Some(slot) = replaces {
+            self.replace_sampled(slot, line, meta);
+            return;
+        }
+        self.lines.push(line);
+        self.meta.push_back(meta);
+        self.index_last_line();
+        if let Some(max) = self.max_lines {
+            if self.lines.len() > max {
+                self.evict_front(self.lines.len() - max);
+            }
+        }
+        self.archive_if_needed();
+    }
+
+    /// Splits decoded `bytes` into lines, buffering the unfinished last one.
+    fn collect_bytes(&mut self, bytes: &[u8]) {
+        let s = String::from_utf8_lossy(bytes);
+        let mut terminators = self.delimiters;
+        if self.newline_policy == NewlinePolicy::CrTerminates {
+            terminators |= delimiter::mask(b'\r');
+        }
+        let find_terminator = |text: &str| {
+            if terminators == delimiter::NEWLINE {
+                text.find('\n')
+            } else {
+                text.bytes()
+                    .position(|byte| delimiter::contains(terminators, byte))
+            }
+        };
+        // Terminators are ASCII and lossy decoding keeps every ASCII byte, so
+        // the raw segments line up with the decoded ones.
+        let mut raw_segments = self
+            .raw_bytes
+            .then(|| bytes.split(move |&byte| delimiter::contains(terminators, byte)));
+        // The terminators come in the same order in the written bytes, where
+        // the one after each line gives the offset of the next. Valid UTF-8
+        // is decoded in place, so positions in `s` are already byte offsets;
+        // only lossily decoded bytes need a separate scan.
+        let base = self.stream_offset;
+        self.stream_offset += bytes.len() as u64;
+        let mut next_offsets = matches!(s, Cow::Owned(_)).then(|| {
+            bytes
+                .iter()
+                .enumerate()
+                .filter(move |&(_, &byte)| delimiter::contains(terminators, byte))
+                .map(move |(pos, _)| base + pos as u64 + 1)
+        });
+        let mut rest: &str = &s;
+        while let Some(end) = find_terminator(rest) {
+            let raw = raw_segments.as_mut().and_then(Iterator::next);
+            let next_offset = match &mut next_offsets {
+                Some(offsets) => offsets.next().unwrap_or(self.stream_offset),
+                None => base + (s.len() - rest.len() + end) as u64 + 1,
+            };
+            let terminator = rest.as_bytes()[end];
+            if terminator == b'\n' && end == 0 && std::mem::take(&mut self.after_cr) {
+                // The '\n' of a "\r\n" whose '\r' already ended the line.
+                self.line_offset = next_offset;
+                rest = &rest[1..];
+                continue;
+            }
+            self.after_cr = terminator == b'\r';
+            if self.current_line.is_empty() {
+                self.next_offset = Some(self.line_offset);
+                self.push_line_raw(rest[..end].to_string(), raw.map(<[u8]>::to_vec), 0);
+            } else {
+                self.current_line.push_str(&rest[..end]);
+                if let Some(raw) = raw {
+                    self.current_raw.extend_from_slice(raw);
+                }
+                self.complete_line();
+            }
+            self.line_offset = next_offset;
+            rest = &rest[end + 1..];
+        }
+        if !rest.is_empty() {
+            self.after_cr = false;
+        }
+        self.current_line.push_str(rest);
+        if let Some(raw) = raw_segments.as_mut().and_then(Iterator::next) {
+            self.current_raw.extend_from_slice(raw);
+        }
+        self.truncate_pending();
+    }
+
+    fn complete_line(&mut self) {
+        self.next_offset = Some(self.line_offset);
+        self.line_offset = self.stream_offset;
+        let line = std::mem::take(&mut self.current_line);
+        let raw = self
+            .raw_bytes
+            .then(|| std::mem::take(&mut self.current_raw));
+        let truncated = std::mem::take(&mut self.pending_truncated);
+        self.push_line_raw(line, raw, truncated);
+    }
+}
+
+impl Clone for TestLogCollector {
+    /// Copies the lines, their metadata, the partial line and the
+    /// configuration. The copy does not share the original's files: it is
+    /// not persisted to the original's [`persist_to`](Self::persist_to) path
+    /// or write-ahead log, and lines archived to disk stay with the original.
+    /// Nor does it share the original's [`on_line`](Self::on_line) hooks or
+    /// [`subscribe`](Self::subscribe) receivers.
+    fn clone(&self) -> Self {
+        Self {
+            lines: self.lines.clone(),
+            meta: self.meta.clone(),
+            current_line: self.current_line.clone(),
+            current_raw: self.current_raw.clone(),
+            pending_truncated: self.pending_truncated,
+            max_line_len: self.max_line_len,
+            max_lines: self.max_lines,
+            timestamps: self.timestamps,
+            collapse_duplicates: self.collapse_duplicates,
+            paused: self.paused,
+            suppressed_count: self.suppressed_count,
+            sampling: self.sampling,
+            total_written: self.total_written,
+            sample_rng: self.sample_rng,
+            archive: None,
+            shared_lines: Default::default(),
+            section: self.section.clone(),
+            continuation: self.continuation.clone(),
+            utf8_carry: self.utf8_carry.clone(),
+            raw_bytes: self.raw_bytes,
+            newline_policy: self.newline_policy,
+            delimiters: self.delimiters,
+            after_cr: self.after_cr,
+            strict_utf8: self.strict_utf8,
+            scrubbers: self.scrubbers.clone(),
+            sources: self.sources.clone(),
+            target: self.target.clone(),
+            directives: self.directives.clone(),
+            allowed_targets: self.allowed_targets.clone(),
+            denied_targets: self.denied_targets.clone(),
+            unfiltered_count: self.unfiltered_count,
+            errors: self.errors.clone(),
+            index: self.index.clone(),
+            index_threshold: self.index_threshold,
+            write_calls: self.write_calls.clone(),
+            max_write: self.max_write,
+            flush_count: self.flush_count,
+            expectations: self.expectations.clone(),
+            evaluate_and_drop: self.evaluate_and_drop,
+            catalog: self.catalog.clone(),
+            reclassifications: self.reclassifications.clone(),
+            max_level: self.max_level,
+            persist_path: None,
+            write_ahead: None,
+            hooks: Vec::new(),
+            next_hook: 0,
+            heuristic_levels: self.heuristic_levels,
+            auto_flush_on_drop: self.auto_flush_on_drop,
+            record_per_write: self.record_per_write,
+            terminal: self.terminal,
+            stream_offset: self.stream_offset,
+            line_offset: self.line_offset,
+            next_offset: None,
+        }
+    }
+}
+
 impl Default for TestLogCollector {
     fn default() -> Self {
         Self::new()
@@ -151,22 +1266,80 @@ impl Default for TestLogCollector {
 
 impl Write for TestLogCollector {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        let s = String::from_utf8_lossy(buf);
-        for ch in s.chars() {
-            if ch == '\n' {
-                self.lines.push(self.current_line.clone());
-                self.current_line.clear();
-            } else {
-                self.current_line.push(ch);
+        if let Some(calls) = &mut self.write_calls {
+            calls.push(buf.to_vec());
+        }
+        let buf = &buf[..self.max_write.map_or(buf.len(), |max| buf.len().min(max))];
+        let carried = self.utf8_carry.len();
+        let joined;
+        let bytes = if carried == 0 {
+            buf
+        } else {
+            joined = [std::mem::take(&mut self.utf8_carry).as_slice(), buf].concat();
+            &joined[..]
+        };
+        let complete = bytes.len() - incomplete_utf8_suffix(bytes);
+        if self.strict_utf8 {
+            if let Err(error) = std::str::from_utf8(&bytes[..complete]) {
+                self.utf8_carry = bytes[..carried].to_vec();
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "invalid UTF-8 at byte {}",
+                        error.valid_up_to().saturating_sub(carried)
+                    ),
+                ));
             }
         }
+        self.collect_bytes(&bytes[..complete]);
+        self.utf8_carry.extend_from_slice(&bytes[complete..]);
+        if self.record_per_write && !self.current_line.is_empty() {
+            self.complete_line();
+        }
+        if let Some(write_ahead) = &mut self.write_ahead {
+            write_ahead.take_error()?;
+        }
         Ok(buf.len())
     }
 
+    /// Collects all of `bufs` as a single write, so a character split
+    /// between two buffers is decoded correctly and the write spy records
+    /// one call.
+    fn write_vectored(&mut self, bufs: &[io::IoSlice<'_>]) -> io::Result<usize> {
+        let mut non_empty = bufs.iter().filter(|buf| !buf.is_empty());
+        match (non_empty.next(), non_empty.next()) {
+            (None, _) => self.write(&[]),
+            (Some(only), None) => self.write(only),
+            _ => {
+                let mut joined = Vec::with_capacity(bufs.iter().map(|buf| buf.len()).sum());
+                for buf in bufs {
+                    joined.extend_from_slice(buf);
+                }
+                self.write(&joined)
+            }
+        }
+    }
+
+    /// Completes the partial line. An incomplete UTF-8 sequence left by the
+    /// last write is decoded as `U+FFFD`, or, in strict mode, discarded and
+    /// reported as an `InvalidData` error after the line is completed.
     fn flush(&mut self) -> io::Result<()> {
+        self.flush_count += 1;
+        let carry = std::mem::take(&mut self.utf8_carry);
+        if !self.strict_utf8 {
+            self.collect_bytes(&carry);
+        }
         if !self.current_line.is_empty() {
-            self.lines.push(self.current_line.clone());
-            self.current_line.clear();
+            self.complete_line();
+        }
+        if let Some(write_ahead) = &mut self.write_ahead {
+            write_ahead.flush()?;
+        }
+        if self.strict_utf8 && !carry.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "incomplete UTF-8 sequence at end of input",
+            ));
         }
         Ok(())
     }
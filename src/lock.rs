@@ -0,0 +1,62 @@
+use std::sync::MutexGuard;
+
+use crate::{SharedCollector, TestLogCollector};
+
+/// Accessors for a [`SharedCollector`] that never fail because of a panic.
+///
+/// A test thread panicking while it holds the lock poisons the mutex, after
+/// which every `.lock().unwrap()` fails with a `PoisonError` that hides the
+/// original panic. These accessors recover the collector instead and clear
+/// the poison, so later plain `lock()` calls succeed too.
+pub trait SharedCollectorExt {
+    /// Locks the collector, recovering it if the mutex was poisoned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::{SharedCollectorExt, TestLogCollector};
+    ///
+    /// let collector = TestLogCollector::new_shared();
+    /// let shared = collector.clone();
+    /// let _ = std::thread::spawn(move || {
+    ///     let mut guard = shared.lock().unwrap();
+    ///     writeln!(guard, "about to fail").unwrap();
+    ///     panic!("boom");
+    /// })
+    /// .join();
+    ///
+    /// assert!(collector.lock_logs().contains("about to fail"));
+    /// assert!(collector.lock().is_ok());
+    /// ```
+    fn lock_logs(&self) -> MutexGuard<'_, TestLogCollector>;
+
+    /// Runs `f` with the locked collector, recovering it if the mutex was
+    /// poisoned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::{SharedCollectorExt, TestLogCollector};
+    ///
+    /// let collector = TestLogCollector::new_shared();
+    /// collector.with_logs(|logs| writeln!(logs, "written").unwrap());
+    ///
+    /// assert_eq!(collector.with_logs(|logs| logs.count()), 1);
+    /// ```
+    fn with_logs<R>(&self, f: impl FnOnce(&mut TestLogCollector) -> R) -> R;
+}
+
+impl SharedCollectorExt for SharedCollector {
+    fn lock_logs(&self) -> MutexGuard<'_, TestLogCollector> {
+        self.lock().unwrap_or_else(|poisoned| {
+            self.clear_poison();
+            poisoned.into_inner()
+        })
+    }
+
+    fn with_logs<R>(&self, f: impl FnOnce(&mut TestLogCollector) -> R) -> R {
+        f(&mut self.lock_logs())
+    }
+}
@@ -0,0 +1,132 @@
+use std::fmt;
+use std::sync::Arc;
+
+use crate::search;
+
+/// A predicate over a single line, with a description for failure messages.
+///
+/// APIs that take `impl Into<Matcher>` accept a string slice, which matches
+/// lines containing it, or any of the constructors below.
+///
+/// # Examples
+///
+/// ```
+/// use test_log_collector::Matcher;
+///
+/// let matcher = Matcher::starts_with("ERROR");
+/// assert!(matcher.matches("ERROR disk full"));
+/// assert!(!matcher.matches("WARN disk almost full"));
+///
+/// let matcher: Matcher = "disk".into();
+/// assert!(matcher.matches("WARN disk almost full"));
+/// ```
+#[derive(Clone)]
+pub struct Matcher {
+    description: String,
+    predicate: Arc<dyn Fn(&str) -> bool + Send + Sync>,
+}
+
+impl Matcher {
+    /// Creates a matcher from a description and a predicate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::Matcher;
+    ///
+    /// let matcher = Matcher::new("longer than 10 bytes", |line| line.len() > 10);
+    /// assert!(matcher.matches("a long enough line"));
+    /// assert_eq!(matcher.to_string(), "longer than 10 bytes");
+    /// ```
+    pub fn new<F>(description: impl Into<String>, predicate: F) -> Self
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        Self {
+            description: description.into(),
+            predicate: Arc::new(predicate),
+        }
+    }
+
+    /// Matches lines containing `pattern`.
+    pub fn contains(pattern: impl Into<String>) -> Self {
+        let pattern = pattern.into();
+        Self::new(format!("contains {:?}", pattern), move |line| {
+            line.contains(&pattern)
+        })
+    }
+
+    /// Matches lines containing `pattern`, ignoring case.
+    pub fn contains_ignore_case(pattern: impl Into<String>) -> Self {
+        let pattern = pattern.into();
+        let lowercase = pattern.to_lowercase();
+        let description = format!("contains {:?} ignoring case", pattern);
+        Self::new(description, move |line| {
+            search::contains_ignore_case(line, &pattern, &lowercase)
+        })
+    }
+
+    /// Matches lines equal to `expected`.
+    pub fn exact(expected: impl Into<String>) -> Self {
+        let expected = expected.into();
+        Self::new(format!("equals {:?}", expected), move |line| {
+            line == expected
+        })
+    }
+
+    /// Matches lines starting with `prefix`.
+    pub fn starts_with(prefix: impl Into<String>) -> Self {
+        let prefix = prefix.into();
+        Self::new(format!("starts with {:?}", prefix), move |line| {
+            line.starts_with(&prefix)
+        })
+    }
+
+    /// Matches lines ending with `suffix`.
+    pub fn ends_with(suffix: impl Into<String>) -> Self {
+        let suffix = suffix.into();
+        Self::new(format!("ends with {:?}", suffix), move |line| {
+            line.ends_with(&suffix)
+        })
+    }
+
+    /// Returns `true` if `line` matches.
+    pub fn matches(&self, line: &str) -> bool {
+        (self.predicate)(line)
+    }
+
+    /// Returns the description used in failure messages.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+}
+
+impl From<&str> for Matcher {
+    fn from(pattern: &str) -> Self {
+        Self::contains(pattern)
+    }
+}
+
+impl From<String> for Matcher {
+    fn from(pattern: String) -> Self {
+        Self::contains(pattern)
+    }
+}
+
+impl From<&String> for Matcher {
+    fn from(pattern: &String) -> Self {
+        Self::contains(pattern.as_str())
+    }
+}
+
+impl fmt::Display for Matcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.description)
+    }
+}
+
+impl fmt::Debug for Matcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Matcher").field(&self.description).finish()
+    }
+}
@@ -0,0 +1,40 @@
+use crate::TestLogCollector;
+
+/// How carriage returns in written output are treated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NewlinePolicy {
+    /// Only `\n` ends a line; `\r` is kept as written.
+    #[default]
+    Keep,
+    /// Only `\n` ends a line, and one `\r` at the end of a line is removed,
+    /// so `\r\n` output collects like `\n` output.
+    StripTrailingCr,
+    /// `\n`, `\r` and `\r\n` each end a line, including a `\r\n` split
+    /// between two writes.
+    CrTerminates,
+}
+
+impl TestLogCollector {
+    /// Sets how carriage returns are treated, e.g. for output written with
+    /// Windows line endings or progress bars that redraw with `\r`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::{NewlinePolicy, TestLogCollector};
+    ///
+    /// let mut collector =
+    ///     TestLogCollector::new().with_newline_policy(NewlinePolicy::StripTrailingCr);
+    /// write!(collector, "windows\r\nline endings\r\n").unwrap();
+    /// assert_eq!(collector, ["windows", "line endings"]);
+    ///
+    /// let mut collector = TestLogCollector::new().with_newline_policy(NewlinePolicy::CrTerminates);
+    /// write!(collector, "10%\r50%\r100%\r\ndone\n").unwrap();
+    /// assert_eq!(collector, ["10%", "50%", "100%", "done"]);
+    /// ```
+    pub fn with_newline_policy(mut self, policy: NewlinePolicy) -> Self {
+        self.newline_policy = policy;
+        self
+    }
+}
@@ -0,0 +1,346 @@
+use std::fmt;
+use std::ops::BitOr;
+
+use crate::TestLogCollector;
+
+/// A built-in normalizer for content that changes from run to run.
+///
+/// Combine presets with `|` and enable them with
+/// [`TestLogCollector::with_normalization`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Preset {
+    /// RFC 3339 timestamps, including the `tracing` fmt default
+    /// (`2024-01-15T10:30:00.123456Z`), and syslog timestamps
+    /// (`Jan  5 14:03:22`), replaced by `<TIMESTAMP>`.
+    Timestamps,
+    /// UUIDs in their hyphenated form, replaced by `<UUID>`.
+    Uuids,
+    /// Durations such as `12.3ms`, `250µs` or `3s`, replaced by
+    /// `<DURATION>`.
+    Durations,
+    /// Memory addresses such as `0x7ffd5e8a1b20`, with at least eight hex
+    /// digits, replaced by `<ADDR>`.
+    Addresses,
+}
+
+impl Preset {
+    const ALL: [Preset; 4] = [
+        Preset::Timestamps,
+        Preset::Uuids,
+        Preset::Addresses,
+        Preset::Durations,
+    ];
+
+    fn bit(self) -> u8 {
+        1 << self as u8
+    }
+
+    fn placeholder(self) -> &'static str {
+        match self {
+            Preset::Timestamps => "<TIMESTAMP>",
+            Preset::Uuids => "<UUID>",
+            Preset::Durations => "<DURATION>",
+            Preset::Addresses => "<ADDR>",
+        }
+    }
+
+    fn match_at(self, bytes: &[u8]) -> Option<usize> {
+        match self {
+            Preset::Timestamps => rfc3339(bytes).or_else(|| syslog(bytes)),
+            Preset::Uuids => uuid(bytes),
+            Preset::Durations => duration(bytes),
+            Preset::Addresses => address(bytes),
+        }
+    }
+}
+
+/// A set of [`Preset`]s, built with `|`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Presets(u8);
+
+impl Presets {
+    /// Every preset.
+    pub const ALL: Presets = Presets(0b1111);
+
+    /// Returns `true` if `preset` is in the set.
+    pub fn contains(self, preset: Preset) -> bool {
+        self.0 & preset.bit() != 0
+    }
+
+    /// Replaces the content matched by the presets in the set.
+    ///
+    /// Timestamps are replaced first, then UUIDs, addresses and durations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::{Preset, Presets};
+    ///
+    /// let line = "2024-01-15T10:30:00.123Z request 3f2b8c1e-0d4a-4b6e-9f1a-2c3d4e5f6a7b took 12.3ms";
+    /// assert_eq!(
+    ///     Presets::ALL.normalize(line),
+    ///     "<TIMESTAMP> request <UUID> took <DURATION>"
+    /// );
+    /// assert_eq!(Presets::from(Preset::Durations).normalize("took 3s"), "took <DURATION>");
+    /// ```
+    pub fn normalize(self, line: &str) -> String {
+        let mut line = line.to_string();
+        for preset in Preset::ALL {
+            if self.contains(preset) {
+                line = replace_matches(&line, preset);
+            }
+        }
+        line
+    }
+}
+
+impl From<Preset> for Presets {
+    fn from(preset: Preset) -> Self {
+        Presets(preset.bit())
+    }
+}
+
+impl BitOr for Preset {
+    type Output = Presets;
+
+    fn bitor(self, rhs: Preset) -> Presets {
+        Presets(self.bit() | rhs.bit())
+    }
+}
+
+impl BitOr<Preset> for Presets {
+    type Output = Presets;
+
+    fn bitor(self, rhs: Preset) -> Presets {
+        Presets(self.0 | rhs.bit())
+    }
+}
+
+impl BitOr for Presets {
+    type Output = Presets;
+
+    fn bitor(self, rhs: Presets) -> Presets {
+        Presets(self.0 | rhs.0)
+    }
+}
+
+impl fmt::Display for Presets {
+    /// Lists the presets, e.g. `timestamps, UUIDs`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<&str> = Preset::ALL
+            .into_iter()
+            .filter(|&preset| self.contains(preset))
+            .map(|preset| match preset {
+                Preset::Timestamps => "timestamps",
+                Preset::Uuids => "UUIDs",
+                Preset::Durations => "durations",
+                Preset::Addresses => "addresses",
+            })
+            .collect();
+        f.write_str(&names.join(", "))
+    }
+}
+
+fn is_word(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_'
+}
+
+/// Replaces every match of `preset` that starts and ends at a word
+/// boundary.
+fn replace_matches(line: &str, preset: Preset) -> String {
+    let bytes = line.as_bytes();
+    let mut normalized = String::with_capacity(line.len());
+    let (mut copied, mut i) = (0, 0);
+    while i < bytes.len() {
+        if i == 0 || !is_word(bytes[i - 1]) {
+            if let Some(len) = preset.match_at(&bytes[i..]) {
+                if bytes.get(i + len).is_none_or(|&byte| !is_word(byte)) {
+                    normalized.push_str(&line[copied..i]);
+                    normalized.push_str(preset.placeholder());
+                    i += len;
+                    copied = i;
+                    continue;
+                }
+            }
+        }
+        i += 1;
+    }
+    normalized.push_str(&line[copied..]);
+    normalized
+}
+
+/// Matches fixed-shape ASCII patterns one piece at a time.
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, pred: impl Fn(u8) -> bool) -> Option<u8> {
+        let byte = self.peek().filter(|&byte| pred(byte))?;
+        self.pos += 1;
+        Some(byte)
+    }
+
+    fn literal(&mut self, literal: &[u8]) -> Option<()> {
+        if self.bytes[self.pos..].starts_with(literal) {
+            self.pos += literal.len();
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    fn exactly(&mut self, n: usize, pred: impl Fn(u8) -> bool) -> Option<()> {
+        for _ in 0..n {
+            self.eat(&pred)?;
+        }
+        Some(())
+    }
+
+    fn many(&mut self, pred: impl Fn(u8) -> bool) -> usize {
+        let start = self.pos;
+        while self.eat(&pred).is_some() {}
+        self.pos - start
+    }
+}
+
+fn digit(byte: u8) -> bool {
+    byte.is_ascii_digit()
+}
+
+fn hex(byte: u8) -> bool {
+    byte.is_ascii_hexdigit()
+}
+
+/// `HH:MM:SS`.
+fn clock(cursor: &mut Cursor<'_>) -> Option<()> {
+    cursor.exactly(2, digit)?;
+    cursor.eat(|byte| byte == b':')?;
+    cursor.exactly(2, digit)?;
+    cursor.eat(|byte| byte == b':')?;
+    cursor.exactly(2, digit)
+}
+
+/// `YYYY-MM-DD[T ]HH:MM:SS[.frac][Z|±HH:MM]`.
+fn rfc3339(bytes: &[u8]) -> Option<usize> {
+    let mut cursor = Cursor::new(bytes);
+    cursor.exactly(4, digit)?;
+    cursor.eat(|byte| byte == b'-')?;
+    cursor.exactly(2, digit)?;
+    cursor.eat(|byte| byte == b'-')?;
+    cursor.exactly(2, digit)?;
+    cursor.eat(|byte| matches!(byte, b'T' | b't' | b' '))?;
+    clock(&mut cursor)?;
+    let before_fraction = cursor.pos;
+    if cursor.eat(|byte| matches!(byte, b'.' | b',')).is_some() && cursor.many(digit) == 0 {
+        cursor.pos = before_fraction;
+    }
+    if cursor.eat(|byte| matches!(byte, b'Z' | b'z')).is_none() {
+        let before_offset = cursor.pos;
+        let offset = cursor
+            .eat(|byte| matches!(byte, b'+' | b'-'))
+            .and_then(|_| cursor.exactly(2, digit))
+            .and_then(|_| {
+                cursor.eat(|byte| byte == b':');
+                cursor.exactly(2, digit)
+            });
+        if offset.is_none() {
+            cursor.pos = before_offset;
+        }
+    }
+    Some(cursor.pos)
+}
+
+/// `Mmm dd HH:MM:SS`, with the day padded by a space or a zero.
+pub(crate) fn syslog(bytes: &[u8]) -> Option<usize> {
+    const MONTHS: [&[u8]; 12] = [
+        b"Jan", b"Feb", b"Mar", b"Apr", b"May", b"Jun", b"Jul", b"Aug", b"Sep", b"Oct", b"Nov",
+        b"Dec",
+    ];
+    let mut cursor = Cursor::new(bytes);
+    MONTHS.iter().find_map(|month| cursor.literal(month))?;
+    cursor.eat(|byte| byte == b' ')?;
+    cursor.eat(|byte| byte == b' ' || digit(byte))?;
+    cursor.eat(digit)?;
+    cursor.eat(|byte| byte == b' ')?;
+    clock(&mut cursor)?;
+    Some(cursor.pos)
+}
+
+/// `8-4-4-4-12` hex digits.
+fn uuid(bytes: &[u8]) -> Option<usize> {
+    let mut cursor = Cursor::new(bytes);
+    for (i, len) in [8, 4, 4, 4, 12].into_iter().enumerate() {
+        if i > 0 {
+            cursor.eat(|byte| byte == b'-')?;
+        }
+        cursor.exactly(len, hex)?;
+    }
+    Some(cursor.pos)
+}
+
+/// `0x` followed by at least eight hex digits.
+fn address(bytes: &[u8]) -> Option<usize> {
+    let mut cursor = Cursor::new(bytes);
+    cursor.literal(b"0x")?;
+    (cursor.many(hex) >= 8).then_some(cursor.pos)
+}
+
+/// A decimal number followed directly by a time unit.
+fn duration(bytes: &[u8]) -> Option<usize> {
+    const UNITS: [&str; 8] = ["min", "ms", "ns", "us", "µs", "s", "m", "h"];
+    let mut cursor = Cursor::new(bytes);
+    if cursor.many(digit) == 0 {
+        return None;
+    }
+    let before_fraction = cursor.pos;
+    if cursor.eat(|byte| byte == b'.').is_some() && cursor.many(digit) == 0 {
+        cursor.pos = before_fraction;
+    }
+    UNITS
+        .iter()
+        .find_map(|unit| cursor.literal(unit.as_bytes()))?;
+    Some(cursor.pos)
+}
+
+impl TestLogCollector {
+    /// Replaces content that changes from run to run, such as timestamps
+    /// and UUIDs, with placeholders before each line is stored, so snapshot
+    /// and equality assertions stop flaking. See [`Preset`] for what each
+    /// preset matches.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::{Preset, TestLogCollector};
+    ///
+    /// let mut collector =
+    ///     TestLogCollector::new().with_normalization(Preset::Timestamps | Preset::Uuids);
+    /// writeln!(
+    ///     collector,
+    ///     "2024-01-15T10:30:00Z session 3f2b8c1e-0d4a-4b6e-9f1a-2c3d4e5f6a7b opened"
+    /// )
+    /// .unwrap();
+    /// writeln!(collector, "Jan  5 14:03:22 host sshd: accepted").unwrap();
+    ///
+    /// assert_eq!(
+    ///     collector,
+    ///     ["<TIMESTAMP> session <UUID> opened", "<TIMESTAMP> host sshd: accepted"]
+    /// );
+    /// ```
+    pub fn with_normalization(self, presets: impl Into<Presets>) -> Self {
+        let presets = presets.into();
+        let name = format!("normalize {}", presets);
+        self.with_named_scrubber(name, move |line| presets.normalize(line))
+    }
+}
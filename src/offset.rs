@@ -0,0 +1,60 @@
+use crate::TestLogCollector;
+
+impl TestLogCollector {
+    /// Returns the byte offset at which the line at `index` starts in
+    /// everything written to the collector since it was created or
+    /// [`clear`](Self::clear)ed.
+    ///
+    /// Offsets count the bytes as written, before decoding, so they can be
+    /// used to find a line in a copy of the stream. Returns `None` if there
+    /// is no line at `index` or the line was added without being written,
+    /// e.g. through `Extend`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// collector.write_all(b"first\r\nsecond\n").unwrap();
+    /// collector.extend(["added".to_string()]);
+    ///
+    /// assert_eq!(collector.byte_offset(0), Some(0));
+    /// assert_eq!(collector.byte_offset(1), Some(7));
+    /// assert_eq!(collector.byte_offset(2), None);
+    /// ```
+    pub fn byte_offset(&self, index: usize) -> Option<u64> {
+        self.meta.get(index)?.offset
+    }
+
+    /// Returns the line at `index` as it was before scrubbing, such as
+    /// [`with_normalization`](Self::with_normalization) or ANSI stripping,
+    /// for reporting the exact original content when an assertion on the
+    /// normalized text fails.
+    ///
+    /// Lines that no scrubber changed return their collected text. Lines
+    /// cut by [`with_max_line_len`](Self::with_max_line_len) are returned
+    /// truncated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::{Presets, TestLogCollector};
+    ///
+    /// let mut collector = TestLogCollector::new().with_normalization(Presets::ALL);
+    /// writeln!(collector, "took 1.5ms").unwrap();
+    /// writeln!(collector, "done").unwrap();
+    ///
+    /// assert_eq!(collector, ["took <DURATION>", "done"]);
+    /// assert_eq!(collector.raw_line(0), Some("took 1.5ms"));
+    /// assert_eq!(collector.raw_line(1), Some("done"));
+    /// ```
+    pub fn raw_line(&self, index: usize) -> Option<&str> {
+        let meta = self.meta.get(index)?;
+        meta.original
+            .as_deref()
+            .or(self.lines.get(index).map(String::as_str))
+    }
+}
@@ -0,0 +1,81 @@
+use std::io::Write;
+use std::process::Output;
+
+use crate::TestLogCollector;
+
+impl TestLogCollector {
+    /// Appends the stdout and then the stderr of a finished process, e.g. a
+    /// `std::process::Command` or `assert_cmd` run, so its output can be
+    /// asserted with the same API as in-process logs.
+    ///
+    /// A missing trailing newline at the end of either stream is completed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::process::{ExitStatus, Output};
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let output = Output {
+    ///     status: ExitStatus::default(),
+    ///     stdout: b"started\nready".to_vec(),
+    ///     stderr: b"WARN slow disk\n".to_vec(),
+    /// };
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// collector.extend_from_output(&output);
+    /// assert_eq!(collector.clone_lines(), vec!["started", "ready", "WARN slow disk"]);
+    /// ```
+    pub fn extend_from_output(&mut self, output: &Output) {
+        for stream in [&output.stdout, &output.stderr] {
+            let _ = self.write_all(stream);
+            if !self.current_line.is_empty() {
+                self.complete_line();
+            }
+        }
+    }
+}
+
+impl From<&Output> for TestLogCollector {
+    /// Collects the stdout and then the stderr of a finished process.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::process::{ExitStatus, Output};
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let output = Output {
+    ///     status: ExitStatus::default(),
+    ///     stdout: b"done\n".to_vec(),
+    ///     stderr: Vec::new(),
+    /// };
+    /// assert!(TestLogCollector::from(&output).contains("done"));
+    /// ```
+    fn from(output: &Output) -> Self {
+        let mut collector = TestLogCollector::new();
+        collector.extend_from_output(output);
+        collector
+    }
+}
+
+impl From<Output> for TestLogCollector {
+    /// Collects the stdout and then the stderr of a finished process.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::process::{ExitStatus, Output};
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let output = Output {
+    ///     status: ExitStatus::default(),
+    ///     stdout: b"done\n".to_vec(),
+    ///     stderr: Vec::new(),
+    /// };
+    /// assert_eq!(TestLogCollector::from(output).count(), 1);
+    /// ```
+    fn from(output: Output) -> Self {
+        Self::from(&output)
+    }
+}
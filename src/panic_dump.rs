@@ -0,0 +1,120 @@
+use std::panic;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, Once, TryLockError};
+use std::thread::{self, ThreadId};
+
+use crate::TestLogCollector;
+
+struct Registration {
+    id: u64,
+    thread: ThreadId,
+    collector: Arc<Mutex<TestLogCollector>>,
+}
+
+static REGISTRATIONS: Mutex<Vec<Registration>> = Mutex::new(Vec::new());
+static NEXT_REGISTRATION: AtomicU64 = AtomicU64::new(0);
+static INSTALL_HOOK: Once = Once::new();
+
+/// Keeps a collector registered for dumping while the current thread panics.
+///
+/// Returned by [`TestLogCollector::dump_on_panic`]. Dropping the guard
+/// unregisters the collector.
+#[must_use = "the collector is only dumped while the guard is alive"]
+pub struct PanicDumpGuard {
+    id: u64,
+}
+
+impl Drop for PanicDumpGuard {
+    fn drop(&mut self) {
+        lock(&REGISTRATIONS).retain(|registration| registration.id != self.id);
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn install_hook() {
+    INSTALL_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            dump_for_current_thread();
+            previous(info);
+        }));
+    });
+}
+
+fn dump_for_current_thread() {
+    let thread = thread::current().id();
+    let collectors: Vec<_> = lock(&REGISTRATIONS)
+        .iter()
+        .filter(|registration| registration.thread == thread)
+        .map(|registration| registration.collector.clone())
+        .collect();
+
+    for collector in collectors {
+        match collector.try_lock() {
+            Ok(collector) => print_lines(&collector),
+            Err(TryLockError::Poisoned(poisoned)) => print_lines(&poisoned.into_inner()),
+            Err(TryLockError::WouldBlock) => {
+                eprintln!("---- captured logs unavailable: collector is locked ----");
+            }
+        }
+    }
+}
+
+fn print_lines(collector: &TestLogCollector) {
+    eprintln!("---- captured logs ({} lines) ----", collector.count());
+    let mut section = None;
+    for (index, line) in collector.lines().iter().enumerate() {
+        let line_section = collector.line_section(index);
+        if line_section != section {
+            eprintln!("---- section: {} ----", line_section.unwrap_or("(none)"));
+            section = line_section;
+        }
+        eprintln!("{}", line);
+    }
+    if !collector.current_line.is_empty() {
+        eprintln!("{}", collector.current_line);
+    }
+    eprintln!("---- end of captured logs ----");
+}
+
+impl TestLogCollector {
+    /// Prints the collected lines to stderr if the current thread panics
+    /// while the returned guard is alive.
+    ///
+    /// Nothing is printed when the test passes. Under libtest, stderr output
+    /// is itself captured and only shown for failing tests, so this gives
+    /// "quiet on pass, verbose on fail" behavior for logs routed through the
+    /// collector. Any pending partial line is printed too.
+    ///
+    /// The first call installs a process-wide panic hook that chains to the
+    /// previously installed hook and stays in place; guards only register and
+    /// unregister collectors for their thread, which keeps tests running in
+    /// parallel from interfering with each other.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let log_collector = TestLogCollector::new_shared();
+    /// let _dump = TestLogCollector::dump_on_panic(&log_collector);
+    ///
+    /// writeln!(log_collector.lock().unwrap(), "only shown if this test fails").unwrap();
+    /// ```
+    pub fn dump_on_panic(collector: &Arc<Mutex<Self>>) -> PanicDumpGuard {
+        install_hook();
+        let id = NEXT_REGISTRATION.fetch_add(1, Ordering::Relaxed);
+        lock(&REGISTRATIONS).push(Registration {
+            id,
+            thread: thread::current().id(),
+            collector: collector.clone(),
+        });
+        PanicDumpGuard { id }
+    }
+}
@@ -0,0 +1,75 @@
+use crate::TestLogCollector;
+
+impl TestLogCollector {
+    /// Stops recording lines until [`resume`](Self::resume), e.g. during a
+    /// noisy warm-up phase. Suppressed lines are counted but not stored, and
+    /// expectations do not see them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// collector.pause();
+    /// for i in 0..1_000 {
+    ///     writeln!(collector, "warming cache {}", i).unwrap();
+    /// }
+    /// collector.resume();
+    /// writeln!(collector, "cache hit").unwrap();
+    ///
+    /// assert_eq!(collector, ["cache hit"]);
+    /// assert_eq!(collector.suppressed_count(), 1_000);
+    /// ```
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Resumes recording after [`pause`](Self::pause).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// collector.pause();
+    /// collector.resume();
+    /// writeln!(collector, "recorded").unwrap();
+    ///
+    /// assert_eq!(collector.count(), 1);
+    /// ```
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Returns `true` while recording is paused.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// collector.pause();
+    /// assert!(collector.is_paused());
+    /// ```
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Returns the number of lines dropped while paused.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// assert_eq!(TestLogCollector::new().suppressed_count(), 0);
+    /// ```
+    pub fn suppressed_count(&self) -> usize {
+        self.suppressed_count
+    }
+}
@@ -0,0 +1,114 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::TestLogCollector;
+
+/// Directory that collectors write their logs to when dropped.
+const DIR_ENV_VAR: &str = "TEST_LOG_COLLECTOR_DIR";
+
+static NEXT_FILE: AtomicU64 = AtomicU64::new(0);
+
+/// Returns a file name unique within the directory, derived from the
+/// current thread's name, which libtest sets to the test's path.
+fn unique_file_name() -> String {
+    let thread = std::thread::current();
+    let name: String = thread
+        .name()
+        .unwrap_or("collector")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!(
+        "{}-{}-{}.log",
+        name,
+        std::process::id(),
+        NEXT_FILE.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+impl TestLogCollector {
+    /// Writes every collected line, including an unfinished partial line, to
+    /// `path` when the collector is dropped.
+    ///
+    /// Without this, a collector is persisted automatically if the
+    /// `TEST_LOG_COLLECTOR_DIR` environment variable is set: non-empty
+    /// captures are written to a uniquely named file in that directory,
+    /// named after the dropping thread (the test). Use this in CI to keep
+    /// the logs of flaky failures as artifacts.
+    ///
+    /// Errors writing the file are reported on stderr, never as panics.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let path = std::env::temp_dir().join("test_log_collector_persist_doc.log");
+    /// {
+    ///     let mut collector = TestLogCollector::new().persist_to(&path);
+    ///     writeln!(collector, "kept after drop").unwrap();
+    /// }
+    /// assert_eq!(std::fs::read_to_string(&path).unwrap(), "kept after drop\n");
+    /// ```
+    pub fn persist_to(mut self, path: impl Into<PathBuf>) -> Self {
+        self.persist_path = Some(path.into());
+        self
+    }
+
+    fn persist(&self, path: &Path) {
+        let result = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::File::create(path))
+            .and_then(|mut file| {
+                for line in self.all_lines()? {
+                    writeln!(file, "{}", line?)?;
+                }
+                if !self.current_line.is_empty() {
+                    writeln!(file, "{}", self.current_line)?;
+                }
+                file.flush()
+            });
+        if let Err(error) = result {
+            eprintln!(
+                "test_log_collector: failed to persist logs to {}: {}",
+                path.display(),
+                error
+            );
+        }
+    }
+}
+
+impl TestLogCollector {
+    /// Writes the log where it is persisted when dropped, if anywhere, and
+    /// turns persistence off. Used by consuming methods that move the lines
+    /// out before the collector is dropped.
+    pub(crate) fn persist_now(&mut self) {
+        if let Some(path) = self.persist_path.take() {
+            self.persist(&path);
+        } else if !self.lines.is_empty() || !self.current_line.is_empty() {
+            if let Some(dir) = std::env::var_os(DIR_ENV_VAR) {
+                self.persist(&Path::new(&dir).join(unique_file_name()));
+            }
+        }
+    }
+}
+
+impl Drop for TestLogCollector {
+    fn drop(&mut self) {
+        if self.auto_flush_on_drop {
+            let _ = self.flush();
+        }
+        self.persist_now();
+    }
+}
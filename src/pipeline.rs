@@ -0,0 +1,264 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::provenance::Label;
+use crate::{SharedCollector, TestLogCollector};
+
+enum Message {
+    Opened(u64, String),
+    Data(u64, Vec<u8>),
+    Flush(u64),
+    Closed(u64),
+}
+
+type Producers = Arc<Mutex<Vec<(u64, String)>>>;
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// A collector fed through a channel by any number of producer writers.
+///
+/// Each [`PipelineWriter`] sends its bytes to a background drainer thread
+/// that assembles them into lines, so producers never block on the
+/// collector's lock and lines from different producers never interleave.
+/// Call [`shutdown`](Pipeline::shutdown) at the end of the test to wait for
+/// everything in flight to be collected.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Write;
+/// use std::thread;
+/// use std::time::Duration;
+/// use test_log_collector::Pipeline;
+///
+/// let mut pipeline = Pipeline::new();
+/// let mut writer = pipeline.writer("worker");
+/// thread::spawn(move || writeln!(writer, "from the worker").unwrap());
+///
+/// let collector = pipeline.shutdown(Duration::from_secs(5)).unwrap();
+/// assert_eq!(collector.clone_lines(), vec!["from the worker"]);
+/// ```
+pub struct Pipeline {
+    collector: SharedCollector,
+    sender: Sender<Message>,
+    drainer: JoinHandle<()>,
+    done: Receiver<()>,
+    producers: Producers,
+    next_producer: u64,
+}
+
+impl Pipeline {
+    /// Creates a pipeline feeding a new empty collector.
+    pub fn new() -> Self {
+        Self::with_collector(TestLogCollector::new())
+    }
+
+    /// Creates a pipeline feeding `collector`.
+    pub fn with_collector(collector: TestLogCollector) -> Self {
+        let collector = Arc::new(Mutex::new(collector));
+        let (sender, receiver) = mpsc::channel();
+        let (done_sender, done) = mpsc::channel();
+        let drainer_collector = collector.clone();
+        let drainer = thread::Builder::new()
+            .name("test_log_collector pipeline".into())
+            .spawn(move || {
+                drain(receiver, &drainer_collector);
+                let _ = done_sender.send(());
+            })
+            .expect("failed to spawn pipeline drainer thread");
+        Self {
+            collector,
+            sender,
+            drainer,
+            done,
+            producers: Producers::default(),
+            next_producer: 0,
+        }
+    }
+
+    /// Creates a producer writer. `name` identifies it in a
+    /// [`ShutdownError`].
+    pub fn writer(&mut self, name: impl Into<String>) -> PipelineWriter {
+        self.next_producer += 1;
+        let id = self.next_producer;
+        let name = name.into();
+        let _ = self.sender.send(Message::Opened(id, name.clone()));
+        lock(&self.producers).push((id, name));
+        PipelineWriter {
+            id,
+            sender: self.sender.clone(),
+            producers: self.producers.clone(),
+        }
+    }
+
+    /// Returns a handle to the collector for inspecting it while producers
+    /// are still running.
+    ///
+    /// Lines still in flight in the channel are not visible yet.
+    pub fn collector(&self) -> SharedCollector {
+        self.collector.clone()
+    }
+
+    /// Waits up to `timeout` for every producer to be dropped and everything
+    /// they sent to be collected, then returns the collector.
+    ///
+    /// Partial lines left by producers are completed.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ShutdownError`] naming the producers still alive if they
+    /// are not all dropped within `timeout`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use test_log_collector::Pipeline;
+    ///
+    /// let mut pipeline = Pipeline::new();
+    /// let _leaked = pipeline.writer("background task");
+    ///
+    /// let Err(error) = pipeline.shutdown(Duration::from_millis(10)) else {
+    ///     panic!("shutdown should time out");
+    /// };
+    /// assert_eq!(error.alive_producers(), ["background task"]);
+    /// ```
+    pub fn shutdown(self, timeout: Duration) -> Result<TestLogCollector, ShutdownError> {
+        drop(self.sender);
+        if self.done.recv_timeout(timeout).is_err() {
+            let alive_producers = lock(&self.producers)
+                .iter()
+                .map(|(_, name)| name.clone())
+                .collect();
+            return Err(ShutdownError {
+                alive_producers,
+                collector: self.collector,
+            });
+        }
+        let _ = self.drainer.join();
+
+        let mut collector = match Arc::try_unwrap(self.collector) {
+            Ok(collector) => collector
+                .into_inner()
+                .unwrap_or_else(|poisoned| poisoned.into_inner()),
+            Err(shared) => std::mem::take(&mut *lock(&shared)),
+        };
+        let _ = collector.flush();
+        Ok(collector)
+    }
+}
+
+impl Default for Pipeline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn drain(receiver: Receiver<Message>, collector: &SharedCollector) {
+    let mut sources: HashMap<u64, Label> = HashMap::new();
+    let mut pending: HashMap<u64, Vec<u8>> = HashMap::new();
+    for message in receiver {
+        match message {
+            Message::Opened(id, name) => {
+                sources.insert(id, Label::from(format!("via pipeline producer {:?}", name)));
+            }
+            Message::Data(id, bytes) => {
+                let buffer = pending.entry(id).or_default();
+                buffer.extend_from_slice(&bytes);
+                if let Some(end) = buffer.iter().rposition(|&byte| byte == b'\n') {
+                    let _ = lock(collector).write_all_via(&sources[&id], &buffer[..=end]);
+                    buffer.drain(..=end);
+                }
+            }
+            Message::Flush(id) | Message::Closed(id) => {
+                if let Some(buffer) = pending.remove(&id).filter(|b| !b.is_empty()) {
+                    let mut collector = lock(collector);
+                    let _ = collector.write_all_via(&sources[&id], &buffer);
+                    let _ = collector.flush_via(&sources[&id]);
+                }
+                if matches!(message, Message::Closed(_)) {
+                    sources.remove(&id);
+                }
+            }
+        }
+    }
+}
+
+/// A producer feeding a [`Pipeline`].
+///
+/// Writes never block on the collector. Flushing completes the producer's
+/// partial line, as flushing a [`TestLogCollector`] does.
+pub struct PipelineWriter {
+    id: u64,
+    sender: Sender<Message>,
+    producers: Producers,
+}
+
+impl Write for PipelineWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.sender
+            .send(Message::Data(self.id, buf.to_vec()))
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "pipeline drainer stopped"))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.sender
+            .send(Message::Flush(self.id))
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "pipeline drainer stopped"))
+    }
+}
+
+impl Drop for PipelineWriter {
+    fn drop(&mut self) {
+        let _ = self.sender.send(Message::Closed(self.id));
+        lock(&self.producers).retain(|(id, _)| *id != self.id);
+    }
+}
+
+/// The error returned when a [`Pipeline`] does not shut down in time.
+pub struct ShutdownError {
+    alive_producers: Vec<String>,
+    collector: SharedCollector,
+}
+
+impl ShutdownError {
+    /// Returns the names of the producers that were still alive.
+    pub fn alive_producers(&self) -> &[String] {
+        &self.alive_producers
+    }
+
+    /// Returns the collector, holding everything collected so far.
+    pub fn collector(&self) -> SharedCollector {
+        self.collector.clone()
+    }
+}
+
+impl fmt::Debug for ShutdownError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShutdownError")
+            .field("alive_producers", &self.alive_producers)
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for ShutdownError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "pipeline shutdown timed out; producers still alive: {}",
+            self.alive_producers.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for ShutdownError {}
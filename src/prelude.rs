@@ -0,0 +1,22 @@
+//! The types most tests need, in one import.
+//!
+//! ```
+//! use test_log_collector::prelude::*;
+//!
+//! let mut collector = TestLogCollector::new();
+//! writeln!(collector, "ERROR disk full").unwrap();
+//! assert_eq!(collector.lines_at_level(Level::Error).len(), 1);
+//! assert!(Matcher::contains("disk").matches(collector.lines()[0].as_str()));
+//! ```
+//!
+//! `std::io::Write` is brought into scope anonymously so `write!` and
+//! `writeln!` work on collectors without clashing with your own imports.
+
+pub use std::io::Write as _;
+
+#[cfg(feature = "macros")]
+pub use crate::collect_logs;
+pub use crate::{
+    current_collector, CollectLogsGuard, CollectingWriter, Level, Matcher, Pipeline,
+    SharedCollector, SharedCollectorExt, Tee, TestLogCollector,
+};
@@ -0,0 +1,104 @@
+use std::io::{self, Write};
+use std::sync::Arc;
+
+use crate::TestLogCollector;
+
+/// An entry in a line's provenance trail, such as `via tee`, shared by
+/// every line it applies to instead of copied onto each.
+#[derive(Clone)]
+pub(crate) enum Label {
+    Static(&'static str),
+    Shared(Arc<str>),
+}
+
+impl Label {
+    pub(crate) fn as_str(&self) -> &str {
+        match self {
+            Label::Static(label) => label,
+            Label::Shared(label) => label,
+        }
+    }
+}
+
+impl From<String> for Label {
+    fn from(label: String) -> Self {
+        Label::Shared(label.into())
+    }
+}
+
+impl TestLogCollector {
+    /// Adds a scrubber like [`with_scrubber`](Self::with_scrubber), named
+    /// `name` in the [`provenance`](Self::provenance) of the lines it
+    /// changes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector =
+    ///     TestLogCollector::new().with_named_scrubber("strip port", |line| line.replace(":8080", ""));
+    /// writeln!(collector, "listening on localhost:8080").unwrap();
+    ///
+    /// assert_eq!(collector.provenance(0).unwrap(), ["scrubbed by strip port"]);
+    /// ```
+    pub fn with_named_scrubber<F>(mut self, name: impl Into<String>, scrubber: F) -> Self
+    where
+        F: Fn(&str) -> String + Send + Sync + 'static,
+    {
+        let label = Label::from(format!("scrubbed by {}", name.into()));
+        self.scrubbers.push((label, Arc::new(scrubber)));
+        self
+    }
+
+    /// Returns the trail of how the line at `index` reached the collector,
+    /// or `None` if there is no such line.
+    ///
+    /// The trail lists the handles the line passed through, outermost first
+    /// (`via tee`, `via pipeline producer "worker"`, `via stderr`, ...),
+    /// then each scrubber that changed it. A line is attributed to the
+    /// handles that completed it. Level reclassification is reported
+    /// separately by [`original_level`](Self::original_level).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{self, Write};
+    /// use test_log_collector::{Tee, TestLogCollector};
+    ///
+    /// let collector = TestLogCollector::new().with_replacement("hunter2", "***");
+    /// let mut tee = Tee::new(collector, io::sink());
+    /// writeln!(tee, "password=hunter2").unwrap();
+    ///
+    /// assert_eq!(
+    ///     tee.collector().provenance(0).unwrap(),
+    ///     ["via tee", "scrubbed by replace \"hunter2\" with \"***\""]
+    /// );
+    /// ```
+    pub fn provenance(&self, index: usize) -> Option<Vec<&str>> {
+        let meta = self.meta.get(index)?;
+        Some(meta.provenance.iter().map(Label::as_str).collect())
+    }
+
+    pub(crate) fn write_via(&mut self, source: &Label, buf: &[u8]) -> io::Result<usize> {
+        self.sources.push(source.clone());
+        let result = self.write(buf);
+        self.sources.pop();
+        result
+    }
+
+    pub(crate) fn write_all_via(&mut self, source: &Label, buf: &[u8]) -> io::Result<()> {
+        self.sources.push(source.clone());
+        let result = self.write_all(buf);
+        self.sources.pop();
+        result
+    }
+
+    pub(crate) fn flush_via(&mut self, source: &Label) -> io::Result<()> {
+        self.sources.push(source.clone());
+        let result = self.flush();
+        self.sources.pop();
+        result
+    }
+}
@@ -0,0 +1,103 @@
+use std::time::{Duration, SystemTime};
+
+use crate::expect::lines_noun;
+use crate::{Matcher, TestLogCollector};
+
+const WINDOW: Duration = Duration::from_secs(1);
+
+impl TestLogCollector {
+    fn matching_timestamps(&self, matcher: &Matcher) -> Vec<(usize, SystemTime)> {
+        let mut matches: Vec<(usize, SystemTime)> = self
+            .lines
+            .iter()
+            .zip(&self.meta)
+            .enumerate()
+            .filter(|(_, (line, _))| matcher.matches(line))
+            .map(|(index, (_, meta))| (index, meta.timestamp))
+            .collect();
+        // The wall clock may step backwards between lines.
+        matches.sort_by_key(|&(_, timestamp)| timestamp);
+        matches
+    }
+
+    /// Returns how many lines matching `pattern` were collected per second,
+    /// averaged over the time between the first and last match, or over one
+    /// second if they are closer together.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// for _ in 0..3 {
+    ///     writeln!(collector, "WARN retrying").unwrap();
+    /// }
+    ///
+    /// assert_eq!(collector.rate_per_sec("retrying"), 3.0);
+    /// assert_eq!(collector.rate_per_sec("connected"), 0.0);
+    /// ```
+    pub fn rate_per_sec(&self, pattern: impl Into<Matcher>) -> f64 {
+        let matches = self.matching_timestamps(&pattern.into());
+        let (Some((_, first)), Some((_, last))) = (matches.first(), matches.last()) else {
+            return 0.0;
+        };
+        let span = last.duration_since(*first).unwrap_or_default().max(WINDOW);
+        matches.len() as f64 / span.as_secs_f64()
+    }
+
+    /// Asserts that no one-second window contains more than `limit` lines
+    /// matching `pattern`, e.g. to check that a rate-limited logger caps
+    /// repeated warnings.
+    ///
+    /// Lines collected [`without_timestamps`](Self::without_timestamps) all
+    /// fall into the same window.
+    ///
+    /// # Panics
+    ///
+    /// Panics listing the lines of the first window over the limit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "WARN queue full").unwrap();
+    /// writeln!(collector, "WARN queue full (suppressed 99 similar)").unwrap();
+    ///
+    /// collector.assert_max_rate("queue full", 2);
+    /// ```
+    #[track_caller]
+    pub fn assert_max_rate(&self, pattern: impl Into<Matcher>, limit: usize) {
+        let matcher = pattern.into();
+        let matches = self.matching_timestamps(&matcher);
+        let mut start = 0;
+        for end in 0..matches.len() {
+            let end_time = matches[end].1;
+            while end_time
+                .duration_since(matches[start].1)
+                .unwrap_or_default()
+                >= WINDOW
+            {
+                start += 1;
+            }
+            if end - start + 1 > limit {
+                let window: Vec<String> = matches[start..=end]
+                    .iter()
+                    .map(|&(index, _)| format!("[{}] {}", index, self.lines[index]))
+                    .collect();
+                panic!(
+                    "expected at most {} {} that {} per second, found {} within one second:\n  {}",
+                    limit,
+                    lines_noun(limit),
+                    matcher,
+                    window.len(),
+                    window.join("\n  ")
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use std::io::{self, BufRead, Read};
+
+use crate::TestLogCollector;
+
+/// Reads a collector's content back as bytes.
+///
+/// Returned by [`TestLogCollector::as_reader`].
+pub struct LogReader<'a> {
+    collector: &'a TestLogCollector,
+    segment: usize,
+    offset: usize,
+}
+
+impl LogReader<'_> {
+    /// Even segments are lines, odd segments their newlines, and the last
+    /// segment is the unfinished partial line.
+    fn current_segment(&self) -> &[u8] {
+        let lines = &self.collector.lines;
+        let bytes: &[u8] = if self.segment == 2 * lines.len() {
+            self.collector.current_line.as_bytes()
+        } else if self.segment > 2 * lines.len() {
+            &[]
+        } else if self.segment % 2 == 1 {
+            b"\n"
+        } else {
+            lines[self.segment / 2].as_bytes()
+        };
+        &bytes[self.offset.min(bytes.len())..]
+    }
+}
+
+impl Read for LogReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl BufRead for LogReader<'_> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        let end = 2 * self.collector.lines.len();
+        while self.segment < end && self.current_segment().is_empty() {
+            self.segment += 1;
+            self.offset = 0;
+        }
+        Ok(self.current_segment())
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.offset += amt;
+    }
+}
+
+impl TestLogCollector {
+    /// Returns a reader over the collected content: every line followed by a
+    /// newline, then the unfinished partial line, if any, without one.
+    ///
+    /// Use this to feed captured logs into code under test that consumes a
+    /// reader, such as a log parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{BufRead, Write};
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// write!(collector, "first\nsecond\npartial").unwrap();
+    ///
+    /// let lines: Vec<String> = collector.as_reader().lines().map(Result::unwrap).collect();
+    /// assert_eq!(lines, vec!["first", "second", "partial"]);
+    /// ```
+    pub fn as_reader(&self) -> LogReader<'_> {
+        LogReader {
+            collector: self,
+            segment: 0,
+            offset: 0,
+        }
+    }
+}
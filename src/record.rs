@@ -0,0 +1,82 @@
+use std::sync::Arc;
+
+use crate::TestLogCollector;
+
+pub(crate) type Continuation = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+fn starts_with_whitespace(line: &str) -> bool {
+    line.starts_with(char::is_whitespace)
+}
+
+impl TestLogCollector {
+    /// Sets which lines [`records`](Self::records) attaches to the preceding
+    /// record instead of starting a new one. By default a line continues the
+    /// previous record if it starts with whitespace.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new()
+    ///     .with_continuation(|line| line.starts_with("\tat ") || line.starts_with("Caused by"));
+    /// write!(
+    ///     collector,
+    ///     "java.lang.IllegalStateException: closed\n\tat Pool.get\nCaused by: timeout\nnext\n"
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(collector.records().len(), 2);
+    /// ```
+    pub fn with_continuation<F>(mut self, is_continuation: F) -> Self
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        self.continuation = Some(Arc::new(is_continuation));
+        self
+    }
+
+    /// Returns the collected lines grouped into multi-line records, such as
+    /// a message followed by its backtrace, joined with `\n`.
+    ///
+    /// Continuation lines, by default those starting with whitespace, are
+    /// attached to the preceding record. A continuation line without a
+    /// preceding record starts one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// write!(
+    ///     collector,
+    ///     "thread 'main' panicked at src/main.rs:2:5:\n  0: std::panicking::begin_panic\n  1: main\nafter\n"
+    /// )
+    /// .unwrap();
+    ///
+    /// let records = collector.records();
+    /// assert_eq!(records.len(), 2);
+    /// assert!(records[0].contains("panicked") && records[0].ends_with("1: main"));
+    /// assert_eq!(records[1], "after");
+    /// ```
+    pub fn records(&self) -> Vec<String> {
+        let is_continuation = |line: &str| match &self.continuation {
+            Some(continuation) => continuation(line),
+            None => starts_with_whitespace(line),
+        };
+        let mut records: Vec<String> = Vec::new();
+        for line in &self.lines {
+            match records.last_mut() {
+                Some(record) if is_continuation(line) => {
+                    record.push('\n');
+                    record.push_str(line);
+                }
+                _ => records.push(line.clone()),
+            }
+        }
+        records
+    }
+}
@@ -0,0 +1,252 @@
+//! A process-wide registry giving each test its own collector.
+//!
+//! A global logger can only write to one place, so when tests run in
+//! parallel behind a single logger their output interleaves. Pointing the
+//! logger at [`writer()`] instead routes every write to the collector of the
+//! test that made it.
+//!
+//! Collectors are keyed by name. [`current()`] uses the calling thread's
+//! name, which libtest sets to the test's name, falling back to its thread
+//! id for unnamed threads. Use [`named()`] to share a collector explicitly,
+//! for example with threads spawned by the test.
+//!
+//! Since `log::set_logger` can only be called once per process, a global
+//! logger's `log` method can hand each record to [`log()`], which picks the
+//! calling test's collector the same way, preferring one installed on the
+//! thread by a [`CollectLogsGuard`](crate::CollectLogsGuard) such as `#[collect_logs]`,
+//! then one installed process-wide by
+//! [`set_global`](crate::TestLogCollector::set_global).
+//!
+//! # Examples
+//!
+//! ```
+//! use std::io::Write;
+//! use test_log_collector::registry;
+//!
+//! // Usually installed once as the target of a global logger.
+//! let mut log_target = registry::writer();
+//! writeln!(log_target, "routed to this test's collector").unwrap();
+//!
+//! let logs = registry::current();
+//! assert_eq!(logs.lock().unwrap().count(), 1);
+//! registry::remove_current();
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::thread;
+
+use crate::provenance::Label;
+use crate::{current_collector, global_collector, Level, SharedCollector, TestLogCollector};
+
+fn registry() -> MutexGuard<'static, HashMap<String, SharedCollector>> {
+    static REGISTRY: OnceLock<Mutex<HashMap<String, SharedCollector>>> = OnceLock::new();
+    REGISTRY
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Returns the registry key of the calling thread: its name if it has one,
+/// otherwise its id.
+///
+/// # Examples
+///
+/// ```
+/// use std::thread;
+/// use test_log_collector::registry;
+///
+/// let key = thread::Builder::new()
+///     .name("worker".into())
+///     .spawn(registry::current_key)
+///     .unwrap()
+///     .join()
+///     .unwrap();
+/// assert_eq!(key, "worker");
+/// ```
+pub fn current_key() -> String {
+    let current = thread::current();
+    match current.name() {
+        Some(name) => name.to_string(),
+        None => format!("{:?}", current.id()),
+    }
+}
+
+/// Returns the collector for the calling test, creating it if needed.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use test_log_collector::registry;
+///
+/// assert!(Arc::ptr_eq(&registry::current(), &registry::current()));
+/// registry::remove_current();
+/// ```
+pub fn current() -> SharedCollector {
+    named(&current_key())
+}
+
+/// Returns the collector records from the calling thread belong to: the
+/// one installed on the thread by the innermost live [`CollectLogsGuard`](crate::CollectLogsGuard),
+/// otherwise the process-wide one installed by
+/// [`set_global`](TestLogCollector::set_global), otherwise the registry's
+/// collector for the calling test, as returned by [`current()`].
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use test_log_collector::{registry, CollectLogsGuard};
+///
+/// assert!(Arc::ptr_eq(&registry::routed(), &registry::current()));
+/// let guard = CollectLogsGuard::install();
+/// assert!(Arc::ptr_eq(&registry::routed(), &guard.collector()));
+/// # drop(guard);
+/// # registry::remove_current();
+/// ```
+pub fn routed() -> SharedCollector {
+    current_collector()
+        .or_else(global_collector)
+        .unwrap_or_else(current)
+}
+
+/// Collects a structured record into the calling test's collector, as
+/// returned by [`routed()`], so one process-wide logger can serve tests
+/// running in parallel: each test sees only its own records.
+///
+/// This is the body of a global logger's `log` method; see
+/// [`TestLogCollector::log`] for how the record is stored.
+///
+/// # Examples
+///
+/// ```
+/// use std::thread;
+/// use test_log_collector::{registry, Level};
+///
+/// // What a global `log::Log` implementation would do for each record.
+/// fn global_log(level: Level, target: &str, message: &str) {
+///     registry::log(level, target, message);
+/// }
+///
+/// let worker = thread::Builder::new()
+///     .name("other_test".into())
+///     .spawn(|| {
+///         global_log(Level::Info, "app", "from the other test");
+///         registry::remove_current().unwrap()
+///     })
+///     .unwrap();
+/// global_log(Level::Warn, "app", "from this test");
+///
+/// let other = worker.join().unwrap();
+/// assert_eq!(*other.lock().unwrap(), ["INFO app: from the other test"]);
+/// assert_eq!(*registry::current().lock().unwrap(), ["WARN app: from this test"]);
+/// # registry::remove_current();
+/// ```
+pub fn log(level: Level, target: &str, message: impl fmt::Display) {
+    routed()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .log(level, target, message);
+}
+
+/// Returns the collector registered under `name`, creating it if needed.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Write;
+/// use std::thread;
+/// use test_log_collector::registry;
+///
+/// let logs = registry::named("shared-by-workers");
+/// thread::spawn(|| {
+///     writeln!(registry::named("shared-by-workers").lock().unwrap(), "from a worker").unwrap();
+/// })
+/// .join()
+/// .unwrap();
+///
+/// assert_eq!(logs.lock().unwrap().count(), 1);
+/// registry::remove("shared-by-workers");
+/// ```
+pub fn named(name: &str) -> SharedCollector {
+    registry()
+        .entry(name.to_string())
+        .or_insert_with(TestLogCollector::new_shared)
+        .clone()
+}
+
+/// Returns the collector registered under `name` without creating one.
+pub fn get(name: &str) -> Option<SharedCollector> {
+    registry().get(name).cloned()
+}
+
+/// Registers `collector` under `name`, returning the collector it replaced.
+///
+/// # Examples
+///
+/// ```
+/// use test_log_collector::{registry, TestLogCollector};
+///
+/// let collector = TestLogCollector::new_shared();
+/// assert!(registry::register("custom", collector).is_none());
+/// assert!(registry::get("custom").is_some());
+/// registry::remove("custom");
+/// ```
+pub fn register(name: &str, collector: SharedCollector) -> Option<SharedCollector> {
+    registry().insert(name.to_string(), collector)
+}
+
+/// Removes and returns the collector registered under `name`.
+pub fn remove(name: &str) -> Option<SharedCollector> {
+    registry().remove(name)
+}
+
+/// Removes and returns the calling test's collector.
+///
+/// Call this at the end of a test when thread names may be reused, e.g.
+/// with `--test-threads=1`.
+pub fn remove_current() -> Option<SharedCollector> {
+    remove(&current_key())
+}
+
+/// A writer that routes each write to the calling test's collector.
+///
+/// Created with [`writer()`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RegistryWriter;
+
+/// Returns a writer that routes each write to the collector records from
+/// the calling thread belong to, as returned by [`routed()`], so it agrees
+/// with [`log()`] inside `#[collect_logs]` tests.
+pub fn writer() -> RegistryWriter {
+    RegistryWriter
+}
+
+thread_local! {
+    /// The calling thread's provenance label for [`RegistryWriter`], built
+    /// on its first write.
+    static WRITER_LABEL: Label = Label::from(format!("via registry {:?}", current_key()));
+}
+
+impl Write for RegistryWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        WRITER_LABEL.with(|label| {
+            routed()
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .write_via(label, buf)
+        })
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        WRITER_LABEL.with(|label| {
+            routed()
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .flush_via(label)
+        })
+    }
+}
@@ -0,0 +1,106 @@
+use std::fmt;
+use std::io;
+
+use crate::TestLogCollector;
+
+impl fmt::Display for TestLogCollector {
+    /// Renders the collected lines joined by `\n`, without a trailing
+    /// newline, so the capture can be embedded in panic messages and
+    /// snapshots. The partial line is not included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "first").unwrap();
+    /// writeln!(collector, "second").unwrap();
+    ///
+    /// assert_eq!(format!("captured:\n{}", collector), "captured:\nfirst\nsecond");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, line) in self.lines.iter().enumerate() {
+            if i > 0 {
+                f.write_str("\n")?;
+            }
+            f.write_str(line)?;
+        }
+        Ok(())
+    }
+}
+
+impl TestLogCollector {
+    /// Consumes the collector and returns its lines joined by `\n`, as
+    /// rendered by `Display`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "a").unwrap();
+    /// writeln!(collector, "b").unwrap();
+    ///
+    /// assert_eq!(collector.into_string(), "a\nb");
+    /// ```
+    pub fn into_string(self) -> String {
+        self.lines.join("\n")
+    }
+
+    /// Renders the collected lines like `Display`, followed by the partial
+    /// line, if any, on its own line.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// write!(collector, "done\nwaiting...").unwrap();
+    ///
+    /// assert_eq!(collector.to_string(), "done");
+    /// assert_eq!(collector.to_string_lossy_with_pending(), "done\nwaiting...");
+    /// ```
+    pub fn to_string_lossy_with_pending(&self) -> String {
+        let mut rendered = self.to_string();
+        if !self.current_line.is_empty() {
+            if !self.lines.is_empty() {
+                rendered.push('\n');
+            }
+            rendered.push_str(&self.current_line);
+        }
+        rendered
+    }
+}
+
+impl fmt::Write for TestLogCollector {
+    /// Collects `s` exactly as `io::Write` would, so code generic over
+    /// `fmt::Write` can be tested with a collector. A write-ahead failure is
+    /// reported as `fmt::Error`.
+    ///
+    /// Import only one of the two `Write` traits where `write!` is used, or
+    /// call it through the trait, as the method names clash.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::fmt::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// fn render(out: &mut impl Write) -> std::fmt::Result {
+    ///     writeln!(out, "total: {}", 42)
+    /// }
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// render(&mut collector).unwrap();
+    /// assert_eq!(collector, ["total: 42"]);
+    /// ```
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        io::Write::write_all(self, s.as_bytes()).map_err(|_| fmt::Error)
+    }
+}
@@ -0,0 +1,88 @@
+use std::io::{self, Write};
+use std::thread;
+use std::time::Duration;
+
+use crate::TestLogCollector;
+
+impl TestLogCollector {
+    /// Writes every collected line, each followed by a newline, to
+    /// `writer`, e.g. to feed captured traffic to a log shipper under test.
+    ///
+    /// The unfinished partial line is not replayed.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from `writer`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "first").unwrap();
+    /// writeln!(collector, "second").unwrap();
+    ///
+    /// let mut out = Vec::new();
+    /// collector.replay_into(&mut out).unwrap();
+    /// assert_eq!(out, b"first\nsecond\n");
+    /// ```
+    pub fn replay_into(&self, writer: &mut impl Write) -> io::Result<()> {
+        for line in &self.lines {
+            writer.write_all(line.as_bytes())?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()
+    }
+
+    /// Like [`replay_into`](Self::replay_into), but sleeps between lines to
+    /// reproduce the gaps between their capture times, divided by `speed`.
+    /// A `speed` of `2.0` replays twice as fast as the lines were captured.
+    /// The writer is flushed after each line.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from `writer`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `speed` is not positive and finite.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "first").unwrap();
+    /// writeln!(collector, "second").unwrap();
+    ///
+    /// let mut out = Vec::new();
+    /// collector.replay_with_timing(&mut out, 10.0).unwrap();
+    /// assert_eq!(out, b"first\nsecond\n");
+    /// ```
+    pub fn replay_with_timing(&self, writer: &mut impl Write, speed: f64) -> io::Result<()> {
+        assert!(
+            speed.is_finite() && speed > 0.0,
+            "replay speed must be positive and finite, got {}",
+            speed
+        );
+        let mut previous = None;
+        for (line, meta) in self.lines.iter().zip(&self.meta) {
+            if let Some(previous) = previous {
+                let gap = meta
+                    .timestamp
+                    .duration_since(previous)
+                    .unwrap_or(Duration::ZERO);
+                thread::sleep(gap.div_f64(speed));
+            }
+            previous = Some(meta.timestamp);
+            writer.write_all(line.as_bytes())?;
+            writer.write_all(b"\n")?;
+            writer.flush()?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,297 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard, Once};
+
+use crate::json::write_json_string;
+use crate::TestLogCollector;
+
+static INSTALLED: Mutex<Option<Report>> = Mutex::new(None);
+static REGISTER_EXIT_HOOK: Once = Once::new();
+
+extern "C" {
+    fn atexit(callback: extern "C" fn()) -> std::os::raw::c_int;
+}
+
+extern "C" fn write_installed_report() {
+    if let Some(report) = installed().take() {
+        if let Err(error) = report.write() {
+            eprintln!(
+                "test_log_collector: failed to write report to {}: {}",
+                report.dir.display(),
+                error
+            );
+        }
+    }
+}
+
+fn installed() -> MutexGuard<'static, Option<Report>> {
+    INSTALLED
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+struct Entry {
+    test: String,
+    lines: Vec<String>,
+    failures: Vec<String>,
+}
+
+/// Aggregates the captures of many tests into one browsable artifact.
+///
+/// A report is written to its directory as `report.json` and an
+/// `index.html` listing every test with its verification results and
+/// collapsible logs. Either call [`write`](Report::write) yourself, or
+/// [`install`](Report::install) the report once per test binary and
+/// [`record`](Report::record) each test's collector into it; the installed
+/// report is written when the process exits.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Write;
+/// use test_log_collector::{Report, TestLogCollector};
+///
+/// let dir = std::env::temp_dir().join("test_log_collector_report_doc");
+/// let mut collector = TestLogCollector::new();
+/// writeln!(collector, "hello").unwrap();
+///
+/// let mut report = Report::new(&dir).with_title("doc tests");
+/// report.add("greets", &collector);
+/// report.write().unwrap();
+/// assert!(dir.join("index.html").exists());
+/// ```
+pub struct Report {
+    dir: PathBuf,
+    title: String,
+    entries: Vec<Entry>,
+}
+
+impl Report {
+    /// Creates an empty report written to `dir`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::Report;
+    ///
+    /// let report = Report::new("target/test-logs");
+    /// assert!(report.is_empty());
+    /// ```
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            title: "Test logs".to_string(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Sets the title shown at the top of the HTML index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::Report;
+    ///
+    /// let report = Report::new("target/test-logs").with_title("integration tests");
+    /// ```
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Adds a test's lines and its
+    /// [`unmet_expectations`](TestLogCollector::unmet_expectations) to the
+    /// report.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::{Report, TestLogCollector};
+    ///
+    /// let mut report = Report::new("target/test-logs");
+    /// report.add("my_test", &TestLogCollector::new());
+    /// assert_eq!(report.len(), 1);
+    /// ```
+    pub fn add(&mut self, test: impl Into<String>, collector: &TestLogCollector) {
+        self.entries.push(Entry {
+            test: test.into(),
+            lines: collector.clone_lines(),
+            failures: collector.unmet_expectations(),
+        });
+    }
+
+    /// Returns the number of tests in the report.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::Report;
+    ///
+    /// assert_eq!(Report::new("target/test-logs").len(), 0);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no tests have been added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::Report;
+    ///
+    /// assert!(Report::new("target/test-logs").is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Makes this the process-wide report and arranges for it to be written
+    /// when the process exits.
+    ///
+    /// Installing again replaces the previous report, including the tests
+    /// already recorded into it.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use test_log_collector::Report;
+    ///
+    /// Report::new("target/test-logs").install();
+    /// ```
+    pub fn install(self) {
+        *installed() = Some(self);
+        REGISTER_EXIT_HOOK.call_once(|| {
+            // SAFETY: `atexit` only stores the function pointer, and the
+            // callback does not unwind.
+            unsafe {
+                atexit(write_installed_report);
+            }
+        });
+    }
+
+    /// Adds a test to the installed report. Does nothing if no report is
+    /// installed, so tests can record unconditionally.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::{Report, TestLogCollector};
+    ///
+    /// Report::record("my_test", &TestLogCollector::new());
+    /// ```
+    pub fn record(test: impl Into<String>, collector: &TestLogCollector) {
+        if let Some(report) = installed().as_mut() {
+            report.add(test, collector);
+        }
+    }
+
+    /// Writes `report.json` and `index.html` to the report directory,
+    /// creating it if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from creating the directory or writing the files.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::Report;
+    ///
+    /// let dir = std::env::temp_dir().join("test_log_collector_report_write_doc");
+    /// Report::new(&dir).write().unwrap();
+    /// assert!(dir.join("report.json").exists());
+    /// ```
+    pub fn write(&self) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.dir.join("report.json"), self.to_json())?;
+        fs::write(self.dir.join("index.html"), self.to_html())
+    }
+
+    fn to_json(&self) -> String {
+        let mut out = String::from("{\"title\":");
+        let _ = write_json_string(&mut out, &self.title);
+        out.push_str(",\"tests\":[");
+        for (i, entry) in self.entries.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            out.push_str("{\"name\":");
+            let _ = write_json_string(&mut out, &entry.test);
+            let _ = write!(out, ",\"passed\":{}", entry.failures.is_empty());
+            for (key, values) in [("failures", &entry.failures), ("lines", &entry.lines)] {
+                let _ = write!(out, ",\"{}\":[", key);
+                for (j, value) in values.iter().enumerate() {
+                    if j > 0 {
+                        out.push(',');
+                    }
+                    let _ = write_json_string(&mut out, value);
+                }
+                out.push(']');
+            }
+            out.push('}');
+        }
+        out.push_str("]}\n");
+        out
+    }
+
+    fn to_html(&self) -> String {
+        let passed = self
+            .entries
+            .iter()
+            .filter(|entry| entry.failures.is_empty())
+            .count();
+        let mut out = String::new();
+        let _ = write!(
+            out,
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{0}</title>\n</head>\n<body>\n<h1>{0}</h1>\n<p>{1} of {2} tests passed</p>\n",
+            escape_html(&self.title),
+            passed,
+            self.entries.len()
+        );
+        for entry in &self.entries {
+            let status = if entry.failures.is_empty() {
+                "passed"
+            } else {
+                "FAILED"
+            };
+            let _ = write!(
+                out,
+                "<details>\n<summary>{} &mdash; {} ({} lines)</summary>\n",
+                escape_html(&entry.test),
+                status,
+                entry.lines.len()
+            );
+            if !entry.failures.is_empty() {
+                out.push_str("<ul>\n");
+                for failure in &entry.failures {
+                    let _ = writeln!(out, "<li>{}</li>", escape_html(failure));
+                }
+                out.push_str("</ul>\n");
+            }
+            out.push_str("<pre>");
+            for line in &entry.lines {
+                let _ = writeln!(out, "{}", escape_html(line));
+            }
+            out.push_str("</pre>\n</details>\n");
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
@@ -0,0 +1,134 @@
+use crate::{LineMeta, TestLogCollector};
+
+/// Seed of the reservoir's random number generator, fixed so sampled
+/// captures are reproducible.
+pub(crate) const SEED: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// What [`TestLogCollector::sample`] decided for a line.
+pub(crate) enum Sampled {
+    Kept,
+    Dropped,
+    /// Kept, evicting the line at this index.
+    Replaces(usize),
+}
+
+/// Which lines a collector keeps, set with
+/// [`TestLogCollector::with_sampling`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sampling {
+    /// Keeps the first line and then every `n`th one.
+    EveryNth(usize),
+    /// Keeps a uniform random sample of at most this many lines. The
+    /// sample is the same on every run.
+    ///
+    /// The sample stays in the order the lines were written.
+    Reservoir(usize),
+}
+
+impl TestLogCollector {
+    /// Keeps only a sample of the lines, for stress tests that emit far more
+    /// lines than any assertion needs.
+    /// [`total_written`](Self::total_written) still counts every line.
+    ///
+    /// Expectations see every line, sampled or not.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `n` of [`Sampling::EveryNth`] is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::{Sampling, TestLogCollector};
+    ///
+    /// let mut collector = TestLogCollector::new().with_sampling(Sampling::EveryNth(1_000));
+    /// for i in 0..10_000 {
+    ///     writeln!(collector, "request {}", i).unwrap();
+    /// }
+    ///
+    /// assert_eq!(collector.count(), 10);
+    /// assert_eq!(collector.nth(1), Some("request 1000"));
+    /// assert_eq!(collector.total_written(), 10_000);
+    ///
+    /// let mut collector = TestLogCollector::new().with_sampling(Sampling::Reservoir(100));
+    /// for i in 0..10_000 {
+    ///     writeln!(collector, "request {}", i).unwrap();
+    /// }
+    /// assert_eq!(collector.count(), 100);
+    /// ```
+    pub fn with_sampling(mut self, sampling: Sampling) -> Self {
+        assert!(
+            sampling != Sampling::EveryNth(0),
+            "Sampling::EveryNth requires a non-zero interval"
+        );
+        self.sampling = Some(sampling);
+        self
+    }
+
+    /// Returns the number of lines that passed the capture-time filters,
+    /// whether or not sampling kept them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// write!(collector, "a\nb\n").unwrap();
+    ///
+    /// assert_eq!(collector.total_written(), 2);
+    /// ```
+    pub fn total_written(&self) -> usize {
+        self.total_written
+    }
+
+    /// Counts a line and decides whether to keep it, and in a full
+    /// reservoir, which random earlier line it evicts.
+    pub(crate) fn sample(&mut self) -> Sampled {
+        let seen = self.total_written;
+        self.total_written += 1;
+        match self.sampling {
+            None => Sampled::Kept,
+            Some(Sampling::EveryNth(n)) if seen.is_multiple_of(n) => Sampled::Kept,
+            Some(Sampling::EveryNth(_)) => Sampled::Dropped,
+            Some(Sampling::Reservoir(capacity)) => {
+                if self.lines.len() < capacity {
+                    return Sampled::Kept;
+                }
+                let slot = (self.next_random() % (seen as u64 + 1)) as usize;
+                if slot >= capacity {
+                    Sampled::Dropped
+                } else {
+                    Sampled::Replaces(slot)
+                }
+            }
+        }
+    }
+
+    /// Evicts the line at `slot` and appends `line`, the newest, so the
+    /// sample stays in the order it was written.
+    pub(crate) fn replace_sampled(&mut self, slot: usize, line: String, meta: LineMeta) {
+        let evicted = self.lines.remove(slot);
+        self.meta.remove(slot);
+        if let Some(index) = &mut self.index {
+            index.remove(slot, &evicted);
+        }
+        self.invalidate_shared_lines();
+        self.lines.push(line);
+        self.meta.push_back(meta);
+        self.index_last_line();
+    }
+
+    /// xorshift64*: statistically good enough for sampling and needs no
+    /// dependencies.
+    fn next_random(&mut self) -> u64 {
+        let mut x = self.sample_rng;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.sample_rng = x;
+        x.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+}
@@ -0,0 +1,186 @@
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use crate::{PanicDumpGuard, SharedCollector, TestLogCollector};
+
+thread_local! {
+    static CURRENT: RefCell<Vec<SharedCollector>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Makes a collector the current thread's collector for a scope.
+///
+/// This is what `#[collect_logs]` installs around a test body. While the
+/// guard is alive, [`current_collector`] returns its collector on the thread
+/// that created it and the collected lines are dumped to stderr if that
+/// thread panics. Guards can be nested; the innermost one is current.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Write;
+/// use test_log_collector::{current_collector, CollectLogsGuard};
+///
+/// let guard = CollectLogsGuard::install();
+/// let logs = current_collector().unwrap();
+/// writeln!(logs.lock().unwrap(), "inside the scope").unwrap();
+///
+/// assert_eq!(guard.collector().lock().unwrap().count(), 1);
+/// drop(guard);
+/// assert!(current_collector().is_none());
+/// ```
+#[must_use = "the collector is only current while the guard is alive"]
+pub struct CollectLogsGuard {
+    collector: SharedCollector,
+    _dump: PanicDumpGuard,
+}
+
+impl CollectLogsGuard {
+    /// Installs a new empty collector as the current thread's collector.
+    pub fn install() -> Self {
+        Self::install_collector(TestLogCollector::new_shared())
+    }
+
+    /// Installs `collector` as the current thread's collector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::{current_collector, CollectLogsGuard, TestLogCollector};
+    ///
+    /// let collector = TestLogCollector::new_shared();
+    /// let _guard = CollectLogsGuard::install_collector(collector.clone());
+    ///
+    /// assert!(std::sync::Arc::ptr_eq(&current_collector().unwrap(), &collector));
+    /// ```
+    pub fn install_collector(collector: SharedCollector) -> Self {
+        let dump = TestLogCollector::dump_on_panic(&collector);
+        CURRENT.with(|current| current.borrow_mut().push(collector.clone()));
+        Self {
+            collector,
+            _dump: dump,
+        }
+    }
+
+    /// Returns a handle to the installed collector.
+    pub fn collector(&self) -> SharedCollector {
+        self.collector.clone()
+    }
+}
+
+impl Drop for CollectLogsGuard {
+    fn drop(&mut self) {
+        leave(&self.collector);
+    }
+}
+
+/// Removes the innermost entry for `collector` from the current thread's
+/// stack.
+fn leave(collector: &SharedCollector) {
+    CURRENT.with(|current| {
+        let mut current = current.borrow_mut();
+        if let Some(pos) = current
+            .iter()
+            .rposition(|entry| Arc::ptr_eq(entry, collector))
+        {
+            current.remove(pos);
+        }
+    });
+}
+
+/// Makes a collector current for the duration of one poll.
+struct Enter(SharedCollector);
+
+impl Enter {
+    fn new(collector: SharedCollector) -> Self {
+        CURRENT.with(|current| current.borrow_mut().push(collector.clone()));
+        Self(collector)
+    }
+}
+
+impl Drop for Enter {
+    fn drop(&mut self) {
+        leave(&self.0);
+    }
+}
+
+/// A future that runs with a collector made current on whichever thread
+/// polls it.
+///
+/// Created with [`propagate_to_spawned`].
+#[must_use = "futures do nothing unless polled"]
+pub struct Propagated<F> {
+    collector: SharedCollector,
+    inner: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for Propagated<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        let _enter = Enter::new(self.collector.clone());
+        self.inner.as_mut().poll(cx)
+    }
+}
+
+/// Wraps `future` so the collector the calling thread's logs are routed to
+/// is current again whenever the future is polled, wherever that happens.
+///
+/// That is the thread's [`current_collector`] or, failing that, its
+/// [`registry`](crate::registry) collector, as picked by
+/// [`registry::routed`](crate::registry::routed). Both are per-thread, so
+/// tasks handed to a multi-threaded executor, e.g. with `tokio::spawn`,
+/// would otherwise lose them, and every tokio worker thread shares one
+/// name: wrap them at the spawn boundary to attribute their logs to the
+/// test that spawned them.
+///
+/// # Examples
+///
+/// ```
+/// use std::future::Future;
+/// use std::pin::pin;
+/// use std::task::{Context, Waker};
+/// use test_log_collector::{current_collector, propagate_to_spawned, CollectLogsGuard};
+///
+/// let guard = CollectLogsGuard::install();
+/// let task = propagate_to_spawned(async {
+///     current_collector().unwrap().lock().unwrap().log(
+///         test_log_collector::Level::Info,
+///         "task",
+///         "attributed to the spawning test",
+///     );
+/// });
+///
+/// // Stands in for `tokio::spawn(task)`: poll it on another thread.
+/// std::thread::spawn(move || {
+///     let mut task = pin!(task);
+///     let mut cx = Context::from_waker(Waker::noop());
+///     assert!(task.as_mut().poll(&mut cx).is_ready());
+/// })
+/// .join()
+/// .unwrap();
+///
+/// assert_eq!(guard.collector().lock().unwrap().count(), 1);
+/// ```
+pub fn propagate_to_spawned<F: Future>(future: F) -> Propagated<F> {
+    Propagated {
+        collector: crate::registry::routed(),
+        inner: Box::pin(future),
+    }
+}
+
+/// Returns the collector installed for the current thread by the innermost
+/// live [`CollectLogsGuard`], such as the one created by `#[collect_logs]`.
+///
+/// # Examples
+///
+/// ```
+/// use test_log_collector::current_collector;
+///
+/// assert!(current_collector().is_none());
+/// ```
+pub fn current_collector() -> Option<SharedCollector> {
+    CURRENT.with(|current| current.borrow().last().cloned())
+}
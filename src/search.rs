@@ -0,0 +1,485 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::{Matcher, TestLogCollector};
+
+/// Number of lines after which a collector builds its token index.
+pub(crate) const DEFAULT_INDEX_THRESHOLD: usize = 50_000;
+
+/// Returns the windows of `before` and `after` lines around the ascending
+/// `matches` in a capture of `len` lines, merging those that overlap or
+/// touch.
+pub(crate) fn context_ranges(
+    matches: &[usize],
+    before: usize,
+    after: usize,
+    len: usize,
+) -> Vec<Range<usize>> {
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    for &index in matches {
+        let start = index.saturating_sub(before);
+        let end = index.saturating_add(after).saturating_add(1).min(len);
+        match ranges.last_mut() {
+            Some(last) if last.end >= start => last.end = last.end.max(end),
+            _ => ranges.push(start..end),
+        }
+    }
+    ranges
+}
+
+/// Maps each token to the ascending indices of the lines containing it.
+///
+/// A token is a maximal run of alphanumeric characters or underscores.
+/// Postings are numbered from the first line indexed, so evicting the oldest
+/// lines only moves `base`; entries below it are stale and skipped.
+#[derive(Clone, Default)]
+pub(crate) struct TokenIndex {
+    postings: HashMap<Box<str>, Vec<usize>>,
+    base: usize,
+}
+
+impl TokenIndex {
+    pub(crate) fn build(lines: &[String]) -> Self {
+        let mut index = Self::default();
+        for (line_index, line) in lines.iter().enumerate() {
+            index.add(line_index, line);
+        }
+        index
+    }
+
+    pub(crate) fn add(&mut self, line_index: usize, line: &str) {
+        let line_index = self.base + line_index;
+        for token in tokens(line) {
+            let postings = self.postings.entry(token.into()).or_default();
+            if postings.last() != Some(&line_index) {
+                postings.push(line_index);
+            }
+        }
+    }
+
+    /// Removes `line`, stored at `line_index`, and renumbers the lines
+    /// after it, which move down by one.
+    pub(crate) fn remove(&mut self, line_index: usize, line: &str) {
+        let line_index = self.base + line_index;
+        for token in tokens(line) {
+            if let Some(postings) = self.postings.get_mut(token) {
+                if let Ok(pos) = postings.binary_search(&line_index) {
+                    postings.remove(pos);
+                }
+                if postings.is_empty() {
+                    self.postings.remove(token);
+                }
+            }
+        }
+        for postings in self.postings.values_mut() {
+            let after = postings.partition_point(|&line| line < line_index);
+            for line in &mut postings[after..] {
+                *line -= 1;
+            }
+        }
+    }
+
+    /// Drops the `n` oldest lines, renumbering the rest from zero.
+    ///
+    /// Returns `false`, leaving the index unchanged, once stale postings
+    /// outnumber the `len` lines left, so the caller rebuilds it instead.
+    pub(crate) fn evict_front(&mut self, n: usize, len: usize) -> bool {
+        if self.base + n > len {
+            return false;
+        }
+        self.base += n;
+        true
+    }
+
+    /// Returns the live part of `postings`.
+    fn live<'a>(&self, postings: &'a [usize]) -> &'a [usize] {
+        &postings[postings.partition_point(|&line| line < self.base)..]
+    }
+
+    /// Returns the lines containing every token, or `None` if `tokens` is
+    /// empty.
+    fn lookup(&self, tokens: &[&str]) -> Option<Vec<usize>> {
+        let mut lists = Vec::with_capacity(tokens.len());
+        for token in tokens {
+            lists.push(
+                self.postings
+                    .get(*token)
+                    .map_or(&[][..], |postings| self.live(postings)),
+            );
+        }
+        lists.sort_by_key(|list| list.len());
+        let (shortest, rest) = lists.split_first()?;
+        Some(
+            shortest
+                .iter()
+                .copied()
+                .filter(|line| rest.iter().all(|list| list.binary_search(line).is_ok()))
+                .map(|line| line - self.base)
+                .collect(),
+        )
+    }
+
+    /// Like [`lookup`](Self::lookup), but matches tokens ignoring case by
+    /// merging the postings of every spelling of each token.
+    fn lookup_ignore_case(&self, tokens: &[&str]) -> Option<Vec<usize>> {
+        let mut lists: Vec<Vec<usize>> = tokens
+            .iter()
+            .map(|token| {
+                let mut list: Vec<usize> = self
+                    .postings
+                    .iter()
+                    .filter(|(key, _)| eq_ignore_case(key, token))
+                    .flat_map(|(_, postings)| self.live(postings).iter().copied())
+                    .collect();
+                list.sort_unstable();
+                list.dedup();
+                list
+            })
+            .collect();
+        lists.sort_by_key(Vec::len);
+        let (shortest, rest) = lists.split_first()?;
+        Some(
+            shortest
+                .iter()
+                .copied()
+                .filter(|line| rest.iter().all(|list| list.binary_search(line).is_ok()))
+                .map(|line| line - self.base)
+                .collect(),
+        )
+    }
+}
+
+/// Returns `true` if `line` contains `pattern` ignoring case, comparing
+/// bytes in place when both are ASCII and falling back to lowercasing the
+/// line, against the already lowercased pattern, otherwise.
+pub(crate) fn contains_ignore_case(line: &str, pattern: &str, lowered: &str) -> bool {
+    if line.is_ascii() && pattern.is_ascii() {
+        let pattern = pattern.as_bytes();
+        return pattern.is_empty()
+            || line
+                .as_bytes()
+                .windows(pattern.len())
+                .any(|window| window.eq_ignore_ascii_case(pattern));
+    }
+    line.to_lowercase().contains(lowered)
+}
+
+fn eq_ignore_case(a: &str, b: &str) -> bool {
+    if a.is_ascii() && b.is_ascii() {
+        return a.eq_ignore_ascii_case(b);
+    }
+    a.chars()
+        .flat_map(char::to_lowercase)
+        .eq(b.chars().flat_map(char::to_lowercase))
+}
+
+fn is_token_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_'
+}
+
+fn tokens(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|ch: char| !is_token_char(ch))
+        .filter(|token| !token.is_empty())
+}
+
+/// Returns the tokens of `pattern` that must appear as whole tokens in any
+/// line containing `pattern`, i.e. those delimited on both sides within the
+/// pattern itself.
+fn complete_tokens(pattern: &str) -> Vec<&str> {
+    let mut complete = Vec::new();
+    let mut start = None;
+    for (pos, ch) in pattern.char_indices() {
+        match (is_token_char(ch), start) {
+            (true, None) => start = Some(pos),
+            (false, Some(token_start)) => {
+                if token_start > 0 {
+                    complete.push(&pattern[token_start..pos]);
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    complete
+}
+
+impl TestLogCollector {
+    /// Sets the number of lines after which a token index is built.
+    ///
+    /// Once a capture grows past the threshold, every token of every line is
+    /// indexed so that [`contains`](Self::contains),
+    /// [`contains_ignore_case`](Self::contains_ignore_case),
+    /// [`matching_lines`](Self::matching_lines) and
+    /// [`lines_with_tokens`](Self::lines_with_tokens) only have to look at
+    /// candidate lines instead of rescanning the whole capture. The default
+    /// threshold is 50,000 lines.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new().with_index_threshold(1);
+    /// writeln!(collector, "GET /health 200").unwrap();
+    /// writeln!(collector, "GET /users 500").unwrap();
+    ///
+    /// assert!(collector.contains("/users 500"));
+    /// ```
+    pub fn with_index_threshold(mut self, lines: usize) -> Self {
+        self.index_threshold = lines;
+        self.reindex();
+        self
+    }
+
+    /// Returns `true` if any collected line contains `pattern`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "connection established").unwrap();
+    ///
+    /// assert!(collector.contains("established"));
+    /// assert!(!collector.contains("refused"));
+    /// ```
+    pub fn contains(&self, pattern: &str) -> bool {
+        match self.candidates(pattern) {
+            Some(candidates) => candidates
+                .into_iter()
+                .any(|index| self.lines[index].contains(pattern)),
+            None => self.lines.iter().any(|line| line.contains(pattern)),
+        }
+    }
+
+    /// Returns `true` if any collected line contains `pattern`, ignoring
+    /// case, so `"error"` finds both `Error` and `ERROR`.
+    ///
+    /// Case folding does not unify composed and decomposed characters; add a
+    /// scrubber that normalizes lines, e.g. to NFC, if sources differ there.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "CONNECTION Refused").unwrap();
+    ///
+    /// assert!(collector.contains_ignore_case("connection refused"));
+    /// assert!(!collector.contains("connection refused"));
+    /// ```
+    pub fn contains_ignore_case(&self, pattern: &str) -> bool {
+        let lowered = pattern.to_lowercase();
+        let matches = |line: &str| contains_ignore_case(line, pattern, &lowered);
+        let candidates = self
+            .index
+            .as_ref()
+            .and_then(|index| index.lookup_ignore_case(&complete_tokens(pattern)));
+        match candidates {
+            Some(candidates) => candidates
+                .into_iter()
+                .any(|index| matches(&self.lines[index])),
+            None => self.lines.iter().any(|line| matches(line)),
+        }
+    }
+
+    /// Returns the collected lines that contain `pattern`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "request 1 ok").unwrap();
+    /// writeln!(collector, "request 2 failed").unwrap();
+    /// writeln!(collector, "request 3 ok").unwrap();
+    ///
+    /// assert_eq!(collector.matching_lines(" ok"), vec!["request 1 ok", "request 3 ok"]);
+    /// ```
+    pub fn matching_lines(&self, pattern: &str) -> Vec<&str> {
+        match self.candidates(pattern) {
+            Some(candidates) => candidates
+                .into_iter()
+                .map(|index| self.lines[index].as_str())
+                .filter(|line| line.contains(pattern))
+                .collect(),
+            None => self
+                .lines
+                .iter()
+                .map(String::as_str)
+                .filter(|line| line.contains(pattern))
+                .collect(),
+        }
+    }
+
+    /// Returns the index and text of each line containing `pattern`, so
+    /// failure messages can cite line numbers and tests can compare the
+    /// positions of matches.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// write!(collector, "connecting\nconnected\nlistening\n").unwrap();
+    ///
+    /// let matches = collector.grep("connect");
+    /// assert_eq!(matches, [(0, "connecting"), (1, "connected")]);
+    /// assert!(matches[1].0 < collector.grep("listening")[0].0);
+    /// ```
+    pub fn grep(&self, pattern: &str) -> Vec<(usize, &str)> {
+        let candidates = self
+            .candidates(pattern)
+            .unwrap_or_else(|| (0..self.lines.len()).collect());
+        candidates
+            .into_iter()
+            .map(|index| (index, self.lines[index].as_str()))
+            .filter(|(_, line)| line.contains(pattern))
+            .collect()
+    }
+
+    /// Returns the index and text of each line `matcher` matches. Wrap a
+    /// regex in [`Matcher::new`] to grep with it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::{Matcher, TestLogCollector};
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// write!(collector, "status=200\nstatus=503\nstatus=502\n").unwrap();
+    ///
+    /// let server_error = Matcher::new("a 5xx status", |line| line.starts_with("status=5"));
+    /// assert_eq!(collector.grep_matching(server_error), [(1, "status=503"), (2, "status=502")]);
+    /// ```
+    pub fn grep_matching(&self, matcher: impl Into<Matcher>) -> Vec<(usize, &str)> {
+        let matcher = matcher.into();
+        self.lines
+            .iter()
+            .enumerate()
+            .map(|(index, line)| (index, line.as_str()))
+            .filter(|(_, line)| matcher.matches(line))
+            .collect()
+    }
+
+    /// Returns the lines `pattern` matches with up to `before` lines before
+    /// and `after` lines after each, in order and without repeating lines
+    /// where the windows overlap, like `grep -B before -A after`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// write!(collector, "connect\nretry\nconnected\nidle\nidle\nclosed\n").unwrap();
+    ///
+    /// assert_eq!(
+    ///     collector.context("connect", 0, 1),
+    ///     ["connect", "retry", "connected", "idle"]
+    /// );
+    /// ```
+    pub fn context(&self, pattern: impl Into<Matcher>, before: usize, after: usize) -> Vec<&str> {
+        let matches = self.matching_indices(&pattern.into());
+        context_ranges(&matches, before, after, self.lines.len())
+            .into_iter()
+            .flatten()
+            .map(|index| self.lines[index].as_str())
+            .collect()
+    }
+
+    /// Returns the indices of lines containing every one of `tokens` as a
+    /// whole token.
+    ///
+    /// A token is a maximal run of alphanumeric characters or underscores.
+    /// This is useful as a cheap prefilter before running an expensive
+    /// matcher, such as a regex, over a large capture.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "db timeout after 30s").unwrap();
+    /// writeln!(collector, "http timeout").unwrap();
+    /// writeln!(collector, "db_pool ready").unwrap();
+    ///
+    /// assert_eq!(collector.lines_with_tokens(&["db", "timeout"]), vec![0]);
+    /// ```
+    pub fn lines_with_tokens(&self, tokens: &[&str]) -> Vec<usize> {
+        if let Some(index) = &self.index {
+            if let Some(found) = index.lookup(tokens) {
+                return found;
+            }
+        }
+        self.lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| {
+                let line_tokens: Vec<&str> = self::tokens(line).collect();
+                tokens.iter().all(|token| line_tokens.contains(token))
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Returns the candidate lines for `pattern` from the index, or `None` if
+    /// there is no index or the pattern has no complete tokens to look up.
+    fn candidates(&self, pattern: &str) -> Option<Vec<usize>> {
+        self.index.as_ref()?.lookup(&complete_tokens(pattern))
+    }
+
+    /// Adds the most recently stored line to the index, building the index
+    /// if the capture just crossed the threshold.
+    pub(crate) fn index_last_line(&mut self) {
+        let line_index = self.lines.len() - 1;
+        match &mut self.index {
+            Some(index) => index.add(line_index, &self.lines[line_index]),
+            None if self.lines.len() > self.index_threshold => {
+                self.index = Some(TokenIndex::build(&self.lines));
+            }
+            None => {}
+        }
+    }
+
+    /// Removes the `n` oldest lines, shifting the index instead of
+    /// rebuilding it.
+    pub(crate) fn evict_front(&mut self, n: usize) {
+        self.lines.drain(..n);
+        self.meta.drain(..n);
+        let len = self.lines.len();
+        let shifted = self
+            .index
+            .as_mut()
+            .is_some_and(|index| index.evict_front(n, len));
+        if shifted {
+            self.invalidate_shared_lines();
+        } else {
+            self.reindex();
+        }
+    }
+
+    /// Rebuilds the index from scratch, and drops the cached shared lines,
+    /// after lines were removed or changed.
+    pub(crate) fn reindex(&mut self) {
+        self.invalidate_shared_lines();
+        self.index = if self.lines.len() > self.index_threshold {
+            Some(TokenIndex::build(&self.lines))
+        } else {
+            None
+        };
+    }
+}
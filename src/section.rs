@@ -0,0 +1,93 @@
+use std::sync::Arc;
+
+use crate::TestLogCollector;
+
+impl TestLogCollector {
+    /// Starts a named section, such as `"setup"`, that every line completed
+    /// until [`end_section`](Self::end_section) or the next section belongs
+    /// to. Panic dumps show where each section starts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// collector.begin_section("setup");
+    /// writeln!(collector, "migrating database").unwrap();
+    /// collector.begin_section("exercise");
+    /// writeln!(collector, "GET /users").unwrap();
+    /// collector.end_section();
+    /// writeln!(collector, "teardown").unwrap();
+    ///
+    /// assert_eq!(collector.section_lines("setup"), ["migrating database"]);
+    /// assert_eq!(collector.line_section(2), None);
+    /// ```
+    pub fn begin_section(&mut self, name: impl Into<String>) {
+        self.section = Some(Arc::from(name.into()));
+    }
+
+    /// Ends the current section; later lines belong to no section.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// collector.begin_section("warm-up");
+    /// collector.end_section();
+    /// writeln!(collector, "measured").unwrap();
+    ///
+    /// assert!(collector.section_lines("warm-up").is_empty());
+    /// ```
+    pub fn end_section(&mut self) {
+        self.section = None;
+    }
+
+    /// Returns the lines completed during every section named `name`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// collector.begin_section("retry");
+    /// writeln!(collector, "attempt 1").unwrap();
+    /// collector.begin_section("other");
+    /// collector.begin_section("retry");
+    /// writeln!(collector, "attempt 2").unwrap();
+    ///
+    /// assert_eq!(collector.section_lines("retry"), ["attempt 1", "attempt 2"]);
+    /// ```
+    pub fn section_lines(&self, name: &str) -> Vec<&str> {
+        self.lines
+            .iter()
+            .zip(&self.meta)
+            .filter(|(_, meta)| meta.section.as_deref() == Some(name))
+            .map(|(line, _)| line.as_str())
+            .collect()
+    }
+
+    /// Returns the section the line at `index` belongs to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// collector.begin_section("setup");
+    /// writeln!(collector, "ready").unwrap();
+    ///
+    /// assert_eq!(collector.line_section(0), Some("setup"));
+    /// ```
+    pub fn line_section(&self, index: usize) -> Option<&str> {
+        self.meta.get(index)?.section.as_deref()
+    }
+}
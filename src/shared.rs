@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use crate::TestLogCollector;
+
+impl TestLogCollector {
+    /// Returns the collected lines as reference-counted strings, cheap to
+    /// clone and to send to other threads.
+    ///
+    /// The lines are copied into the returned snapshot. While any snapshot
+    /// is alive, later calls reuse it: they return it as is if no line was
+    /// added, and otherwise copy only the new lines, until lines are removed
+    /// or changed. Once every snapshot is dropped its copy is freed. Use
+    /// [`clone_lines`](Self::clone_lines) for owned `String`s.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use std::sync::Arc;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "a large capture").unwrap();
+    ///
+    /// let first = collector.shared_lines();
+    /// assert!(Arc::ptr_eq(&first, &collector.shared_lines()));
+    ///
+    /// writeln!(collector, "more").unwrap();
+    /// assert!(Arc::ptr_eq(&first[0], &collector.shared_lines()[0]));
+    ///
+    /// let handle = std::thread::spawn(move || first.len());
+    /// assert_eq!(handle.join().unwrap(), 1);
+    /// ```
+    pub fn shared_lines(&self) -> Arc<[Arc<str>]> {
+        let mut cache = self
+            .shared_lines
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let previous = cache
+            .as_ref()
+            .and_then(|snapshot| snapshot.upgrade())
+            .filter(|snapshot| snapshot.len() <= self.lines.len());
+        if let Some(previous) = &previous {
+            if previous.len() == self.lines.len() {
+                return previous.clone();
+            }
+        }
+        let reused = previous.as_deref().unwrap_or_default();
+        let snapshot: Arc<[Arc<str>]> = reused
+            .iter()
+            .cloned()
+            .chain(
+                self.lines[reused.len()..]
+                    .iter()
+                    .map(|line| Arc::from(line.as_str())),
+            )
+            .collect();
+        *cache = Some(Arc::downgrade(&snapshot));
+        snapshot
+    }
+
+    pub(crate) fn invalidate_shared_lines(&mut self) {
+        *self
+            .shared_lines
+            .get_mut()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+    }
+}
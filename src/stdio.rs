@@ -0,0 +1,425 @@
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::sync::{Mutex, MutexGuard, TryLockError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::provenance::Label;
+use crate::{SharedCollector, TestLogCollector};
+
+/// How long dropping the guard waits for the collector to be unlocked
+/// before leaving the last lines to a background thread.
+const DELIVERY_WAIT: Duration = Duration::from_millis(100);
+
+/// Only one capture may redirect the process's stdio at a time.
+static CAPTURE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Clone, Copy)]
+enum Stream {
+    Stdout,
+    Stderr,
+}
+
+impl Stream {
+    fn label(self) -> Label {
+        match self {
+            Stream::Stdout => Label::Static("via stdout"),
+            Stream::Stderr => Label::Static("via stderr"),
+        }
+    }
+}
+
+#[cfg(unix)]
+mod sys {
+    use std::fs::File;
+    use std::io;
+    use std::os::raw::c_int;
+    use std::os::unix::io::FromRawFd;
+
+    use super::Stream;
+
+    extern "C" {
+        fn pipe(fds: *mut c_int) -> c_int;
+        fn dup(fd: c_int) -> c_int;
+        fn dup2(src: c_int, dst: c_int) -> c_int;
+        fn close(fd: c_int) -> c_int;
+    }
+
+    fn check(result: c_int) -> io::Result<c_int> {
+        if result < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(result)
+        }
+    }
+
+    /// The original descriptor of a redirected stream, restored on drop.
+    pub(super) struct Saved {
+        target: c_int,
+        saved: c_int,
+    }
+
+    /// Points the stream's descriptor at a new pipe and returns its read
+    /// end.
+    pub(super) fn redirect(stream: Stream) -> io::Result<(File, Saved)> {
+        let target = match stream {
+            Stream::Stdout => 1,
+            Stream::Stderr => 2,
+        };
+        let mut fds = [0; 2];
+        // SAFETY: `fds` has room for the two descriptors `pipe` writes.
+        check(unsafe { pipe(fds.as_mut_ptr()) })?;
+        let [read_fd, write_fd] = fds;
+        // SAFETY: `read_fd` is a freshly created descriptor owned by nothing
+        // else, so the `File` becomes its sole owner.
+        let reader = unsafe { File::from_raw_fd(read_fd) };
+
+        // SAFETY: plain descriptor calls on valid descriptors; failures are
+        // checked and the write end is closed on every path.
+        let redirected = unsafe {
+            check(dup(target)).and_then(|saved| match check(dup2(write_fd, target)) {
+                Ok(_) => Ok(saved),
+                Err(error) => {
+                    close(saved);
+                    Err(error)
+                }
+            })
+        };
+        // SAFETY: `write_fd` is ours; `target` now holds its own duplicate.
+        unsafe { close(write_fd) };
+        Ok((
+            reader,
+            Saved {
+                target,
+                saved: redirected?,
+            },
+        ))
+    }
+
+    impl Drop for Saved {
+        fn drop(&mut self) {
+            // SAFETY: `saved` is the duplicate of the original descriptor
+            // made in `redirect`; restoring it closes the last write end of
+            // the pipe, so the reader sees end-of-file.
+            unsafe {
+                dup2(self.saved, self.target);
+                close(self.saved);
+            }
+        }
+    }
+}
+
+#[cfg(windows)]
+mod sys {
+    use std::ffi::c_void;
+    use std::fs::File;
+    use std::io;
+    use std::os::raw::c_int;
+    use std::os::windows::io::FromRawHandle;
+
+    use super::Stream;
+
+    type Handle = *mut c_void;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn CreatePipe(
+            read: *mut Handle,
+            write: *mut Handle,
+            attributes: *mut c_void,
+            size: u32,
+        ) -> i32;
+        fn GetStdHandle(std_handle: u32) -> Handle;
+        fn SetStdHandle(std_handle: u32, handle: Handle) -> i32;
+        fn CloseHandle(handle: Handle) -> i32;
+    }
+
+    // The C runtime's descriptors, which C code and `printf` write to.
+    extern "C" {
+        fn _open_osfhandle(handle: isize, flags: c_int) -> c_int;
+        fn _get_osfhandle(fd: c_int) -> isize;
+        fn _dup(fd: c_int) -> c_int;
+        fn _dup2(src: c_int, dst: c_int) -> c_int;
+        fn _close(fd: c_int) -> c_int;
+    }
+
+    const STD_OUTPUT_HANDLE: u32 = -11i32 as u32;
+    const STD_ERROR_HANDLE: u32 = -12i32 as u32;
+
+    /// The original handle and C runtime descriptor of a redirected stream,
+    /// restored on drop.
+    pub(super) struct Saved {
+        std_handle: u32,
+        original: Handle,
+        fd: c_int,
+        saved_fd: Option<c_int>,
+    }
+
+    /// Points the stream's standard handle and C runtime descriptor at a
+    /// new pipe and returns its read end.
+    pub(super) fn redirect(stream: Stream) -> io::Result<(File, Saved)> {
+        let (std_handle, fd) = match stream {
+            Stream::Stdout => (STD_OUTPUT_HANDLE, 1),
+            Stream::Stderr => (STD_ERROR_HANDLE, 2),
+        };
+        let mut read: Handle = std::ptr::null_mut();
+        let mut write: Handle = std::ptr::null_mut();
+        // SAFETY: both out-pointers are valid; the handles are checked.
+        if unsafe { CreatePipe(&mut read, &mut write, std::ptr::null_mut(), 0) } == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: `read` is a fresh handle owned by nothing else, so the
+        // `File` becomes its sole owner.
+        let reader = unsafe { File::from_raw_handle(read) };
+
+        // SAFETY: plain handle and descriptor calls; every failure is
+        // checked and the write end is released on every path. `fd` ends up
+        // owning the only write handle, also installed as the standard
+        // handle.
+        unsafe {
+            let original = GetStdHandle(std_handle);
+            let write_fd = _open_osfhandle(write as isize, 0);
+            if write_fd < 0 {
+                CloseHandle(write);
+                return Err(io::Error::other("cannot open a descriptor for the pipe"));
+            }
+            // A process without a console may have no descriptor to save.
+            let saved_fd = Some(_dup(fd)).filter(|&saved| saved >= 0);
+            let redirected = _dup2(write_fd, fd);
+            _close(write_fd);
+            if redirected < 0 {
+                if let Some(saved) = saved_fd {
+                    _close(saved);
+                }
+                return Err(io::Error::other(format!(
+                    "cannot redirect descriptor {}",
+                    fd
+                )));
+            }
+            if SetStdHandle(std_handle, _get_osfhandle(fd) as Handle) == 0 {
+                let error = io::Error::last_os_error();
+                restore_fd(fd, saved_fd);
+                return Err(error);
+            }
+            Ok((
+                reader,
+                Saved {
+                    std_handle,
+                    original,
+                    fd,
+                    saved_fd,
+                },
+            ))
+        }
+    }
+
+    /// Puts the original descriptor back, closing the pipe's write handle.
+    unsafe fn restore_fd(fd: c_int, saved_fd: Option<c_int>) {
+        match saved_fd {
+            Some(saved) => {
+                _dup2(saved, fd);
+                _close(saved);
+            }
+            None => {
+                _close(fd);
+            }
+        }
+    }
+
+    impl Drop for Saved {
+        fn drop(&mut self) {
+            // SAFETY: the original handle is put back before the descriptor
+            // owning the pipe's write handle is restored, which closes the
+            // last write end so the reader sees end-of-file.
+            unsafe {
+                SetStdHandle(self.std_handle, self.original);
+                restore_fd(self.fd, self.saved_fd);
+            }
+        }
+    }
+}
+
+/// Routes the process's stdout and stderr into a collector while alive.
+///
+/// Returned by [`TestLogCollector::capture_stdio`]. Dropping the guard
+/// restores the original streams and collects everything written during
+/// the capture. If the collector is locked at that moment, for instance by
+/// the thread dropping the guard, the last lines are collected in the
+/// background as soon as it is unlocked.
+#[must_use = "stdio is only captured while the guard is alive"]
+pub struct StdioCapture {
+    collector: SharedCollector,
+    redirects: Vec<Redirect>,
+    _lock: MutexGuard<'static, ()>,
+}
+
+struct Redirect {
+    stream: Stream,
+    saved: Option<sys::Saved>,
+    reader: Option<JoinHandle<Vec<u8>>>,
+}
+
+fn flush_std_streams() {
+    let _ = io::stdout().flush();
+    let _ = io::stderr().flush();
+}
+
+impl Redirect {
+    fn new(stream: Stream, collector: SharedCollector) -> io::Result<Self> {
+        let (reader, saved) = sys::redirect(stream)?;
+        let reader = thread::Builder::new()
+            .name("test_log_collector stdio".into())
+            .spawn(move || drain(reader, &stream.label(), &collector))?;
+        Ok(Self {
+            stream,
+            saved: Some(saved),
+            reader: Some(reader),
+        })
+    }
+
+    /// Restores the stream and returns what its reader could not collect.
+    fn finish(&mut self) -> Vec<u8> {
+        self.saved.take();
+        self.reader
+            .take()
+            .and_then(|reader| reader.join().ok())
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for Redirect {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+/// Copies complete lines from the pipe into the collector, so output from
+/// stdout and stderr never interleaves within a line, and returns what is
+/// left at end-of-file.
+///
+/// The reader never waits for the collector's lock: while it is held, the
+/// lines are buffered, so the pipe keeps draining and neither writers nor
+/// the guard's drop can block on a lock held by the test.
+fn drain(mut reader: File, source: &Label, collector: &SharedCollector) -> Vec<u8> {
+    let mut pending = Vec::new();
+    let mut buf = [0; 8192];
+    loop {
+        match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => pending.extend_from_slice(&buf[..n]),
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(_) => break,
+        }
+        if let Some(end) = pending.iter().rposition(|&byte| byte == b'\n') {
+            let locked = match collector.try_lock() {
+                Ok(collector) => Some(collector),
+                Err(TryLockError::Poisoned(poisoned)) => Some(poisoned.into_inner()),
+                Err(TryLockError::WouldBlock) => None,
+            };
+            if let Some(mut collector) = locked {
+                let _ = collector.write_all_via(source, &pending[..=end]);
+                pending.drain(..=end);
+            }
+        }
+    }
+    pending
+}
+
+/// Collects the output left by the readers, each ending as a whole line.
+fn deliver(collector: &mut TestLogCollector, leftovers: &[(Label, Vec<u8>)]) {
+    for (source, bytes) in leftovers {
+        let _ = collector.write_all_via(source, bytes);
+        if !bytes.ends_with(b"\n") {
+            let _ = collector.write_all_via(source, b"\n");
+        }
+    }
+}
+
+impl Drop for StdioCapture {
+    fn drop(&mut self) {
+        flush_std_streams();
+        let leftovers: Vec<(Label, Vec<u8>)> = self
+            .redirects
+            .iter_mut()
+            .map(|redirect| (redirect.stream.label(), redirect.finish()))
+            .filter(|(_, bytes)| !bytes.is_empty())
+            .collect();
+        if leftovers.is_empty() {
+            return;
+        }
+        let deadline = Instant::now() + DELIVERY_WAIT;
+        loop {
+            match self.collector.try_lock() {
+                Ok(mut collector) => return deliver(&mut collector, &leftovers),
+                Err(TryLockError::Poisoned(poisoned)) => {
+                    return deliver(&mut poisoned.into_inner(), &leftovers);
+                }
+                Err(TryLockError::WouldBlock) if Instant::now() < deadline => {
+                    thread::sleep(Duration::from_millis(1));
+                }
+                Err(TryLockError::WouldBlock) => break,
+            }
+        }
+        let collector = self.collector.clone();
+        thread::spawn(move || {
+            let mut collector = collector
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            deliver(&mut collector, &leftovers);
+        });
+    }
+}
+
+impl TestLogCollector {
+    /// Redirects the process's stdout and stderr into `collector` until the
+    /// returned guard is dropped.
+    ///
+    /// Everything written to the standard streams is collected, including
+    /// output from C libraries, child processes that inherit stdio, and
+    /// direct writes to `io::stdout()`/`io::stderr()`. Lines from the two
+    /// streams are kept whole. Only one capture can be active at a time;
+    /// a second call blocks until the first guard is dropped.
+    ///
+    /// On Unix, descriptors 1 and 2 are redirected. On Windows, the standard
+    /// handles and the C runtime's descriptors 1 and 2 are.
+    ///
+    /// Note that libtest captures `print!`/`println!` from tests before they
+    /// reach the streams unless the tests run with `--nocapture`. Output
+    /// from other tests running in parallel is captured too.
+    ///
+    /// Lines written while the collector is locked are collected once it
+    /// is unlocked and more output arrives, or when the guard is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pipes cannot be created or the streams
+    /// cannot be redirected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let collector = TestLogCollector::new_shared();
+    /// {
+    ///     let _capture = TestLogCollector::capture_stdio(&collector).unwrap();
+    ///     std::io::stdout().write_all(b"printed by a dependency\n").unwrap();
+    /// }
+    ///
+    /// assert!(collector.lock().unwrap().contains("printed by a dependency"));
+    /// ```
+    pub fn capture_stdio(collector: &SharedCollector) -> io::Result<StdioCapture> {
+        let lock = CAPTURE_LOCK
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        flush_std_streams();
+        let stdout = Redirect::new(Stream::Stdout, collector.clone())?;
+        let stderr = Redirect::new(Stream::Stderr, collector.clone())?;
+        Ok(StdioCapture {
+            collector: collector.clone(),
+            redirects: vec![stdout, stderr],
+            _lock: lock,
+        })
+    }
+}
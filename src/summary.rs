@@ -0,0 +1,155 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::SystemTime;
+
+use crate::{Level, TestLogCollector};
+
+/// Statistics about the collected lines, returned by
+/// [`TestLogCollector::summary`].
+///
+/// The `Display` rendering is a short multi-line report suited to a failure
+/// message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Summary {
+    lines: usize,
+    bytes: usize,
+    levels: BTreeMap<Level, usize>,
+    targets: BTreeMap<String, usize>,
+    length_histogram: BTreeMap<usize, usize>,
+    first: Option<SystemTime>,
+    last: Option<SystemTime>,
+}
+
+impl Summary {
+    /// Returns the number of lines.
+    pub fn lines(&self) -> usize {
+        self.lines
+    }
+
+    /// Returns the total length of the lines in bytes, without terminators.
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    /// Returns the number of lines at `level`.
+    pub fn level_count(&self, level: Level) -> usize {
+        self.levels.get(&level).copied().unwrap_or(0)
+    }
+
+    /// Returns the number of lines at each level that occurs.
+    pub fn levels(&self) -> &BTreeMap<Level, usize> {
+        &self.levels
+    }
+
+    /// Returns the number of structured records from each target.
+    pub fn targets(&self) -> &BTreeMap<String, usize> {
+        &self.targets
+    }
+
+    /// Returns the number of lines per length bucket, keyed by the bucket's
+    /// inclusive upper bound: 0, then powers of two.
+    pub fn length_histogram(&self) -> &BTreeMap<usize, usize> {
+        &self.length_histogram
+    }
+
+    /// Returns when the first line was completed.
+    pub fn first_timestamp(&self) -> Option<SystemTime> {
+        self.first
+    }
+
+    /// Returns when the last line was completed.
+    pub fn last_timestamp(&self) -> Option<SystemTime> {
+        self.last
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} lines, {} bytes", self.lines, self.bytes)?;
+        if let (Some(first), Some(last)) = (self.first, self.last) {
+            let span = last.duration_since(first).unwrap_or_default();
+            write!(f, " over {:?}", span)?;
+        }
+        let section = |f: &mut fmt::Formatter<'_>, title: &str, entries: Vec<String>| {
+            if entries.is_empty() {
+                Ok(())
+            } else {
+                write!(f, "\n{}: {}", title, entries.join(", "))
+            }
+        };
+        section(
+            f,
+            "levels",
+            self.levels
+                .iter()
+                .map(|(level, count)| format!("{} {}", level, count))
+                .collect(),
+        )?;
+        section(
+            f,
+            "targets",
+            self.targets
+                .iter()
+                .map(|(target, count)| format!("{} {}", target, count))
+                .collect(),
+        )?;
+        section(
+            f,
+            "line lengths",
+            self.length_histogram
+                .iter()
+                .map(|(bound, count)| format!("<={} {}", bound, count))
+                .collect(),
+        )
+    }
+}
+
+impl TestLogCollector {
+    /// Returns statistics about the collected lines: counts per level and
+    /// target, total bytes, a line-length histogram, and the first and last
+    /// timestamps.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::{Level, TestLogCollector};
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// collector.log(Level::Debug, "myapp", "polling");
+    /// writeln!(collector, "WARN slow").unwrap();
+    ///
+    /// let summary = collector.summary();
+    /// assert_eq!(summary.level_count(Level::Debug), 1);
+    /// assert_eq!(summary.level_count(Level::Warn), 1);
+    /// assert_eq!(summary.targets()["myapp"], 1);
+    /// assert!(summary.to_string().starts_with("2 lines, 29 bytes"));
+    /// ```
+    pub fn summary(&self) -> Summary {
+        let mut summary = Summary {
+            lines: self.lines.len(),
+            bytes: 0,
+            levels: BTreeMap::new(),
+            targets: BTreeMap::new(),
+            length_histogram: BTreeMap::new(),
+            first: self.meta.front().map(|meta| meta.timestamp),
+            last: self.meta.back().map(|meta| meta.timestamp),
+        };
+        for (line, meta) in self.lines.iter().zip(&self.meta) {
+            summary.bytes += line.len();
+            if let Some(level) = meta.level {
+                *summary.levels.entry(level).or_default() += 1;
+            }
+            if let Some(target) = &meta.target {
+                *summary.targets.entry(target.to_string()).or_default() += 1;
+            }
+            let bound = if line.is_empty() {
+                0
+            } else {
+                line.len().next_power_of_two()
+            };
+            *summary.length_histogram.entry(bound).or_default() += 1;
+        }
+        summary
+    }
+}
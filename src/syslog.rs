@@ -0,0 +1,268 @@
+use crate::{Level, TestLogCollector};
+
+/// A line in syslog framing, parsed by [`SyslogRecord::parse`].
+///
+/// Both RFC 5424 (`<34>1 2003-10-11T22:14:15.003Z host app 42 ID47 - msg`)
+/// and the older BSD format of RFC 3164 (`<34>Oct 11 22:14:15 host app[42]:
+/// msg`) are recognized. Fields the line leaves out, or sets to the nil
+/// value `-`, are `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyslogRecord {
+    facility: u8,
+    severity: u8,
+    version: Option<u8>,
+    timestamp: Option<String>,
+    hostname: Option<String>,
+    app_name: Option<String>,
+    proc_id: Option<String>,
+    msg_id: Option<String>,
+    structured_data: Option<String>,
+    message: String,
+}
+
+impl SyslogRecord {
+    /// Parses a syslog-framed line, or returns `None` if it does not start
+    /// with a valid `<PRI>` header.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::{Level, SyslogRecord};
+    ///
+    /// let record = SyslogRecord::parse("<13>Feb  5 17:32:18 web01 nginx[811]: worker started").unwrap();
+    /// assert_eq!(record.facility(), 1);
+    /// assert_eq!(record.severity(), 5);
+    /// assert_eq!(record.level(), Level::Info);
+    /// assert_eq!(record.hostname(), Some("web01"));
+    /// assert_eq!(record.app_name(), Some("nginx"));
+    /// assert_eq!(record.proc_id(), Some("811"));
+    /// assert_eq!(record.message(), "worker started");
+    ///
+    /// let record = SyslogRecord::parse(
+    ///     "<165>1 2003-10-11T22:14:15.003Z mymachine evntslog - ID47 [exampleSDID@32473 iut=\"3\"] An application event",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(record.version(), Some(1));
+    /// assert_eq!(record.severity(), 5);
+    /// assert_eq!(record.proc_id(), None);
+    /// assert_eq!(record.msg_id(), Some("ID47"));
+    /// assert_eq!(record.structured_data(), Some("[exampleSDID@32473 iut=\"3\"]"));
+    /// assert_eq!(record.message(), "An application event");
+    /// ```
+    pub fn parse(line: &str) -> Option<Self> {
+        let rest = line.strip_prefix('<')?;
+        let end = rest.find('>')?;
+        let pri = &rest[..end];
+        if pri.is_empty() || pri.len() > 3 || !pri.bytes().all(|byte| byte.is_ascii_digit()) {
+            return None;
+        }
+        let pri: u8 = pri.parse().ok().filter(|&pri| pri <= 191)?;
+        let rest = &rest[end + 1..];
+        let record = Self {
+            facility: pri / 8,
+            severity: pri % 8,
+            version: None,
+            timestamp: None,
+            hostname: None,
+            app_name: None,
+            proc_id: None,
+            msg_id: None,
+            structured_data: None,
+            message: String::new(),
+        };
+        Some(match rfc5424_version(rest) {
+            Some((version, rest)) => record.rfc5424(version, rest),
+            None => record.rfc3164(rest),
+        })
+    }
+
+    fn rfc5424(mut self, version: u8, rest: &str) -> Self {
+        fn nil(field: &str) -> Option<String> {
+            (field != "-").then(|| field.to_string())
+        }
+        let mut fields = rest.splitn(6, ' ');
+        self.version = Some(version);
+        self.timestamp = fields.next().and_then(nil);
+        self.hostname = fields.next().and_then(nil);
+        self.app_name = fields.next().and_then(nil);
+        self.proc_id = fields.next().and_then(nil);
+        self.msg_id = fields.next().and_then(nil);
+        let rest = fields.next().unwrap_or("");
+        let (structured_data, message) = split_structured_data(rest);
+        self.structured_data = nil(structured_data);
+        self.message = message.trim_start_matches('\u{feff}').to_string();
+        self
+    }
+
+    fn rfc3164(mut self, rest: &str) -> Self {
+        let rest = match crate::normalize::syslog(rest.as_bytes()) {
+            Some(len) => {
+                self.timestamp = Some(rest[..len].to_string());
+                let rest = rest[len..].trim_start_matches(' ');
+                match rest.split_once(' ') {
+                    Some((hostname, rest)) if !hostname.ends_with(':') => {
+                        self.hostname = Some(hostname.to_string());
+                        rest
+                    }
+                    _ => rest,
+                }
+            }
+            None => rest,
+        };
+        // The tag is the app name, optionally followed by `[pid]`, then `:`.
+        let tag_end = rest.find([' ', ':', '[']);
+        let message = match tag_end.map(|end| rest.split_at(end)) {
+            Some((app_name, tail)) if !app_name.is_empty() && !tail.starts_with(' ') => {
+                let (proc_id, tail) = match tail.strip_prefix('[').and_then(|t| t.split_once(']')) {
+                    Some((proc_id, tail)) => (Some(proc_id.to_string()), tail),
+                    None => (None, tail),
+                };
+                match tail.strip_prefix(':') {
+                    Some(message) => {
+                        self.app_name = Some(app_name.to_string());
+                        self.proc_id = proc_id;
+                        message.strip_prefix(' ').unwrap_or(message)
+                    }
+                    None => rest,
+                }
+            }
+            _ => rest,
+        };
+        self.message = message.to_string();
+        self
+    }
+
+    /// Returns the facility code, from 0 (kernel) to 23 (local7).
+    pub fn facility(&self) -> u8 {
+        self.facility
+    }
+
+    /// Returns the severity code, from 0 (emergency) to 7 (debug).
+    pub fn severity(&self) -> u8 {
+        self.severity
+    }
+
+    /// Returns the severity as a [`Level`]: emergency to error are
+    /// [`Level::Error`], warning is [`Level::Warn`], notice and
+    /// informational are [`Level::Info`], and debug is [`Level::Debug`].
+    pub fn level(&self) -> Level {
+        match self.severity {
+            0..=3 => Level::Error,
+            4 => Level::Warn,
+            5 | 6 => Level::Info,
+            _ => Level::Debug,
+        }
+    }
+
+    /// Returns the protocol version of an RFC 5424 line, or `None` for the
+    /// BSD format.
+    pub fn version(&self) -> Option<u8> {
+        self.version
+    }
+
+    /// Returns the timestamp as written in the line.
+    pub fn timestamp(&self) -> Option<&str> {
+        self.timestamp.as_deref()
+    }
+
+    /// Returns the hostname.
+    pub fn hostname(&self) -> Option<&str> {
+        self.hostname.as_deref()
+    }
+
+    /// Returns the app name, or the tag of a BSD-format line.
+    pub fn app_name(&self) -> Option<&str> {
+        self.app_name.as_deref()
+    }
+
+    /// Returns the process id.
+    pub fn proc_id(&self) -> Option<&str> {
+        self.proc_id.as_deref()
+    }
+
+    /// Returns the message id of an RFC 5424 line.
+    pub fn msg_id(&self) -> Option<&str> {
+        self.msg_id.as_deref()
+    }
+
+    /// Returns the raw structured data of an RFC 5424 line, e.g.
+    /// `[origin ip="10.0.0.1"]`.
+    pub fn structured_data(&self) -> Option<&str> {
+        self.structured_data.as_deref()
+    }
+
+    /// Returns the free-form message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+/// Returns the RFC 5424 version and the rest of the header, if `rest`
+/// starts with a version number followed by a space.
+fn rfc5424_version(rest: &str) -> Option<(u8, &str)> {
+    let (version, rest) = rest.split_once(' ')?;
+    if version.is_empty() || version.len() > 2 || !version.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some((version.parse().ok().filter(|&v| v > 0)?, rest))
+}
+
+/// Splits RFC 5424 structured data, `-` or one or more `[...]` elements,
+/// from the message that follows it.
+fn split_structured_data(rest: &str) -> (&str, &str) {
+    if let Some(message) = rest.strip_prefix('-') {
+        return ("-", message.strip_prefix(' ').unwrap_or(message));
+    }
+    let bytes = rest.as_bytes();
+    let mut end = 0;
+    while bytes.get(end) == Some(&b'[') {
+        let (mut i, mut quoted) = (end + 1, false);
+        while let Some(&byte) = bytes.get(i) {
+            match byte {
+                b'\\' if quoted => i += 1,
+                b'"' => quoted = !quoted,
+                b']' if !quoted => break,
+                _ => {}
+            }
+            i += 1;
+        }
+        end = (i + 1).min(bytes.len());
+    }
+    if end == 0 {
+        return ("-", rest);
+    }
+    let message = &rest[end..];
+    (&rest[..end], message.strip_prefix(' ').unwrap_or(message))
+}
+
+impl TestLogCollector {
+    /// Parses every collected line in syslog framing, skipping the lines
+    /// that are not.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::{Level, TestLogCollector};
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "<30>Mar  1 09:00:00 db postgres[42]: ready").unwrap();
+    /// writeln!(collector, "<27>Mar  1 09:00:05 db postgres[42]: checkpoint failed").unwrap();
+    /// writeln!(collector, "not syslog").unwrap();
+    ///
+    /// let records = collector.syslog_records();
+    /// assert_eq!(records.len(), 2);
+    /// let errors: Vec<&str> = records
+    ///     .iter()
+    ///     .filter(|record| record.level() == Level::Error)
+    ///     .map(|record| record.message())
+    ///     .collect();
+    /// assert_eq!(errors, ["checkpoint failed"]);
+    /// ```
+    pub fn syslog_records(&self) -> Vec<SyslogRecord> {
+        self.lines
+            .iter()
+            .filter_map(|line| SyslogRecord::parse(line))
+            .collect()
+    }
+}
@@ -0,0 +1,89 @@
+use std::sync::mpsc::Receiver;
+use std::time::Duration;
+
+use crate::TestLogCollector;
+
+/// A blocking iterator over lines as they are collected, like `tail -f`.
+///
+/// Created with [`TestLogCollector::tail_iter`]. `next()` waits until the
+/// next line arrives. It returns `None` once the collector has been dropped,
+/// or when a timeout set with [`with_timeout`](Self::with_timeout) elapses
+/// without a new line.
+pub struct TailIter {
+    receiver: Receiver<String>,
+    timeout: Option<Duration>,
+}
+
+impl TailIter {
+    /// Ends the iteration when no new line arrives within `timeout`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// let mut tail = collector.tail_iter().with_timeout(Duration::from_millis(10));
+    ///
+    /// assert_eq!(tail.next(), None);
+    /// ```
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+impl Iterator for TailIter {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        match self.timeout {
+            Some(timeout) => self.receiver.recv_timeout(timeout).ok(),
+            None => self.receiver.recv().ok(),
+        }
+    }
+}
+
+impl TestLogCollector {
+    /// Returns an iterator that blocks until each new line is collected, for
+    /// tests that consume logs incrementally from another thread. Lines
+    /// collected before the call are not yielded.
+    ///
+    /// Share the collector (e.g. with [`new_shared`](Self::new_shared)) so
+    /// it can keep collecting while the iterator waits; the iterator does
+    /// not hold the lock.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use std::thread;
+    /// use std::time::Duration;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let collector = TestLogCollector::new_shared();
+    /// let tail = collector
+    ///     .lock()
+    ///     .unwrap()
+    ///     .tail_iter()
+    ///     .with_timeout(Duration::from_secs(5));
+    ///
+    /// let writer = collector.clone();
+    /// let worker = thread::spawn(move || {
+    ///     for step in 1..=3 {
+    ///         writeln!(writer.lock().unwrap(), "step {}", step).unwrap();
+    ///     }
+    /// });
+    ///
+    /// let steps: Vec<String> = tail.take(3).collect();
+    /// assert_eq!(steps, ["step 1", "step 2", "step 3"]);
+    /// worker.join().unwrap();
+    /// ```
+    pub fn tail_iter(&mut self) -> TailIter {
+        TailIter {
+            receiver: self.subscribe(),
+            timeout: None,
+        }
+    }
+}
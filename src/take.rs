@@ -0,0 +1,78 @@
+use std::ops::RangeBounds;
+
+use crate::TestLogCollector;
+
+impl TestLogCollector {
+    /// Moves all collected lines out of the collector without copying them.
+    ///
+    /// The partial line, expectations and configuration are kept, so the
+    /// collector can go on collecting.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "first").unwrap();
+    /// writeln!(collector, "second").unwrap();
+    ///
+    /// assert_eq!(collector.take_lines(), vec!["first", "second"]);
+    /// assert_eq!(collector.count(), 0);
+    /// ```
+    pub fn take_lines(&mut self) -> Vec<String> {
+        self.meta.clear();
+        self.index = None;
+        self.invalidate_shared_lines();
+        std::mem::take(&mut self.lines)
+    }
+
+    /// Removes the lines in `range` and returns them, shifting later lines
+    /// down.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds, like [`Vec::drain`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// for line in ["a", "b", "c", "d"] {
+    ///     writeln!(collector, "{}", line).unwrap();
+    /// }
+    ///
+    /// assert_eq!(collector.drain(1..3), vec!["b", "c"]);
+    /// assert_eq!(collector.clone_lines(), vec!["a", "d"]);
+    /// ```
+    pub fn drain(&mut self, range: impl RangeBounds<usize> + Clone) -> Vec<String> {
+        let drained = self.lines.drain(range.clone()).collect();
+        self.meta.drain(range);
+        self.reindex();
+        drained
+    }
+
+    /// Removes and returns up to `n` of the oldest lines.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "first").unwrap();
+    /// writeln!(collector, "second").unwrap();
+    ///
+    /// assert_eq!(collector.pop_front(1), vec!["first"]);
+    /// assert_eq!(collector.pop_front(5), vec!["second"]);
+    /// assert!(collector.pop_front(1).is_empty());
+    /// ```
+    pub fn pop_front(&mut self, n: usize) -> Vec<String> {
+        self.drain(..n.min(self.lines.len()))
+    }
+}
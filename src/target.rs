@@ -0,0 +1,220 @@
+use std::fmt;
+
+use crate::{Level, ParseLevelError, TestLogCollector};
+
+/// Per-target maximum levels parsed from an env_logger-style directive
+/// string. A filter of `None` means `off`.
+#[derive(Debug, Clone)]
+pub(crate) struct Directives {
+    default: Option<Option<Level>>,
+    targets: Vec<(String, Option<Level>)>,
+}
+
+fn parse_filter(name: &str) -> Result<Option<Level>, ParseLevelError> {
+    if name.eq_ignore_ascii_case("off") {
+        Ok(None)
+    } else {
+        name.parse().map(Some)
+    }
+}
+
+impl Directives {
+    fn parse(directives: &str) -> Result<Self, ParseLevelError> {
+        let mut parsed = Directives {
+            default: None,
+            targets: Vec::new(),
+        };
+        for directive in directives.split(',').map(str::trim) {
+            if directive.is_empty() {
+                continue;
+            }
+            match directive.split_once('=') {
+                Some((target, filter)) => parsed
+                    .targets
+                    .push((target.trim().to_string(), parse_filter(filter.trim())?)),
+                None => match parse_filter(directive) {
+                    Ok(filter) => parsed.default = Some(filter),
+                    Err(_) => parsed
+                        .targets
+                        .push((directive.to_string(), Some(Level::Trace))),
+                },
+            }
+        }
+        Ok(parsed)
+    }
+
+    /// Returns the filter of the longest target prefix matching `target`,
+    /// falling back to the default.
+    fn filter(&self, target: Option<&str>) -> Option<Option<Level>> {
+        target
+            .and_then(|target| {
+                self.targets
+                    .iter()
+                    .filter(|(prefix, _)| target.starts_with(prefix.as_str()))
+                    .max_by_key(|(prefix, _)| prefix.len())
+                    .map(|(_, filter)| *filter)
+            })
+            .or(self.default)
+    }
+}
+
+impl TestLogCollector {
+    /// Collects a structured log record from `target`, as a log or tracing
+    /// adapter would, storing it as `LEVEL target: message`.
+    ///
+    /// The record is stored as one line without affecting a partially
+    /// written line, and its target is available through
+    /// [`line_target`](Self::line_target) and subject to
+    /// [`with_directives`](Self::with_directives).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::{Level, TestLogCollector};
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// collector.log(Level::Warn, "myapp::db", format_args!("retrying in {}ms", 50));
+    ///
+    /// assert_eq!(collector, ["WARN myapp::db: retrying in 50ms"]);
+    /// assert_eq!(collector.line_level(0), Some(Level::Warn));
+    /// ```
+    pub fn log(&mut self, level: Level, target: &str, message: impl fmt::Display) {
+        self.target = Some(target.to_string());
+        self.push_line(format!("{} {}: {}", level, target, message));
+        self.target = None;
+    }
+
+    /// Returns the target of the line at `index` if it was collected with
+    /// [`log`](Self::log).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::{Level, TestLogCollector};
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// collector.log(Level::Info, "myapp", "started");
+    /// writeln!(collector, "plain").unwrap();
+    ///
+    /// assert_eq!(collector.line_target(0), Some("myapp"));
+    /// assert_eq!(collector.line_target(1), None);
+    /// ```
+    pub fn line_target(&self, index: usize) -> Option<&str> {
+        self.meta.get(index)?.target.as_deref()
+    }
+
+    /// Filters lines at capture time with env_logger-style directives, such
+    /// as `"warn,myapp=debug,hyper=off"`, so noisy dependencies never reach
+    /// the collector.
+    ///
+    /// A bare level sets the maximum level for all lines; `target=level`
+    /// overrides it for records whose target starts with `target`, the
+    /// longest match winning; a bare target admits all of its levels. Lines
+    /// without a target, such as plain written lines, use the bare level.
+    /// Lines without a detectable level are always kept. The filter applies
+    /// in addition to [`with_max_level`](Self::with_max_level).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a directive names an unknown level.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::{Level, TestLogCollector};
+    ///
+    /// let mut collector = TestLogCollector::new()
+    ///     .with_directives("warn,myapp=debug,hyper=off")
+    ///     .unwrap();
+    /// collector.log(Level::Debug, "myapp::pool", "checked out");
+    /// collector.log(Level::Info, "tokio", "worker started");
+    /// collector.log(Level::Error, "hyper::proto", "connection reset");
+    ///
+    /// assert_eq!(collector, ["DEBUG myapp::pool: checked out"]);
+    /// ```
+    pub fn with_directives(mut self, directives: &str) -> Result<Self, ParseLevelError> {
+        self.directives = Some(Directives::parse(directives)?);
+        Ok(self)
+    }
+
+    /// Keeps only structured records whose target starts with one of the
+    /// allowed prefixes, once any prefix is allowed. Plain written lines have
+    /// no target and are not affected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::{Level, TestLogCollector};
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// collector.allow_target("myapp::");
+    /// collector.log(Level::Info, "myapp::server", "listening");
+    /// collector.log(Level::Info, "hyper::server", "accepted");
+    ///
+    /// assert_eq!(collector, ["INFO myapp::server: listening"]);
+    /// assert_eq!(collector.unfiltered_count(), 2);
+    /// ```
+    pub fn allow_target(&mut self, prefix: impl Into<String>) {
+        self.allowed_targets.push(prefix.into());
+    }
+
+    /// Drops structured records whose target starts with `prefix`, even if
+    /// the target is also allowed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::{Level, TestLogCollector};
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// collector.allow_target("myapp");
+    /// collector.deny_target("myapp::health");
+    /// collector.log(Level::Info, "myapp::health", "ok");
+    /// collector.log(Level::Info, "myapp::api", "GET /users");
+    ///
+    /// assert_eq!(collector, ["INFO myapp::api: GET /users"]);
+    /// ```
+    pub fn deny_target(&mut self, prefix: impl Into<String>) {
+        self.denied_targets.push(prefix.into());
+    }
+
+    /// Returns the number of lines offered to the collector, including those
+    /// dropped at capture time by level or target filters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::{Level, TestLogCollector};
+    ///
+    /// let mut collector = TestLogCollector::new().with_max_level(Level::Info);
+    /// writeln!(collector, "INFO kept").unwrap();
+    /// writeln!(collector, "DEBUG dropped").unwrap();
+    ///
+    /// assert_eq!(collector.count(), 1);
+    /// assert_eq!(collector.unfiltered_count(), 2);
+    /// ```
+    pub fn unfiltered_count(&self) -> usize {
+        self.unfiltered_count
+    }
+
+    pub(crate) fn target_admits(&self, level: Option<Level>) -> bool {
+        if let Some(target) = self.target.as_deref() {
+            let matches = |prefix: &String| target.starts_with(prefix.as_str());
+            if self.denied_targets.iter().any(matches)
+                || !(self.allowed_targets.is_empty() || self.allowed_targets.iter().any(matches))
+            {
+                return false;
+            }
+        }
+        let filter = self
+            .directives
+            .as_ref()
+            .and_then(|directives| directives.filter(self.target.as_deref()));
+        match (level, filter) {
+            (Some(level), Some(filter)) => filter.is_some_and(|max| level <= max),
+            _ => true,
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use std::io::{self, Write};
+
+use crate::provenance::Label;
+use crate::TestLogCollector;
+
+/// A writer that collects lines and forwards every byte to another writer.
+///
+/// Created with [`TestLogCollector::tee`] or [`Tee::new`]. Forwarding to
+/// `io::stderr()` lets you watch the output live while still asserting on it
+/// afterwards.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Write;
+/// use test_log_collector::TestLogCollector;
+///
+/// let mut tee = TestLogCollector::tee(Vec::new());
+/// writeln!(tee, "visible and collected").unwrap();
+///
+/// let (collector, forwarded) = tee.into_parts();
+/// assert_eq!(collector.clone_lines(), vec!["visible and collected"]);
+/// assert_eq!(forwarded, b"visible and collected\n");
+/// ```
+pub struct Tee<W> {
+    collector: TestLogCollector,
+    inner: W,
+}
+
+impl<W: Write> Tee<W> {
+    /// Creates a tee that collects into `collector` and forwards to `inner`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{self, Write};
+    /// use test_log_collector::{Tee, TestLogCollector};
+    ///
+    /// let collector = TestLogCollector::new().with_replacement("secret", "<REDACTED>");
+    /// let mut tee = Tee::new(collector, io::sink());
+    /// writeln!(tee, "token=secret").unwrap();
+    ///
+    /// assert_eq!(tee.collector().clone_lines(), vec!["token=<REDACTED>"]);
+    /// ```
+    pub fn new(collector: TestLogCollector, inner: W) -> Self {
+        Self { collector, inner }
+    }
+
+    /// Returns the collector.
+    pub fn collector(&self) -> &TestLogCollector {
+        &self.collector
+    }
+
+    /// Returns a mutable reference to the collector.
+    pub fn collector_mut(&mut self) -> &mut TestLogCollector {
+        &mut self.collector
+    }
+
+    /// Returns the writer that output is forwarded to.
+    pub fn inner(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the writer that output is forwarded to.
+    pub fn inner_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Consumes the tee, returning the collector and the inner writer.
+    pub fn into_parts(self) -> (TestLogCollector, W) {
+        (self.collector, self.inner)
+    }
+}
+
+impl<W: Write> Write for Tee<W> {
+    /// Collects as much of `buf` as the collector accepts, then forwards
+    /// the same bytes to the inner writer, so both sides see every byte
+    /// once even when the collector makes short writes.
+    ///
+    /// If forwarding fails the error is returned, although the bytes were
+    /// already collected.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.collector.write_via(&Label::Static("via tee"), buf)?;
+        self.inner.write_all(&buf[..written])?;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.collector.flush_via(&Label::Static("via tee"))?;
+        self.inner.flush()
+    }
+}
+
+impl TestLogCollector {
+    /// Creates a writer that collects lines into a new collector and also
+    /// forwards every byte to `inner`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{self, Write};
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut tee = TestLogCollector::tee(io::stderr());
+    /// writeln!(tee, "shown on stderr while the test runs").unwrap();
+    ///
+    /// assert_eq!(tee.collector().count(), 1);
+    /// ```
+    pub fn tee<W: Write>(inner: W) -> Tee<W> {
+        Tee::new(Self::new(), inner)
+    }
+}
@@ -0,0 +1,111 @@
+use crate::TestLogCollector;
+
+/// Replaces a variable part of a line in a template.
+const WILDCARD: &str = "<*>";
+
+fn mask_token(token: &str) -> String {
+    if let Some((key, value)) = token.split_once('=') {
+        if !key.is_empty() && !key.bytes().any(|byte| byte.is_ascii_digit()) {
+            return format!("{}={}", key, mask_token(value));
+        }
+    }
+    if token.bytes().any(|byte| byte.is_ascii_digit()) {
+        WILDCARD.to_string()
+    } else {
+        token.to_string()
+    }
+}
+
+impl TestLogCollector {
+    /// Returns the template of `line`: its words with every word containing
+    /// a digit masked as `<*>`, so lines logged by the same statement with
+    /// different ids, counts or durations share a template. In `key=value`
+    /// words only the value is masked. Runs of whitespace become one space.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// assert_eq!(
+    ///     TestLogCollector::template("INFO user=42 logged in after 3ms"),
+    ///     "INFO user=<*> logged in after <*>"
+    /// );
+    /// ```
+    pub fn template(line: &str) -> String {
+        line.split_whitespace()
+            .map(mask_token)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Returns the distinct templates of the collected lines with how many
+    /// lines share each, in order of first appearance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "fetched 10 rows").unwrap();
+    /// writeln!(collector, "fetched 25 rows").unwrap();
+    /// writeln!(collector, "done").unwrap();
+    ///
+    /// assert_eq!(
+    ///     collector.templates(),
+    ///     vec![("fetched <*> rows".to_string(), 2), ("done".to_string(), 1)]
+    /// );
+    /// ```
+    pub fn templates(&self) -> Vec<(String, usize)> {
+        let mut templates: Vec<(String, usize)> = Vec::new();
+        for line in &self.lines {
+            let template = Self::template(line);
+            match templates.iter_mut().find(|(known, _)| *known == template) {
+                Some((_, count)) => *count += 1,
+                None => templates.push((template, 1)),
+            }
+        }
+        templates
+    }
+
+    /// Panics if any collected line has a template not in `known`, listing
+    /// the new templates and an example line for each.
+    ///
+    /// Record the output of [`templates`](Self::templates) once as the
+    /// baseline, then use this to catch debug logging accidentally left in.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new();
+    /// writeln!(collector, "fetched 10 rows").unwrap();
+    ///
+    /// collector.assert_no_new_templates(&["fetched <*> rows", "done"]);
+    /// ```
+    #[track_caller]
+    pub fn assert_no_new_templates(&self, known: &[&str]) {
+        let mut new: Vec<(String, &str)> = Vec::new();
+        for line in &self.lines {
+            let template = Self::template(line);
+            if !known.contains(&template.as_str()) && !new.iter().any(|(t, _)| *t == template) {
+                new.push((template, line));
+            }
+        }
+        if !new.is_empty() {
+            let listing: Vec<String> = new
+                .iter()
+                .map(|(template, line)| format!("  {:?} (e.g. {:?})", template, line))
+                .collect();
+            panic!(
+                "found {} new log message templates:\n{}",
+                new.len(),
+                listing.join("\n")
+            );
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use crate::TestLogCollector;
+
+impl TestLogCollector {
+    /// Makes the collector claim to be a terminal, or deny it, through
+    /// [`is_terminal`](Self::is_terminal), so code that formats differently
+    /// for a TTY can be exercised in both modes while captured.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let collector = TestLogCollector::new().with_terminal(true);
+    /// assert!(collector.is_terminal());
+    /// ```
+    pub fn with_terminal(mut self, terminal: bool) -> Self {
+        self.terminal = terminal;
+        self
+    }
+
+    /// Returns whether the collector claims to be a terminal, `false`
+    /// unless set with [`with_terminal`](Self::with_terminal).
+    ///
+    /// This mirrors [`std::io::IsTerminal::is_terminal`], which cannot be
+    /// implemented outside the standard library because the trait is
+    /// sealed. Code under test that should honor the claim can take the
+    /// answer as a parameter, or as a bound on its own trait implemented
+    /// for both its real output and the collector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// fn report(out: &mut TestLogCollector) {
+    ///     if out.is_terminal() {
+    ///         writeln!(out, "\x1b[32mok\x1b[0m").unwrap();
+    ///     } else {
+    ///         writeln!(out, "ok").unwrap();
+    ///     }
+    /// }
+    ///
+    /// let mut piped = TestLogCollector::new();
+    /// report(&mut piped);
+    /// assert_eq!(piped, ["ok"]);
+    ///
+    /// let mut tty = TestLogCollector::new().with_terminal(true);
+    /// report(&mut tty);
+    /// assert_eq!(tty, ["\x1b[32mok\x1b[0m"]);
+    /// ```
+    pub fn is_terminal(&self) -> bool {
+        self.terminal
+    }
+}
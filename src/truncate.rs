@@ -0,0 +1,92 @@
+use crate::TestLogCollector;
+
+/// Returns the largest char boundary of `line` that is at most `max`.
+fn floor_char_boundary(line: &str, max: usize) -> usize {
+    (0..=max.min(line.len()))
+        .rev()
+        .find(|&end| line.is_char_boundary(end))
+        .unwrap_or(0)
+}
+
+impl TestLogCollector {
+    /// Truncates lines longer than `max` bytes, replacing the excess with a
+    /// `…[truncated N bytes]` marker. The unfinished line is capped while it
+    /// is being written, so runaway output such as an accidental binary dump
+    /// cannot exhaust memory. The untruncated length is available through
+    /// [`original_len`](Self::original_len).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new().with_max_line_len(8);
+    /// writeln!(collector, "short").unwrap();
+    /// writeln!(collector, "{}", "x".repeat(1_000)).unwrap();
+    ///
+    /// assert_eq!(collector, ["short", "xxxxxxxx…[truncated 992 bytes]"]);
+    /// assert_eq!(collector.original_len(1), Some(1_000));
+    /// ```
+    pub fn with_max_line_len(mut self, max: usize) -> Self {
+        self.max_line_len = Some(max);
+        self
+    }
+
+    /// Returns the length in bytes the line at `index` had before
+    /// [`with_max_line_len`](Self::with_max_line_len) truncated it, or `None`
+    /// if the line was not truncated or `index` is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let mut collector = TestLogCollector::new().with_max_line_len(4);
+    /// writeln!(collector, "fits").unwrap();
+    /// writeln!(collector, "too long").unwrap();
+    ///
+    /// assert_eq!(collector.original_len(0), None);
+    /// assert_eq!(collector.original_len(1), Some(8));
+    /// ```
+    pub fn original_len(&self, index: usize) -> Option<usize> {
+        self.meta.get(index)?.original_len
+    }
+
+    /// Caps the unfinished line, counting the dropped bytes until the line
+    /// is completed.
+    pub(crate) fn truncate_pending(&mut self) {
+        let Some(max) = self.max_line_len else {
+            return;
+        };
+        if self.current_line.len() > max {
+            let end = floor_char_boundary(&self.current_line, max);
+            self.pending_truncated += self.current_line.len() - end;
+            self.current_line.truncate(end);
+        }
+        self.current_raw.truncate(max);
+    }
+
+    /// Truncates a completed `line` that had `truncated` bytes dropped while
+    /// pending, returning its original length if anything was cut.
+    pub(crate) fn truncate_line(
+        &self,
+        line: &mut String,
+        raw: &mut Option<Vec<u8>>,
+        truncated: usize,
+    ) -> Option<usize> {
+        let max = self.max_line_len?;
+        let original_len = line.len() + truncated;
+        if original_len <= max {
+            return None;
+        }
+        let end = floor_char_boundary(line, max);
+        line.truncate(end);
+        line.push_str(&format!("…[truncated {} bytes]", original_len - end));
+        if let Some(raw) = raw {
+            raw.truncate(max);
+        }
+        Some(original_len)
+    }
+}
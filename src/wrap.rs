@@ -0,0 +1,107 @@
+use std::io::{self, Write};
+
+use crate::provenance::Label;
+use crate::TestLogCollector;
+
+/// A transparent decorator that records what an existing writer accepts.
+///
+/// Unlike [`Tee`](crate::Tee), which always forwards whole buffers,
+/// `CollectingWriter` calls the inner writer exactly as its caller did and
+/// returns the inner writer's results unchanged. Only the bytes the inner
+/// writer reports as written are collected, so short writes and errors from
+/// a real file or socket behave exactly as they would in production.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Write;
+/// use test_log_collector::CollectingWriter;
+///
+/// let mut writer = CollectingWriter::wrap(Vec::new());
+/// writeln!(writer, "sent to the real writer").unwrap();
+///
+/// assert_eq!(writer.collector().clone_lines(), vec!["sent to the real writer"]);
+/// assert_eq!(writer.inner(), b"sent to the real writer\n");
+/// ```
+pub struct CollectingWriter<W> {
+    collector: TestLogCollector,
+    inner: W,
+}
+
+impl<W: Write> CollectingWriter<W> {
+    /// Wraps `inner`, recording into a new collector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{self, Write};
+    /// use test_log_collector::CollectingWriter;
+    ///
+    /// let mut writer = CollectingWriter::wrap(io::sink());
+    /// writeln!(writer, "hello").unwrap();
+    /// assert_eq!(writer.collector().count(), 1);
+    /// ```
+    pub fn wrap(inner: W) -> Self {
+        Self::with_collector(TestLogCollector::new(), inner)
+    }
+
+    /// Wraps `inner`, recording into `collector`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{self, Write};
+    /// use test_log_collector::{CollectingWriter, TestLogCollector};
+    ///
+    /// let collector = TestLogCollector::new().with_replacement("secret", "<REDACTED>");
+    /// let mut writer = CollectingWriter::with_collector(collector, io::sink());
+    /// writeln!(writer, "token=secret").unwrap();
+    ///
+    /// assert_eq!(writer.collector().clone_lines(), vec!["token=<REDACTED>"]);
+    /// ```
+    pub fn with_collector(collector: TestLogCollector, inner: W) -> Self {
+        Self { collector, inner }
+    }
+
+    /// Returns the collector.
+    pub fn collector(&self) -> &TestLogCollector {
+        &self.collector
+    }
+
+    /// Returns a mutable reference to the collector.
+    pub fn collector_mut(&mut self) -> &mut TestLogCollector {
+        &mut self.collector
+    }
+
+    /// Returns the wrapped writer.
+    pub fn inner(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped writer.
+    ///
+    /// Bytes written directly to the inner writer are not collected.
+    pub fn inner_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Consumes the decorator, returning the collector and the wrapped writer.
+    pub fn into_parts(self) -> (TestLogCollector, W) {
+        (self.collector, self.inner)
+    }
+}
+
+impl<W: Write> Write for CollectingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.collector
+            .write_all_via(&Label::Static("via collecting writer"), &buf[..written])?;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()?;
+        self.collector
+            .flush_via(&Label::Static("via collecting writer"))
+    }
+}
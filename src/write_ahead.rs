@@ -0,0 +1,141 @@
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::TestLogCollector;
+
+/// How often a write-ahead file is synced to disk with `fsync`.
+///
+/// Every line reaches the operating system before it is collected, so it
+/// survives the test process being killed under any policy; syncing also
+/// protects against the machine going down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncPolicy {
+    /// Sync after every line.
+    EveryLine,
+    /// Sync after every `n` lines, and on flush.
+    EveryLines(usize),
+    /// Sync only when the collector is flushed.
+    OnFlush,
+    /// Never sync explicitly.
+    Never,
+}
+
+pub(crate) struct WriteAhead {
+    path: PathBuf,
+    file: File,
+    policy: SyncPolicy,
+    unsynced: usize,
+    error: Option<io::Error>,
+}
+
+impl WriteAhead {
+    pub(crate) fn append(&mut self, line: &str) {
+        if self.error.is_some() {
+            return;
+        }
+        let mut record = String::with_capacity(line.len() + 1);
+        record.push_str(line);
+        record.push('\n');
+        let result = self.file.write_all(record.as_bytes()).and_then(|()| {
+            self.unsynced += 1;
+            match self.policy {
+                SyncPolicy::EveryLine => self.sync(),
+                SyncPolicy::EveryLines(n) if self.unsynced >= n => self.sync(),
+                _ => Ok(()),
+            }
+        });
+        self.error = result.err();
+    }
+
+    fn sync(&mut self) -> io::Result<()> {
+        self.unsynced = 0;
+        self.file.sync_data()
+    }
+
+    /// Syncs pending lines on flush and reports the first error since the
+    /// last call.
+    pub(crate) fn flush(&mut self) -> io::Result<()> {
+        if self.error.is_none() && self.unsynced > 0 && self.policy != SyncPolicy::Never {
+            self.error = self.sync().err();
+        }
+        self.take_error()
+    }
+
+    pub(crate) fn take_error(&mut self) -> io::Result<()> {
+        match self.error.take() {
+            Some(error) => Err(io::Error::new(
+                error.kind(),
+                format!("write-ahead file {}: {}", self.path.display(), error),
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+impl TestLogCollector {
+    /// Appends every line to the file at `path` before collecting it, so the
+    /// capture up to a crash or abort can be recovered by a follow-up test
+    /// with [`recover`](Self::recover). The file is created or truncated.
+    ///
+    /// A failure to append is returned by the next `write()` or `flush()`
+    /// call; the line is still collected.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from creating the file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use test_log_collector::{SyncPolicy, TestLogCollector};
+    ///
+    /// let path = std::env::temp_dir().join("test_log_collector_write_ahead_doc.log");
+    /// let mut collector = TestLogCollector::new()
+    ///     .with_write_ahead(&path, SyncPolicy::EveryLines(100))
+    ///     .unwrap();
+    /// writeln!(collector, "durable").unwrap();
+    ///
+    /// assert_eq!(std::fs::read_to_string(&path).unwrap(), "durable\n");
+    /// ```
+    pub fn with_write_ahead(
+        mut self,
+        path: impl AsRef<Path>,
+        policy: SyncPolicy,
+    ) -> io::Result<Self> {
+        let path = path.as_ref();
+        self.write_ahead = Some(WriteAhead {
+            path: path.to_path_buf(),
+            file: File::create(path)?,
+            policy,
+            unsynced: 0,
+            error: None,
+        });
+        Ok(self)
+    }
+
+    /// Loads a write-ahead file into a new collector. A torn last line
+    /// without a newline is kept as the partial line.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from reading the file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use test_log_collector::TestLogCollector;
+    ///
+    /// let path = std::env::temp_dir().join("test_log_collector_recover_doc.log");
+    /// std::fs::write(&path, "before the crash\n").unwrap();
+    ///
+    /// let collector = TestLogCollector::recover(&path).unwrap();
+    /// assert_eq!(collector.clone_lines(), vec!["before the crash"]);
+    /// ```
+    pub fn recover(path: impl AsRef<Path>) -> io::Result<TestLogCollector> {
+        let mut collector = TestLogCollector::new();
+        collector.write_all(&fs::read(path)?)?;
+        Ok(collector)
+    }
+}
@@ -0,0 +1,123 @@
+//! Attribute macros for `test_log_collector`.
+//!
+//! Use these through the `macros` feature of `test_log_collector` rather
+//! than depending on this crate directly.
+
+use proc_macro::{Delimiter, Group, Spacing, TokenStream, TokenTree};
+
+/// Turns a function into a `#[test]` whose logs are collected.
+///
+/// A fresh collector is installed as the thread's current collector for the
+/// duration of the test, reachable through
+/// `test_log_collector::current_collector()`, and its lines are printed to
+/// stderr if the test panics. The function may also declare a single
+/// parameter, which is bound to the collector's shared handle:
+///
+/// ```
+/// use std::io::Write;
+/// use test_log_collector::{collect_logs, SharedCollector};
+///
+/// #[collect_logs]
+/// fn logs_are_collected(logs: SharedCollector) {
+///     writeln!(logs.lock().unwrap(), "hello").unwrap();
+///     assert_eq!(logs.lock().unwrap().count(), 1);
+/// }
+/// # fn main() {}
+/// ```
+///
+/// The collector is not installed as a global `log` or `tracing` sink; route
+/// writes to it through the parameter or `current_collector()`.
+#[proc_macro_attribute]
+pub fn collect_logs(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return compile_error("#[collect_logs] does not take arguments");
+    }
+    match expand(item) {
+        Ok(tokens) => tokens,
+        Err(message) => compile_error(message),
+    }
+}
+
+fn expand(item: TokenStream) -> Result<TokenStream, &'static str> {
+    let mut tokens: Vec<TokenTree> = item.into_iter().collect();
+
+    let body = match tokens.pop() {
+        Some(TokenTree::Group(body)) if body.delimiter() == Delimiter::Brace => body,
+        _ => return Err("#[collect_logs] must be applied to a function with a body"),
+    };
+    let fn_pos = tokens
+        .iter()
+        .position(|token| matches!(token, TokenTree::Ident(ident) if ident.to_string() == "fn"))
+        .ok_or("#[collect_logs] must be applied to a function")?;
+    let params_pos = tokens[fn_pos..]
+        .iter()
+        .position(|token| {
+            matches!(token, TokenTree::Group(group) if group.delimiter() == Delimiter::Parenthesis)
+        })
+        .map(|pos| fn_pos + pos)
+        .ok_or("#[collect_logs] could not find the function's parameters")?;
+
+    let params = match &tokens[params_pos] {
+        TokenTree::Group(group) => group.stream(),
+        _ => unreachable!(),
+    };
+    let binding = parameter_binding(params)?;
+    tokens[params_pos] = TokenTree::Group(Group::new(Delimiter::Parenthesis, TokenStream::new()));
+
+    let mut new_body: TokenStream =
+        "let __collect_logs_guard = ::test_log_collector::CollectLogsGuard::install();"
+            .parse()
+            .unwrap();
+    new_body.extend(binding);
+    new_body.extend([TokenTree::Group(body)]);
+
+    let mut output: TokenStream = "#[test]".parse().unwrap();
+    output.extend(tokens);
+    output.extend([TokenTree::Group(Group::new(Delimiter::Brace, new_body))]);
+    Ok(output)
+}
+
+/// Turns `pat: Type` into `let pat: Type = <collector handle>;`.
+fn parameter_binding(params: TokenStream) -> Result<TokenStream, &'static str> {
+    let mut tokens: Vec<TokenTree> = params.into_iter().collect();
+    if tokens.is_empty() {
+        return Ok(TokenStream::new());
+    }
+    if matches!(tokens.last(), Some(TokenTree::Punct(punct)) if punct.as_char() == ',') {
+        tokens.pop();
+    }
+    if tokens
+        .iter()
+        .any(|token| matches!(token, TokenTree::Punct(punct) if punct.as_char() == ','))
+    {
+        return Err("#[collect_logs] functions take at most one parameter");
+    }
+
+    // Look for a `:` that is not part of a `::` path separator.
+    let has_type = (0..tokens.len()).any(|i| {
+        let is_alone_colon = matches!(&tokens[i], TokenTree::Punct(p)
+            if p.as_char() == ':' && p.spacing() == Spacing::Alone);
+        let follows_joint_colon = i > 0
+            && matches!(&tokens[i - 1], TokenTree::Punct(p)
+                if p.as_char() == ':' && p.spacing() == Spacing::Joint);
+        is_alone_colon && !follows_joint_colon
+    });
+    if !has_type {
+        return Err("#[collect_logs] parameter must have a type");
+    }
+
+    let mut binding: TokenStream = "let".parse().unwrap();
+    binding.extend(tokens);
+    binding.extend(
+        "= ::test_log_collector::CollectLogsGuard::collector(&__collect_logs_guard);"
+            .parse::<TokenStream>()
+            .unwrap(),
+    );
+    Ok(binding)
+}
+
+fn compile_error(message: &str) -> TokenStream {
+    format!("::core::compile_error!({:?});", message)
+        .parse()
+        .unwrap()
+}
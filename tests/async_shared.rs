@@ -0,0 +1,120 @@
+use std::future::Future;
+use std::io::Write;
+use std::pin::pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+use test_log_collector::TestLogCollector;
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut context = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+#[test]
+fn test_waiting_task_is_woken_when_guard_is_dropped() {
+    let collector = TestLogCollector::new_shared_async();
+    let mut guard = collector.try_lock().unwrap();
+    writeln!(guard, "first").unwrap();
+
+    let (locked_sender, locked) = mpsc::channel();
+    let waiter = {
+        let collector = collector.clone();
+        thread::spawn(move || {
+            block_on(async {
+                let mut logs = collector.lock().await;
+                locked_sender.send(()).unwrap();
+                writeln!(logs, "second").unwrap();
+            })
+        })
+    };
+
+    assert!(locked
+        .recv_timeout(std::time::Duration::from_millis(50))
+        .is_err());
+    drop(guard);
+    waiter.join().unwrap();
+
+    assert_eq!(block_on(collector.clone_lines()), ["first", "second"]);
+}
+
+#[test]
+fn test_guard_can_move_between_threads() {
+    let collector = TestLogCollector::new_shared_async();
+    let guard = block_on(collector.lock());
+    thread::scope(|scope| {
+        scope.spawn(move || {
+            let mut guard = guard;
+            writeln!(guard, "from another thread").unwrap();
+        });
+    });
+
+    assert!(block_on(collector.contains("another thread")));
+}
+
+#[derive(Default)]
+struct CountingWaker(AtomicUsize);
+
+impl Wake for CountingWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+impl CountingWaker {
+    fn count(&self) -> usize {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+#[test]
+fn test_unlock_wakes_one_waiter_and_cancellation_passes_it_on() {
+    let collector = TestLogCollector::new_shared_async();
+    let guard = collector.try_lock().unwrap();
+
+    let (first_count, second_count) = (
+        Arc::new(CountingWaker::default()),
+        Arc::new(CountingWaker::default()),
+    );
+    let first_waker = Waker::from(first_count.clone());
+    let second_waker = Waker::from(second_count.clone());
+    let mut first = Box::pin(collector.lock());
+    let mut second = Box::pin(collector.lock());
+    for _ in 0..3 {
+        assert!(first
+            .as_mut()
+            .poll(&mut Context::from_waker(&first_waker))
+            .is_pending());
+    }
+    assert!(second
+        .as_mut()
+        .poll(&mut Context::from_waker(&second_waker))
+        .is_pending());
+
+    drop(guard);
+    assert_eq!((first_count.count(), second_count.count()), (1, 0));
+
+    drop(first);
+    assert_eq!(second_count.count(), 1);
+    assert!(second
+        .as_mut()
+        .poll(&mut Context::from_waker(&second_waker))
+        .is_ready());
+}
@@ -0,0 +1,40 @@
+use std::io::Write;
+use test_log_collector::{collect_logs, current_collector, SharedCollector};
+
+#[collect_logs]
+fn test_collect_logs_without_parameter() {
+    let logs = current_collector().expect("collector installed");
+    writeln!(logs.lock().unwrap(), "via the thread-local accessor").unwrap();
+    assert_eq!(logs.lock().unwrap().count(), 1);
+}
+
+#[collect_logs]
+fn test_collect_logs_injects_handle(logs: SharedCollector) {
+    writeln!(logs.lock().unwrap(), "via the parameter").unwrap();
+    let current = current_collector().unwrap();
+    assert!(std::sync::Arc::ptr_eq(&current, &logs));
+}
+
+#[collect_logs]
+fn test_collect_logs_with_path_type(logs: test_log_collector::SharedCollector) {
+    assert_eq!(logs.lock().unwrap().count(), 0);
+}
+
+#[collect_logs]
+fn test_collect_logs_returning_result() -> Result<(), std::io::Error> {
+    let logs = current_collector().unwrap();
+    writeln!(logs.lock().unwrap(), "result")?;
+    Ok(())
+}
+
+#[collect_logs]
+#[should_panic(expected = "expected failure")]
+fn test_collect_logs_dumps_on_panic(logs: SharedCollector) {
+    writeln!(logs.lock().unwrap(), "printed because the test panics").unwrap();
+    panic!("expected failure");
+}
+
+#[test]
+fn test_no_collector_outside_scope() {
+    assert!(current_collector().is_none());
+}
@@ -0,0 +1,59 @@
+use std::io::Write;
+use std::sync::{Arc, Barrier};
+use std::thread;
+use test_log_collector::{ConcurrentLogCollector, TestLogCollector};
+
+#[test]
+fn test_concurrent_writers_keep_lines_whole_and_ordered() {
+    let logs = ConcurrentLogCollector::new();
+    let barrier = Arc::new(Barrier::new(8));
+    let handles: Vec<_> = (0..8)
+        .map(|worker| {
+            let mut writer = logs.writer();
+            let barrier = barrier.clone();
+            thread::spawn(move || {
+                barrier.wait();
+                for i in 0..500 {
+                    write!(writer, "worker {} ", worker).unwrap();
+                    writeln!(writer, "step {}", i).unwrap();
+                }
+                write!(writer, "worker {} unterminated", worker).unwrap();
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    let collector = logs.into_collector();
+    assert_eq!(collector.count(), 8 * 501);
+    for worker in 0..8 {
+        let prefix = format!("worker {} step ", worker);
+        let steps: Vec<usize> = collector
+            .iter()
+            .filter_map(|line| line.strip_prefix(&prefix))
+            .map(|step| step.parse().unwrap())
+            .collect();
+        assert_eq!(steps, (0..500).collect::<Vec<_>>());
+    }
+    let seqs: Vec<u64> = collector
+        .lines_sequenced()
+        .iter()
+        .map(|(seq, _)| *seq)
+        .collect();
+    assert!(seqs.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[test]
+fn test_consolidate_applies_collector_configuration() {
+    let mut logs = ConcurrentLogCollector::with_collector(
+        TestLogCollector::new().with_replacement("secret", "***"),
+    );
+    let mut writer = logs.writer();
+    writeln!(writer, "token secret").unwrap();
+    write!(writer, "pending").unwrap();
+
+    assert_eq!(*logs.consolidate(), ["token ***"]);
+    drop(writer);
+    assert_eq!(logs.consolidate().last(), Some("pending"));
+}
@@ -0,0 +1,42 @@
+#![cfg(windows)]
+
+use std::ffi::CString;
+use std::os::raw::c_char;
+use std::time::{Duration, Instant};
+use test_log_collector::TestLogCollector;
+
+#[link(name = "kernel32")]
+extern "system" {
+    fn OutputDebugStringA(message: *const c_char);
+    fn GetCurrentProcessId() -> u32;
+}
+
+#[test]
+fn test_capture_debug_output_collects_messages() {
+    let collector = TestLogCollector::new_shared();
+    {
+        let _capture = TestLogCollector::capture_debug_output(&collector).unwrap();
+        let message = CString::new("from the debug channel\r\n").unwrap();
+        // SAFETY: `message` is a valid NUL-terminated string.
+        unsafe { OutputDebugStringA(message.as_ptr()) };
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !collector.lock().unwrap().contains("from the debug channel") {
+            assert!(Instant::now() < deadline, "message never collected");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    let collector = collector.lock().unwrap();
+    let index = collector
+        .lines()
+        .iter()
+        .position(|line| line == "from the debug channel")
+        .unwrap();
+    // SAFETY: no preconditions.
+    let pid = unsafe { GetCurrentProcessId() };
+    assert_eq!(
+        collector.provenance(index).unwrap(),
+        [format!("via OutputDebugString pid {}", pid)]
+    );
+}
@@ -0,0 +1,208 @@
+use std::io::Write;
+use test_log_collector::{Matcher, MessageCatalog, TestLogCollector};
+
+#[test]
+fn test_matcher_constructors() {
+    assert!(Matcher::contains("disk").matches("WARN disk full"));
+    assert!(Matcher::exact("done").matches("done"));
+    assert!(!Matcher::exact("done").matches("done!"));
+    assert!(Matcher::starts_with("WARN").matches("WARN disk full"));
+    assert!(Matcher::ends_with("full").matches("WARN disk full"));
+    assert_eq!(Matcher::from("x").description(), r#"contains "x""#);
+}
+
+#[test]
+fn test_expectations_met() {
+    let mut collector = TestLogCollector::new();
+    collector.expect_count("retry", 3);
+    collector.expect_at_least(Matcher::starts_with("INFO"), 1);
+    collector.expect_at_most("ERROR", 0);
+    collector.expect_sequence([
+        Matcher::from("connect"),
+        Matcher::exact("connected"),
+        Matcher::from("closed"),
+    ]);
+
+    writeln!(collector, "INFO connect").unwrap();
+    for _ in 0..3 {
+        writeln!(collector, "WARN retry").unwrap();
+    }
+    writeln!(collector, "connected").unwrap();
+    writeln!(collector, "closed").unwrap();
+
+    assert!(collector.unmet_expectations().is_empty());
+    collector.verify();
+}
+
+#[test]
+fn test_expectation_failures_pluralize_line() {
+    let mut collector = TestLogCollector::new();
+    collector.expect_count("ready", 1);
+    collector.expect_at_least("ready", 2);
+
+    assert_eq!(
+        collector.unmet_expectations(),
+        vec![
+            r#"expected exactly 1 line that contains "ready", saw 0"#,
+            r#"expected at least 2 lines that contains "ready", saw 0"#,
+        ]
+    );
+}
+
+#[test]
+fn test_expectations_report_every_failure() {
+    let mut collector = TestLogCollector::new();
+    writeln!(collector, "ERROR boom").unwrap();
+    collector.expect_at_most("ERROR", 0);
+    collector.expect_sequence([Matcher::exact("b"), Matcher::exact("a")]);
+    writeln!(collector, "a").unwrap();
+    writeln!(collector, "b").unwrap();
+
+    assert_eq!(
+        collector.unmet_expectations(),
+        vec![
+            r#"expected at most 0 lines that contains "ERROR", saw 1"#,
+            r#"sequence stalled at step 2 of 2, waiting for a line that equals "a""#,
+        ]
+    );
+
+    collector.clear();
+    assert_eq!(
+        collector.unmet_expectations(),
+        vec![r#"sequence stalled at step 1 of 2, waiting for a line that equals "b""#]
+    );
+}
+
+#[test]
+#[should_panic(expected = "unmet log expectations")]
+fn test_verify_panics() {
+    let mut collector = TestLogCollector::new();
+    collector.expect_count("ready", 1);
+    collector.verify();
+}
+
+#[test]
+fn test_evaluate_and_drop_keeps_no_lines() {
+    let mut collector = TestLogCollector::new().with_evaluate_and_drop();
+    collector.expect_count("even", 500);
+    for i in 0..1_000 {
+        writeln!(collector, "{}", if i % 2 == 0 { "even" } else { "odd" }).unwrap();
+    }
+
+    assert_eq!(collector.count(), 0);
+    collector.verify();
+}
+
+fn catalog() -> MessageCatalog {
+    MessageCatalog::new()
+        .with_message("connected", "en", "Connected to {host} in {ms}ms")
+        .with_message("connected", "de", "Verbunden mit {host} in {ms}ms")
+        .with_message("shutdown", "en", "Shutting down")
+        .with_message("shutdown", "de", "Fahre herunter")
+}
+
+#[test]
+fn test_catalog_matches_any_locale() {
+    let mut collector = TestLogCollector::new().with_catalog(catalog());
+    writeln!(collector, "INFO Verbunden mit db.local in 12ms").unwrap();
+    writeln!(collector, "INFO Connected to cache in 3ms").unwrap();
+    writeln!(collector, "INFO Connected to").unwrap();
+
+    assert!(collector.contains_message("connected"));
+    assert!(!collector.contains_message("shutdown"));
+    assert_eq!(
+        collector.message_lines("connected"),
+        vec![
+            "INFO Verbunden mit db.local in 12ms",
+            "INFO Connected to cache in 3ms"
+        ]
+    );
+}
+
+#[test]
+fn test_catalog_matcher_in_expectations() {
+    let catalog = catalog();
+    let mut collector = TestLogCollector::new();
+    collector.expect_sequence([catalog.matcher("connected"), catalog.matcher("shutdown")]);
+
+    writeln!(collector, "Verbunden mit a in 1ms").unwrap();
+    writeln!(collector, "Fahre herunter").unwrap();
+
+    collector.verify();
+}
+
+#[test]
+#[should_panic(expected = "message catalog has no message \"missing\"")]
+fn test_catalog_unknown_id_panics() {
+    let collector = TestLogCollector::new().with_catalog(catalog());
+    collector.contains_message("missing");
+}
+
+#[test]
+fn test_expect_panic_with_logs() {
+    let logs = TestLogCollector::new_shared();
+    TestLogCollector::expect_panic_with_logs(
+        &logs,
+        Matcher::starts_with("invalid state"),
+        "transition",
+        || {
+            let mut collector = logs.lock().unwrap();
+            writeln!(collector, "transition idle -> running").unwrap();
+            write!(collector, "partial").unwrap();
+            panic!("invalid state: {}", 42);
+        },
+    );
+
+    // The lock was held while panicking, but the collector is still usable.
+    TestLogCollector::expect_panic_with_logs(&logs, "second", "partial", || panic!("second"));
+}
+
+#[test]
+#[should_panic(expected = "but none occurred")]
+fn test_expect_panic_with_logs_requires_panic() {
+    let logs = TestLogCollector::new_shared();
+    TestLogCollector::expect_panic_with_logs(&logs, "boom", "anything", || {});
+}
+
+#[test]
+#[should_panic(expected = "got \"kaboom\"")]
+fn test_expect_panic_with_logs_checks_message() {
+    let logs = TestLogCollector::new_shared();
+    TestLogCollector::expect_panic_with_logs(&logs, Matcher::exact("boom"), "x", || {
+        panic!("kaboom")
+    });
+}
+
+#[test]
+#[should_panic(expected = "but no line logged before the panic contains \"connecting\"")]
+fn test_expect_panic_with_logs_checks_logs() {
+    let logs = TestLogCollector::new_shared();
+    TestLogCollector::expect_panic_with_logs(&logs, "boom", "connecting", || {
+        writeln!(logs.lock().unwrap(), "starting").unwrap();
+        panic!("boom")
+    });
+}
+
+#[test]
+#[should_panic(
+    expected = "expected exactly 1 line that contains \"retry\", found 2:\n    [0] start\n  > [1] retry 1\n    [2] a\n    [3] b\n  --\n    [5] d\n    [6] e\n  > [7] retry 2\n    [8] end"
+)]
+fn test_assert_count_shows_matches_in_context() {
+    let mut collector = TestLogCollector::new();
+    for line in [
+        "start", "retry 1", "a", "b", "c", "d", "e", "retry 2", "end",
+    ] {
+        writeln!(collector, "{}", line).unwrap();
+    }
+    collector.assert_count_at_least("retry", 2);
+    collector.assert_count_at_most("retry", 2);
+    collector.assert_count("retry", 1);
+}
+
+#[test]
+#[should_panic(expected = "found 0; last lines collected:\n    [1] one\n    [2] two\n")]
+fn test_assert_count_without_matches_shows_tail() {
+    let mut collector = TestLogCollector::new();
+    write!(collector, "zero\none\ntwo\nthree\nfour\nfive\n").unwrap();
+    collector.assert_count_at_least("heartbeat", 1);
+}
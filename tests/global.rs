@@ -0,0 +1,63 @@
+use std::io::Write;
+use std::panic;
+use std::thread;
+use test_log_collector::{global_collector, registry, CollectLogsGuard, Level, TestLogCollector};
+
+#[test]
+fn test_set_global_uninstalls_even_when_verification_fails() {
+    let result = panic::catch_unwind(|| {
+        let guard = TestLogCollector::set_global().verify_on_drop();
+        guard
+            .collector()
+            .lock()
+            .unwrap()
+            .expect_at_least("ready", 1);
+    });
+    assert!(result.is_err());
+    assert!(global_collector().is_none());
+
+    let guard = TestLogCollector::set_global().verify_on_drop();
+    guard.collector().lock().unwrap().expect_count("ready", 1);
+    writeln!(global_collector().unwrap().lock().unwrap(), "ready").unwrap();
+    drop(guard);
+}
+
+#[test]
+fn test_set_global_serializes_installs() {
+    let first = TestLogCollector::set_global();
+    let waiter = thread::spawn(|| {
+        let second = TestLogCollector::set_global();
+        writeln!(global_collector().unwrap().lock().unwrap(), "second").unwrap();
+        second.collector()
+    });
+    thread::sleep(std::time::Duration::from_millis(50));
+    writeln!(global_collector().unwrap().lock().unwrap(), "first").unwrap();
+    let first_collector = first.collector();
+    drop(first);
+
+    let second_collector = waiter.join().unwrap();
+    assert_eq!(*first_collector.lock().unwrap(), ["first"]);
+    assert_eq!(*second_collector.lock().unwrap(), ["second"]);
+}
+
+#[test]
+fn test_registry_routes_to_the_global_collector() {
+    let guard = TestLogCollector::set_global();
+    thread::spawn(|| {
+        registry::log(Level::Info, "app", "from log");
+        writeln!(registry::writer(), "from the writer").unwrap();
+
+        let scoped = CollectLogsGuard::install();
+        registry::log(Level::Info, "app", "scoped");
+        assert_eq!(*scoped.collector().lock().unwrap(), ["INFO app: scoped"]);
+    })
+    .join()
+    .unwrap();
+    let collector = guard.collector();
+    drop(guard);
+
+    assert_eq!(
+        *collector.lock().unwrap(),
+        ["INFO app: from log", "from the writer"]
+    );
+}
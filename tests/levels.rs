@@ -0,0 +1,239 @@
+use std::io::Write;
+use test_log_collector::{Level, Matcher, TestLogCollector};
+
+#[test]
+fn test_level_detection_and_parsing() {
+    assert_eq!(Level::detect("ERROR boom"), Some(Level::Error));
+    assert_eq!(Level::detect("[WARNING] careful"), Some(Level::Warn));
+    assert_eq!(Level::detect("12:00:00 INFO  ready"), Some(Level::Info));
+    assert_eq!(Level::detect("error in lower case"), None);
+    assert_eq!(Level::detect("ERRORS are not levels"), None);
+
+    assert_eq!("warning".parse::<Level>(), Ok(Level::Warn));
+    assert_eq!("TRACE".parse::<Level>(), Ok(Level::Trace));
+    assert!("fatal".parse::<Level>().is_err());
+    assert!(Level::Error < Level::Warn);
+    assert_eq!(format!("{:<5}|", Level::Warn), "WARN |");
+}
+
+#[test]
+fn test_reclassify_at_capture_time() {
+    let mut collector = TestLogCollector::new();
+    writeln!(collector, "ERROR vendor: deprecated config key").unwrap();
+    collector.reclassify(Matcher::contains("vendor:"), Level::Warn);
+    collector.reclassify("deprecated", Level::Debug);
+    writeln!(collector, "ERROR vendor: deprecated flag").unwrap();
+    writeln!(collector, "WARN deprecated flag").unwrap();
+    writeln!(collector, "INFO started").unwrap();
+
+    assert_eq!(collector.line_level(0), Some(Level::Warn));
+    assert_eq!(collector.original_level(0), Some(Level::Error));
+    assert_eq!(collector.line_level(1), Some(Level::Warn));
+    assert_eq!(collector.line_level(2), Some(Level::Debug));
+    assert_eq!(collector.original_level(2), Some(Level::Warn));
+    assert_eq!(collector.line_level(3), Some(Level::Info));
+    assert_eq!(collector.line_level(4), None);
+    assert_eq!(
+        collector.lines_at_level(Level::Warn),
+        vec![
+            "ERROR vendor: deprecated config key",
+            "ERROR vendor: deprecated flag"
+        ]
+    );
+    collector.assert_no_errors();
+}
+
+#[test]
+#[should_panic(expected = "expected no error lines, found 1:\n  [1] ERROR disk full")]
+fn test_assert_no_errors_lists_errors() {
+    let mut collector = TestLogCollector::new();
+    writeln!(collector, "INFO ok").unwrap();
+    writeln!(collector, "ERROR disk full").unwrap();
+    collector.assert_no_errors();
+}
+
+#[test]
+fn test_max_level_filters_at_capture_time() {
+    let mut collector = TestLogCollector::new().with_max_level(Level::Warn);
+    collector.expect_count("DEBUG", 0);
+    writeln!(collector, "ERROR kept").unwrap();
+    writeln!(collector, "INFO dropped").unwrap();
+    writeln!(collector, "DEBUG dropped").unwrap();
+    writeln!(collector, "no level kept").unwrap();
+    collector.verify();
+
+    assert_eq!(collector.clone_lines(), vec!["ERROR kept", "no level kept"]);
+}
+
+#[test]
+fn test_max_level_uses_reclassified_level() {
+    let mut collector = TestLogCollector::new().with_max_level(Level::Info);
+    collector.reclassify("important", Level::Info);
+    writeln!(collector, "TRACE important detail").unwrap();
+    writeln!(collector, "TRACE other detail").unwrap();
+
+    assert_eq!(collector.clone_lines(), vec!["TRACE important detail"]);
+}
+
+#[test]
+fn test_scoped_max_level_restores_previous_level() {
+    let shared = TestLogCollector::new_shared();
+    {
+        let _guard = TestLogCollector::scoped_max_level(&shared, Level::Error);
+        writeln!(shared.lock().unwrap(), "WARN hidden").unwrap();
+        assert_eq!(shared.lock().unwrap().max_level(), Some(Level::Error));
+    }
+    writeln!(shared.lock().unwrap(), "WARN visible").unwrap();
+
+    let collector = shared.lock().unwrap();
+    assert_eq!(collector.max_level(), None);
+    assert_eq!(collector.clone_lines(), vec!["WARN visible"]);
+}
+
+#[test]
+fn test_directives_filter_by_target_at_capture_time() {
+    let mut collector = TestLogCollector::new()
+        .with_directives("info, myapp::db=trace, myapp::db::pool=off, noisy")
+        .unwrap();
+    collector.log(Level::Trace, "myapp::db::query", "select 1");
+    collector.log(Level::Error, "myapp::db::pool", "dropped");
+    collector.log(Level::Trace, "noisy::inner", "kept by bare target");
+    collector.log(Level::Debug, "other", "filtered by default");
+    writeln!(collector, "DEBUG plain line").unwrap();
+    writeln!(collector, "INFO plain line").unwrap();
+
+    assert_eq!(
+        collector,
+        [
+            "TRACE myapp::db::query: select 1",
+            "TRACE noisy::inner: kept by bare target",
+            "INFO plain line",
+        ]
+    );
+}
+
+#[test]
+fn test_directives_reject_unknown_level() {
+    let Err(error) = TestLogCollector::new().with_directives("myapp=loud") else {
+        panic!("expected an error");
+    };
+    assert_eq!(error.to_string(), "unknown log level \"loud\"");
+}
+
+#[test]
+fn test_target_allow_and_deny_lists() {
+    let mut collector = TestLogCollector::new();
+    collector.allow_target("myapp");
+    collector.allow_target("tower_http");
+    collector.deny_target("myapp::metrics");
+    collector.log(Level::Info, "myapp::api", "request");
+    collector.log(Level::Info, "myapp::metrics", "scraped");
+    collector.log(Level::Info, "tower_http::trace", "response");
+    collector.log(Level::Info, "h2::codec", "frame");
+    writeln!(collector, "plain lines have no target").unwrap();
+
+    assert_eq!(collector.count(), 3);
+    assert_eq!(collector.unfiltered_count(), 5);
+    assert_eq!(collector.line_target(1), Some("tower_http::trace"));
+}
+
+#[test]
+fn test_by_level_uses_reclassified_levels() {
+    let mut collector = TestLogCollector::new();
+    collector.reclassify("expected failure", Level::Info);
+    write!(
+        collector,
+        "WARN one\nERROR expected failure\nWARN two\nunleveled\n"
+    )
+    .unwrap();
+
+    let by_level = collector.by_level();
+    assert_eq!(by_level[&Level::Warn].len(), 2);
+    assert_eq!(by_level[&Level::Error].len(), 0);
+    assert_eq!(by_level[&Level::Info], ["ERROR expected failure"]);
+    assert_eq!(by_level.values().map(Vec::len).sum::<usize>(), 3);
+}
+
+#[test]
+fn test_summary_report() {
+    let mut collector = TestLogCollector::new().without_timestamps();
+    collector.log(Level::Info, "myapp::api", "ready");
+    collector.log(Level::Info, "myapp::api", "GET /");
+    write!(collector, "\nERROR {}\n", "x".repeat(20)).unwrap();
+
+    let summary = collector.summary();
+    assert_eq!(summary.lines(), 4);
+    assert_eq!(summary.level_count(Level::Error), 1);
+    assert_eq!(summary.level_count(Level::Warn), 0);
+    assert_eq!(
+        summary.to_string(),
+        "4 lines, 70 bytes over 0ns\n\
+         levels: ERROR 1, INFO 2\n\
+         targets: myapp::api 2\n\
+         line lengths: <=0 1, <=32 3"
+    );
+}
+
+#[test]
+#[should_panic(
+    expected = "expected at most 2 lines that contains \"retry\" per second, found 3 within one second:\n  [0] retry 1\n  [2] retry 2\n  [3] retry 3"
+)]
+fn test_assert_max_rate_reports_window() {
+    let mut collector = TestLogCollector::new();
+    write!(collector, "retry 1\nconnected\nretry 2\nretry 3\n").unwrap();
+    collector.assert_max_rate("retry", 2);
+}
+
+#[test]
+fn test_detect_levels_applies_to_new_and_existing_lines() {
+    let mut collector = TestLogCollector::new();
+    writeln!(collector, "<Warning> low memory").unwrap();
+    collector.reclassify("known", Level::Info);
+    collector.detect_levels();
+    writeln!(collector, "time=1 severity=ERROR known failure").unwrap();
+    writeln!(collector, "(debug) cache").unwrap();
+    writeln!(collector, "error in the message only").unwrap();
+
+    assert_eq!(collector.warnings(), ["<Warning> low memory"]);
+    assert!(collector.errors().is_empty());
+    assert_eq!(collector.original_level(1), Some(Level::Error));
+    assert_eq!(collector.line_level(2), Some(Level::Debug));
+    assert_eq!(collector.line_level(3), None);
+}
+
+#[test]
+fn test_syslog_parsing_of_both_formats() {
+    use test_log_collector::SyslogRecord;
+
+    let record = SyslogRecord::parse("<0>1 - - - - - -").unwrap();
+    assert_eq!((record.facility(), record.severity()), (0, 0));
+    assert_eq!(record.timestamp(), None);
+    assert_eq!(record.structured_data(), None);
+    assert_eq!(record.message(), "");
+
+    let record = SyslogRecord::parse(
+        "<12>1 2024-01-15T10:30:00Z host app 7 - [a x=\"q]\\\"\"][b] \u{feff}hello",
+    )
+    .unwrap();
+    assert_eq!(record.structured_data(), Some("[a x=\"q]\\\"\"][b]"));
+    assert_eq!(record.message(), "hello");
+    assert_eq!(record.level(), Level::Warn);
+
+    let record = SyslogRecord::parse("<191>kernel: panic averted").unwrap();
+    assert_eq!((record.facility(), record.severity()), (23, 7));
+    assert_eq!(record.hostname(), None);
+    assert_eq!(record.app_name(), Some("kernel"));
+    assert_eq!(record.message(), "panic averted");
+
+    let record = SyslogRecord::parse("<14>Jan 15 10:30:00 host free text: here").unwrap();
+    assert_eq!(record.app_name(), None);
+    assert_eq!(record.message(), "free text: here");
+
+    assert!(SyslogRecord::parse("<192>too large").is_none());
+    assert!(SyslogRecord::parse("<>empty").is_none());
+
+    let mut collector = TestLogCollector::new();
+    writeln!(collector, "<11>Jan 15 10:30:00 host app: disk failure").unwrap();
+    collector.detect_levels();
+    assert_eq!(collector.errors().len(), 1);
+}
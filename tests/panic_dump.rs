@@ -0,0 +1,74 @@
+use std::io::Write;
+use std::thread;
+use test_log_collector::TestLogCollector;
+
+#[test]
+fn test_dump_on_panic_survives_panicking_thread() {
+    let log_collector = TestLogCollector::new_shared();
+    let collector = log_collector.clone();
+
+    let result = thread::spawn(move || {
+        let _dump = TestLogCollector::dump_on_panic(&collector);
+        writeln!(collector.lock().unwrap(), "before the panic").unwrap();
+        panic!("boom");
+    })
+    .join();
+
+    assert!(result.is_err());
+    assert_eq!(
+        log_collector.lock().unwrap().clone_lines(),
+        vec!["before the panic"]
+    );
+}
+
+#[test]
+fn test_dump_on_panic_with_collector_locked() {
+    let log_collector = TestLogCollector::new_shared();
+    let collector = log_collector.clone();
+
+    let result = thread::spawn(move || {
+        let _dump = TestLogCollector::dump_on_panic(&collector);
+        let mut guard = collector.lock().unwrap();
+        writeln!(guard, "held while panicking").unwrap();
+        panic!("boom");
+    })
+    .join();
+
+    assert!(result.is_err());
+    let collector = log_collector
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    assert_eq!(collector.count(), 1);
+}
+
+#[test]
+fn test_dump_guard_dropped_without_panic() {
+    let log_collector = TestLogCollector::new_shared();
+    {
+        let _first = TestLogCollector::dump_on_panic(&log_collector);
+        let _second = TestLogCollector::dump_on_panic(&log_collector);
+        writeln!(log_collector.lock().unwrap(), "quiet").unwrap();
+    }
+
+    let result = thread::spawn(|| panic!("unrelated")).join();
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_lock_logs_recovers_poisoned_collector() {
+    use test_log_collector::SharedCollectorExt;
+
+    let log_collector = TestLogCollector::new_shared();
+    let collector = log_collector.clone();
+    let result = thread::spawn(move || {
+        let _guard = collector.lock().unwrap();
+        panic!("boom");
+    })
+    .join();
+
+    assert!(result.is_err());
+    assert!(log_collector.is_poisoned());
+    log_collector.with_logs(|logs| writeln!(logs, "after the panic").unwrap());
+    assert!(!log_collector.is_poisoned());
+    assert_eq!(log_collector.lock().unwrap().count(), 1);
+}
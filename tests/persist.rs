@@ -0,0 +1,72 @@
+use std::fs;
+use std::io::Write;
+use test_log_collector::TestLogCollector;
+
+#[test]
+fn test_persist_to_writes_lines_and_partial_line_on_drop() {
+    let dir =
+        std::env::temp_dir().join(format!("test_log_collector_persist_{}", std::process::id()));
+    let path = dir.join("nested").join("logs.txt");
+    {
+        let mut collector = TestLogCollector::new().persist_to(&path);
+        writeln!(collector, "first").unwrap();
+        write!(collector, "unfinished").unwrap();
+    }
+    assert_eq!(fs::read_to_string(&path).unwrap(), "first\nunfinished\n");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_persist_to_includes_archived_lines() {
+    let path = std::env::temp_dir().join(format!(
+        "test_log_collector_persist_archive_{}.txt",
+        std::process::id()
+    ));
+    {
+        let mut collector = TestLogCollector::new()
+            .with_disk_archive(4)
+            .unwrap()
+            .persist_to(&path);
+        for i in 0..10 {
+            writeln!(collector, "line {}", i).unwrap();
+        }
+        write!(collector, "unfinished").unwrap();
+        assert!(collector.archived_count() > 0);
+    }
+    let expected: String = (0..10)
+        .map(|i| format!("line {}\n", i))
+        .chain(Some("unfinished\n".to_string()))
+        .collect();
+    assert_eq!(fs::read_to_string(&path).unwrap(), expected);
+    fs::remove_file(&path).unwrap();
+}
+
+// The only test in this binary that relies on the environment variable, as
+// it applies to every collector in the process.
+#[test]
+fn test_persists_to_env_dir_with_unique_names() {
+    let dir = std::env::temp_dir().join(format!("test_log_collector_env_{}", std::process::id()));
+    std::env::set_var("TEST_LOG_COLLECTOR_DIR", &dir);
+    for i in 0..2 {
+        let mut collector = TestLogCollector::new();
+        writeln!(collector, "capture {}", i).unwrap();
+    }
+    drop(TestLogCollector::new());
+    std::env::remove_var("TEST_LOG_COLLECTOR_DIR");
+
+    let mut files: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    files.sort();
+    assert_eq!(files.len(), 2);
+    let name = files[0].file_name().unwrap().to_str().unwrap();
+    assert!(name.starts_with("test_persists_to_env_dir_with_unique_names-"));
+    let mut contents: Vec<String> = files
+        .iter()
+        .map(|f| fs::read_to_string(f).unwrap())
+        .collect();
+    contents.sort();
+    assert_eq!(contents, vec!["capture 0\n", "capture 1\n"]);
+    fs::remove_dir_all(&dir).unwrap();
+}
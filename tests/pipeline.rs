@@ -0,0 +1,102 @@
+use std::io::Write;
+use std::thread;
+use std::time::Duration;
+use test_log_collector::Pipeline;
+
+#[test]
+fn test_shutdown_waits_for_in_flight_lines() {
+    let mut pipeline = Pipeline::new();
+    let handles: Vec<_> = (0..4)
+        .map(|i| {
+            let mut writer = pipeline.writer(format!("producer {}", i));
+            thread::spawn(move || {
+                for n in 0..250 {
+                    writeln!(writer, "producer {} line {}", i, n).unwrap();
+                }
+            })
+        })
+        .collect();
+
+    let collector = pipeline.shutdown(Duration::from_secs(10)).unwrap();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert_eq!(collector.count(), 1000);
+    assert!(collector.contains("producer 3 line 249"));
+}
+
+#[test]
+fn test_producers_do_not_interleave_partial_writes() {
+    let mut pipeline = Pipeline::new();
+    let mut a = pipeline.writer("a");
+    let mut b = pipeline.writer("b");
+    write!(a, "first ").unwrap();
+    write!(b, "second ").unwrap();
+    writeln!(a, "half").unwrap();
+    writeln!(b, "half").unwrap();
+    drop((a, b));
+
+    let collector = pipeline.shutdown(Duration::from_secs(5)).unwrap();
+    assert_eq!(collector.clone_lines(), vec!["first half", "second half"]);
+}
+
+#[test]
+fn test_shutdown_finalizes_partial_lines() {
+    let mut pipeline = Pipeline::new();
+    let mut writer = pipeline.writer("tail");
+    writeln!(writer, "complete").unwrap();
+    write!(writer, "no newline").unwrap();
+    drop(writer);
+
+    let collector = pipeline.shutdown(Duration::from_secs(5)).unwrap();
+    assert_eq!(collector.clone_lines(), vec!["complete", "no newline"]);
+}
+
+#[test]
+fn test_flush_completes_producer_line() {
+    let mut pipeline = Pipeline::new();
+    let mut writer = pipeline.writer("flusher");
+    write!(writer, "flushed").unwrap();
+    writer.flush().unwrap();
+    write!(writer, "later").unwrap();
+    drop(writer);
+
+    let collector = pipeline.shutdown(Duration::from_secs(5)).unwrap();
+    assert_eq!(collector.clone_lines(), vec!["flushed", "later"]);
+}
+
+#[test]
+fn test_shutdown_timeout_lists_alive_producers() {
+    let mut pipeline = Pipeline::new();
+    let finished = pipeline.writer("finished");
+    let mut stuck = pipeline.writer("stuck");
+    drop(finished);
+    writeln!(stuck, "before the hang").unwrap();
+
+    let Err(error) = pipeline.shutdown(Duration::from_millis(50)) else {
+        panic!("shutdown should time out");
+    };
+    assert_eq!(error.alive_producers(), ["stuck"]);
+    assert!(error.to_string().contains("stuck"));
+
+    drop(stuck);
+    thread::sleep(Duration::from_millis(50));
+    assert!(error
+        .collector()
+        .lock()
+        .unwrap()
+        .contains("before the hang"));
+}
+
+#[test]
+fn test_shutdown_with_outstanding_collector_handle() {
+    let mut pipeline = Pipeline::new();
+    let handle = pipeline.collector();
+    let mut writer = pipeline.writer("w");
+    writeln!(writer, "line").unwrap();
+    drop(writer);
+
+    let collector = pipeline.shutdown(Duration::from_secs(5)).unwrap();
+    assert_eq!(collector.clone_lines(), vec!["line"]);
+    assert_eq!(handle.lock().unwrap().count(), 0);
+}
@@ -0,0 +1,39 @@
+use test_log_collector::prelude::*;
+
+#[test]
+fn test_prelude_covers_typical_test() {
+    let shared = TestLogCollector::new_shared();
+    let mut tee = TestLogCollector::tee(Vec::new());
+    writeln!(tee, "WARN retrying").unwrap();
+    writeln!(shared.lock().unwrap(), "ERROR failed").unwrap();
+
+    let collector = shared.lock().unwrap();
+    assert_eq!(collector.lines_at_level(Level::Error), vec!["ERROR failed"]);
+    assert!(Matcher::starts_with("WARN").matches(&tee.collector().lines()[0]));
+}
+
+#[collect_logs]
+fn test_prelude_exports_collect_logs(logs: SharedCollector) {
+    writeln!(logs.lock().unwrap(), "from the macro").unwrap();
+    assert!(current_collector().is_some());
+}
+
+#[test]
+fn test_grouped_modules_reexport_the_same_types() {
+    use test_log_collector::{assert, collector, ingest, integrations};
+
+    let mut logs: collector::TestLogCollector = TestLogCollector::new();
+    writeln!(logs, "INFO up").unwrap();
+    let level: collector::Level = Level::Info;
+    assert_eq!(logs.line_level(0), Some(level));
+
+    let matcher: assert::Matcher = Matcher::exact("INFO up");
+    assert!(matcher.matches(&logs.lines()[0]));
+
+    let tee: ingest::Tee<Vec<u8>> = Tee::new(logs, Vec::new());
+    let (_, inner) = tee.into_parts();
+    assert!(inner.is_empty());
+
+    assert!(integrations::current_collector().is_none());
+    let _ = integrations::registry::current_key();
+}
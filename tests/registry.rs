@@ -0,0 +1,178 @@
+use std::io::Write;
+use std::sync::{Arc, Barrier};
+use std::thread;
+use test_log_collector::registry;
+
+#[test]
+fn test_parallel_threads_get_isolated_collectors() {
+    let barrier = Arc::new(Barrier::new(4));
+    let handles: Vec<_> = (0..4)
+        .map(|i| {
+            let barrier = barrier.clone();
+            thread::Builder::new()
+                .name(format!("registry_test_{}", i))
+                .spawn(move || {
+                    let mut writer = registry::writer();
+                    barrier.wait();
+                    for n in 0..100 {
+                        writeln!(writer, "test {} line {}", i, n).unwrap();
+                    }
+                    let logs = registry::remove_current().unwrap();
+                    let logs = logs.lock().unwrap();
+                    assert_eq!(logs.count(), 100);
+                    assert!(logs
+                        .lines()
+                        .iter()
+                        .all(|line| line.starts_with(&format!("test {} ", i))));
+                })
+                .unwrap()
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert!(registry::get("registry_test_0").is_none());
+}
+
+#[test]
+fn test_named_collectors_are_shared() {
+    let logs = registry::named("test_named_collectors_are_shared");
+    thread::spawn(|| {
+        let logs = registry::named("test_named_collectors_are_shared");
+        writeln!(logs.lock().unwrap(), "from a helper thread").unwrap();
+    })
+    .join()
+    .unwrap();
+
+    assert_eq!(logs.lock().unwrap().count(), 1);
+    assert!(registry::remove("test_named_collectors_are_shared").is_some());
+    assert!(registry::get("test_named_collectors_are_shared").is_none());
+}
+
+#[test]
+fn test_current_key_uses_test_thread_name() {
+    let key = thread::Builder::new()
+        .name("named".into())
+        .spawn(registry::current_key)
+        .unwrap()
+        .join()
+        .unwrap();
+    assert_eq!(key, "named");
+
+    let unnamed = thread::spawn(registry::current_key).join().unwrap();
+    assert!(unnamed.starts_with("ThreadId("));
+}
+
+#[test]
+fn test_log_routes_to_the_guard_installed_on_the_thread() {
+    use test_log_collector::{CollectLogsGuard, Level};
+
+    thread::Builder::new()
+        .name("registry_log_guard_test".into())
+        .spawn(|| {
+            let guard = CollectLogsGuard::install();
+            registry::log(Level::Info, "app", "inside the guard");
+            assert_eq!(
+                *guard.collector().lock().unwrap(),
+                ["INFO app: inside the guard"]
+            );
+            drop(guard);
+
+            registry::log(Level::Info, "app", "after the guard");
+            let logs = registry::remove_current().unwrap();
+            assert_eq!(*logs.lock().unwrap(), ["INFO app: after the guard"]);
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+}
+
+#[test]
+fn test_propagated_futures_route_records_to_the_spawning_test() {
+    use std::future::Future;
+    use std::pin::pin;
+    use std::task::{Context, Waker};
+    use test_log_collector::{propagate_to_spawned, CollectLogsGuard, Level};
+
+    let guard = CollectLogsGuard::install();
+    let task = propagate_to_spawned(async {
+        registry::log(Level::Info, "task", "spawned");
+    });
+    let unwrapped = async {
+        registry::log(Level::Info, "task", "lost");
+    };
+
+    thread::Builder::new()
+        .name("registry_propagation_executor".into())
+        .spawn(move || {
+            let mut cx = Context::from_waker(Waker::noop());
+            assert!(pin!(task).poll(&mut cx).is_ready());
+            assert!(pin!(unwrapped).poll(&mut cx).is_ready());
+            let executor = registry::remove_current().unwrap();
+            assert_eq!(*executor.lock().unwrap(), ["INFO task: lost"]);
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+
+    assert_eq!(*guard.collector().lock().unwrap(), ["INFO task: spawned"]);
+}
+
+#[test]
+fn test_propagated_futures_keep_thread_name_attribution() {
+    use std::future::Future;
+    use std::pin::pin;
+    use std::task::{Context, Waker};
+    use test_log_collector::{propagate_to_spawned, Level};
+
+    let task = thread::Builder::new()
+        .name("registry_propagation_test".into())
+        .spawn(|| {
+            propagate_to_spawned(async {
+                registry::log(Level::Info, "task", "spawned");
+            })
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+
+    thread::Builder::new()
+        .name("tokio-runtime-worker".into())
+        .spawn(move || {
+            let mut cx = Context::from_waker(Waker::noop());
+            assert!(pin!(task).poll(&mut cx).is_ready());
+            assert!(registry::get("tokio-runtime-worker").is_none());
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+
+    let test = registry::remove("registry_propagation_test").unwrap();
+    assert_eq!(*test.lock().unwrap(), ["INFO task: spawned"]);
+}
+
+#[test]
+fn test_writer_follows_the_installed_guard() {
+    use test_log_collector::{CollectLogsGuard, Level};
+
+    thread::Builder::new()
+        .name("registry_writer_guard".into())
+        .spawn(|| {
+            let guard = CollectLogsGuard::install();
+            let mut writer = registry::writer();
+            writeln!(writer, "from the writer").unwrap();
+            write!(writer, "flushed").unwrap();
+            writer.flush().unwrap();
+            registry::log(Level::Info, "app", "from log");
+
+            assert_eq!(
+                *guard.collector().lock().unwrap(),
+                ["from the writer", "flushed", "INFO app: from log"]
+            );
+            assert!(registry::remove_current().is_none());
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+}
@@ -0,0 +1,56 @@
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use test_log_collector::{Report, TestLogCollector};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "test_log_collector_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn test_report_writes_json_and_html() {
+    let dir = temp_dir("report_json_html");
+    let mut passing = TestLogCollector::new();
+    writeln!(passing, "all <good> & fine").unwrap();
+    let mut failing = TestLogCollector::new();
+    failing.expect_count("never", 1);
+    writeln!(failing, "quote \" inside").unwrap();
+
+    let mut report = Report::new(&dir).with_title("suite");
+    report.add("passing_test", &passing);
+    report.add("failing_test", &failing);
+    assert_eq!(report.len(), 2);
+    report.write().unwrap();
+
+    let json = fs::read_to_string(dir.join("report.json")).unwrap();
+    assert!(json.starts_with("{\"title\":\"suite\",\"tests\":["));
+    assert!(json.contains(
+        "{\"name\":\"passing_test\",\"passed\":true,\"failures\":[],\"lines\":[\"all <good> & fine\"]}"
+    ));
+    assert!(json.contains("\"name\":\"failing_test\",\"passed\":false"));
+    assert!(json.contains("\"lines\":[\"quote \\\" inside\"]"));
+
+    let html = fs::read_to_string(dir.join("index.html")).unwrap();
+    assert!(html.contains("<h1>suite</h1>"));
+    assert!(html.contains("1 of 2 tests passed"));
+    assert!(html.contains("all &lt;good&gt; &amp; fine"));
+    assert!(html.contains("failing_test &mdash; FAILED"));
+    assert!(
+        html.contains("<li>expected exactly 1 line that contains &quot;never&quot;, saw 0</li>")
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_record_without_installed_report_is_a_no_op() {
+    let mut collector = TestLogCollector::new();
+    writeln!(collector, "ignored").unwrap();
+    Report::record("uninstalled", &collector);
+}
@@ -0,0 +1,68 @@
+#![cfg(any(unix, windows))]
+
+use std::io::Write;
+use std::process::Command;
+use test_log_collector::TestLogCollector;
+
+#[test]
+fn test_capture_stdio_collects_both_streams() {
+    let collector = TestLogCollector::new_shared();
+    {
+        let _capture = TestLogCollector::capture_stdio(&collector).unwrap();
+        std::io::stdout()
+            .write_all(b"to stdout\npartial stdout")
+            .unwrap();
+        std::io::stderr().write_all(b"to stderr\n").unwrap();
+        #[cfg(unix)]
+        let child = Command::new("sh")
+            .args(["-c", "echo from a child process"])
+            .status();
+        #[cfg(windows)]
+        let child = Command::new("cmd")
+            .args(["/C", "echo from a child process"])
+            .status();
+        child.unwrap();
+    }
+
+    let collector = collector.lock().unwrap();
+    assert!(collector.contains("to stdout"));
+    assert!(collector.contains("to stderr"));
+    assert!(collector.contains("from a child process"));
+    assert!(collector
+        .lines()
+        .iter()
+        .any(|line| line == "partial stdout"));
+    let stderr_line = collector
+        .lines()
+        .iter()
+        .position(|line| line == "to stderr")
+        .unwrap();
+    assert_eq!(collector.provenance(stderr_line).unwrap(), ["via stderr"]);
+}
+
+#[test]
+fn test_capture_stdio_restores_descriptors() {
+    let collector = TestLogCollector::new_shared();
+    drop(TestLogCollector::capture_stdio(&collector).unwrap());
+    let count = collector.lock().unwrap().count();
+
+    std::io::stderr().write_all(b"\n").unwrap();
+    assert_eq!(collector.lock().unwrap().count(), count);
+}
+
+#[test]
+fn test_dropping_capture_while_holding_the_collector_does_not_deadlock() {
+    let collector = TestLogCollector::new_shared();
+    let capture = TestLogCollector::capture_stdio(&collector).unwrap();
+    let held = collector.lock().unwrap();
+    std::io::stderr().write_all(b"while locked\n").unwrap();
+    drop(capture);
+    assert!(!held.contains("while locked"));
+    drop(held);
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+    while !collector.lock().unwrap().contains("while locked") {
+        assert!(std::time::Instant::now() < deadline, "line never collected");
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+}
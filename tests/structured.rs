@@ -0,0 +1,243 @@
+use std::io::Write;
+use test_log_collector::TestLogCollector;
+
+fn collector_with(lines: &[&str]) -> TestLogCollector {
+    let mut collector = TestLogCollector::new();
+    for line in lines {
+        writeln!(collector, "{}", line).unwrap();
+    }
+    collector
+}
+
+#[test]
+fn test_records_eq_ignoring_order_and_volatile_keys() {
+    let collector = collector_with(&[
+        r#"{"timestamp":"2024-05-01T10:00:00Z","pid":4242,"level":"INFO","msg":"started","fields":{"port":8080,"duration_ms":12}}"#,
+        r#"{"level":"WARN","msg":"slow \"query\"","tags":["db","xé"],"ok":false,"extra":null}"#,
+    ]);
+
+    collector.assert_records_eq_ignoring(
+        &[
+            r#"{"msg":"started","level":"INFO","fields":{"port":8080.0}}"#,
+            r#"{ "extra": null, "ok": false, "tags": ["db", "xé"], "msg": "slow \"query\"", "level": "WARN" }"#,
+        ],
+        &["timestamp", "pid", "duration_ms"],
+    );
+}
+
+#[test]
+#[should_panic(expected = "record 0 differs")]
+fn test_records_differing_value() {
+    let collector = collector_with(&[r#"{"level":"INFO","msg":"started"}"#]);
+    collector.assert_records_eq_ignoring(&[r#"{"level":"INFO","msg":"stopped"}"#], &[]);
+}
+
+#[test]
+#[should_panic(expected = "expected 2 records, collected 1")]
+fn test_records_differing_count() {
+    let collector = collector_with(&[r#"{"msg":"a"}"#]);
+    collector.assert_records_eq_ignoring(&[r#"{"msg":"a"}"#, r#"{"msg":"b"}"#], &[]);
+}
+
+#[test]
+#[should_panic(expected = "collected record 0 is not valid JSON")]
+fn test_records_invalid_json() {
+    let collector = collector_with(&["plain text"]);
+    collector.assert_records_eq_ignoring(&[r#"{"msg":"a"}"#], &[]);
+}
+
+#[test]
+fn test_to_junit_xml_escapes_and_strips_control_characters() {
+    let mut collector = TestLogCollector::new();
+    writeln!(collector, "\u{1b}[31mred\u{1b}[0m & 'quoted'").unwrap();
+    writeln!(collector, "second").unwrap();
+
+    assert_eq!(
+        collector.to_junit_xml("a<b>"),
+        "<testcase name=\"a&lt;b&gt;\">\n\
+         <system-out>[31mred[0m &amp; &apos;quoted&apos;\nsecond\n</system-out>\n\
+         </testcase>\n"
+    );
+}
+
+#[test]
+fn test_to_junit_reports_unmet_expectations_as_failures() {
+    let mut collector = TestLogCollector::new();
+    collector.expect_count("ready", 1);
+    writeln!(collector, "starting").unwrap();
+
+    let xml = collector.to_junit_testsuite("suite", "starts");
+    assert!(xml.contains("<testsuite name=\"suite\" tests=\"1\" failures=\"1\">"));
+    assert!(xml.contains(
+        "<failure message=\"expected exactly 1 line that contains &quot;ready&quot;, saw 0\">"
+    ));
+    assert!(xml.contains("<system-out>starting\n</system-out>"));
+}
+
+#[test]
+fn test_export_jsonl_round_trips_through_parser() {
+    let mut collector = TestLogCollector::new();
+    writeln!(collector, "ERROR \"quoted\"\tfailure").unwrap();
+    writeln!(collector, "plain").unwrap();
+
+    let mut out = Vec::new();
+    collector.export_jsonl(&mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    let records: Vec<&str> = out.lines().collect();
+    assert_eq!(records.len(), 2);
+
+    let mut parsed = TestLogCollector::new();
+    for record in &records {
+        writeln!(parsed, "{}", record).unwrap();
+    }
+    let thread = std::thread::current().name().map(str::to_string);
+    let thread = thread.map_or("null".to_string(), |name| format!("{:?}", name));
+    parsed.assert_records_eq_ignoring(
+        &[
+            &format!(
+                r#"{{"line":0,"thread":{},"level":"ERROR","message":"ERROR \"quoted\"\tfailure"}}"#,
+                thread
+            ),
+            &format!(
+                r#"{{"line":1,"thread":{},"level":null,"message":"plain"}}"#,
+                thread
+            ),
+        ],
+        &["seq", "timestamp_ms"],
+    );
+}
+
+#[test]
+fn test_export_csv_quotes_fields() {
+    let mut collector = TestLogCollector::new();
+    writeln!(collector, "INFO say \"hi\", then leave").unwrap();
+    writeln!(collector, "plain").unwrap();
+
+    let mut out = Vec::new();
+    collector.export_csv(&mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    let rows: Vec<&str> = out.lines().collect();
+    let sequenced = collector.lines_sequenced();
+
+    assert_eq!(rows[0], "line,seq,timestamp_ms,thread,level,message");
+    assert!(rows[1].starts_with(&format!("0,{},", sequenced[0].0)));
+    assert!(rows[1].ends_with(",INFO,\"INFO say \"\"hi\"\", then leave\""));
+    assert!(rows[2].ends_with(",,plain"));
+}
+
+#[test]
+fn test_fixture_round_trip() {
+    let mut collector = collector_with(&["WARN \"quoted\"\ttab", "", "unicode é"]);
+    write!(collector, "pending").unwrap();
+
+    let fixture = collector.to_fixture();
+    let mut loaded = TestLogCollector::from_fixture(&fixture).unwrap();
+    assert_eq!(loaded, collector.clone_lines());
+    assert_eq!(loaded.pending(), "pending");
+    assert_eq!(loaded.line_level(0), Some(test_log_collector::Level::Warn));
+    loaded.flush().unwrap();
+    assert_eq!(loaded.count(), 4);
+}
+
+#[test]
+fn test_fixture_rejects_invalid_data() {
+    for json in [
+        "not json",
+        "{}",
+        r#"{"lines":[1]}"#,
+        r#"{"lines":[],"pending":3}"#,
+    ] {
+        let error = TestLogCollector::from_fixture(json).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData, "{}", json);
+    }
+    let empty = TestLogCollector::from_fixture(r#"{"lines":[]}"#).unwrap();
+    assert_eq!(empty.count(), 0);
+}
+
+#[test]
+fn test_export_chrome_trace_parses_as_json() {
+    let mut collector = TestLogCollector::new();
+    collector.begin_section("setup");
+    writeln!(collector, "WARN \"slow\" start").unwrap();
+    writeln!(collector, "ready").unwrap();
+    collector.end_section();
+    writeln!(collector, "outside").unwrap();
+
+    let mut out = Vec::new();
+    collector.export_chrome_trace(&mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    let events: Vec<&str> = out.lines().filter(|line| line.starts_with('{')).collect();
+    let events: Vec<&str> = events[2..]
+        .iter()
+        .map(|event| event.trim_end_matches(','))
+        .collect();
+
+    let mut parsed = TestLogCollector::new();
+    for event in &events {
+        writeln!(parsed, "{}", event).unwrap();
+    }
+    parsed.assert_records_eq_ignoring(
+        &[
+            r#"{"name":"setup","cat":"section","ph":"X","pid":1,"tid":1}"#,
+            r#"{"name":"WARN \"slow\" start","cat":"WARN","ph":"i","s":"t","pid":1,"tid":1,"args":{"line":0}}"#,
+            r#"{"name":"ready","cat":"log","ph":"i","s":"t","pid":1,"tid":1,"args":{"line":1}}"#,
+            r#"{"name":"outside","cat":"log","ph":"i","s":"t","pid":1,"tid":1,"args":{"line":2}}"#,
+        ],
+        &["ts", "dur", "seq"],
+    );
+    assert!(out.starts_with("{\"traceEvents\":[\n{\"name\":\"thread_name\",\"ph\":\"M\""));
+    assert!(out.ends_with("\n],\"displayTimeUnit\":\"ms\"}\n"));
+}
+
+#[test]
+fn test_export_otlp_json_parses_as_json() {
+    let mut collector = TestLogCollector::new();
+    collector.log(test_log_collector::Level::Error, "db", "\"down\"");
+    writeln!(collector, "plain").unwrap();
+
+    let mut out = Vec::new();
+    collector.export_otlp_json("svc", &mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert_eq!(out.lines().count(), 1);
+
+    let mut parsed = TestLogCollector::new();
+    write!(parsed, "{}", out).unwrap();
+    let thread = std::thread::current().name().unwrap().to_string();
+    parsed.assert_records_eq_ignoring(
+        &[&format!(
+            r#"{{"resourceLogs":[{{
+                "resource":{{"attributes":[{{"key":"service.name","value":{{"stringValue":"svc"}}}}]}},
+                "scopeLogs":[{{"scope":{{"name":"test_log_collector"}},"logRecords":[
+                    {{"severityNumber":17,"severityText":"ERROR",
+                      "body":{{"stringValue":"ERROR db: \"down\""}},
+                      "attributes":[
+                        {{"key":"thread.name","value":{{"stringValue":"{thread}"}}}},
+                        {{"key":"log.target","value":{{"stringValue":"db"}}}}]}},
+                    {{"body":{{"stringValue":"plain"}},
+                      "attributes":[{{"key":"thread.name","value":{{"stringValue":"{thread}"}}}}]}}
+                ]}}]}}]}}"#
+        )],
+        &["timeUnixNano", "observedTimeUnixNano", "version"],
+    );
+}
+
+#[test]
+fn test_fields_parse_quoting_and_boundaries() {
+    use test_log_collector::Fields;
+
+    let fields = Fields::parse(
+        r#"span{a=1,b="x y"}: msg="say \"hi\"\n" url=http://h/?q=1 x=y=z neg=-4 ratio=0.5 empty= café=ok"#,
+    );
+    assert_eq!(fields.field_u64("a"), Some(1));
+    assert_eq!(fields.field_str("b"), Some("x y"));
+    assert_eq!(fields.field_str("msg"), Some("say \"hi\"\n"));
+    assert_eq!(fields.field_str("url"), Some("http://h/?q=1"));
+    assert_eq!(fields.field_str("q"), None);
+    assert_eq!(fields.field_str("x"), Some("y=z"));
+    assert_eq!(fields.field_i64("neg"), Some(-4));
+    assert_eq!(fields.field_f64("ratio"), Some(0.5));
+    assert_eq!(fields.field_str("empty"), Some(""));
+    assert_eq!(fields.field_str("café"), Some("ok"));
+    assert_eq!(fields.len(), 9);
+    assert!(Fields::parse("no fields here").is_empty());
+}
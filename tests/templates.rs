@@ -0,0 +1,47 @@
+use std::io::Write;
+use test_log_collector::TestLogCollector;
+
+fn collector_with(lines: &[&str]) -> TestLogCollector {
+    let mut collector = TestLogCollector::new();
+    for line in lines {
+        writeln!(collector, "{}", line).unwrap();
+    }
+    collector
+}
+
+#[test]
+fn test_template_masks_variable_words() {
+    assert_eq!(
+        TestLogCollector::template("request 7f3a9 took  12.5ms status=200 path=/users"),
+        "request <*> took <*> status=<*> path=/users"
+    );
+    assert_eq!(TestLogCollector::template("v2=3"), "<*>");
+    assert_eq!(TestLogCollector::template(""), "");
+}
+
+#[test]
+fn test_templates_count_in_first_appearance_order() {
+    let collector = collector_with(&["retry 1 of 3", "connected", "retry 2 of 3", "retry 3 of 3"]);
+    assert_eq!(
+        collector.templates(),
+        vec![
+            ("retry <*> of <*>".to_string(), 3),
+            ("connected".to_string(), 1)
+        ]
+    );
+}
+
+#[test]
+fn test_assert_no_new_templates_passes_for_known() {
+    let collector = collector_with(&["retry 1 of 3", "connected"]);
+    collector.assert_no_new_templates(&["connected", "retry <*> of <*>", "unused"]);
+}
+
+#[test]
+#[should_panic(
+    expected = "found 1 new log message templates:\n  \"DEBUG x=<*>\" (e.g. \"DEBUG x=5\")"
+)]
+fn test_assert_no_new_templates_reports_new() {
+    let collector = collector_with(&["connected", "DEBUG x=5", "DEBUG x=6"]);
+    collector.assert_no_new_templates(&["connected"]);
+}
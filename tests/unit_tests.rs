@@ -1,4 +1,8 @@
+// The baseline tests write empty lines and ignore the written amount on purpose.
+#![allow(clippy::unused_io_amount, clippy::writeln_empty_string)]
+
 use std::io::Write;
+use std::thread;
 use test_log_collector::TestLogCollector;
 
 #[test]
@@ -108,3 +112,1178 @@ fn test_raw_write() {
     assert_eq!(collector.count(), 2);
     assert_eq!(collector.clone_lines(), vec!["Hello", "World"]);
 }
+
+#[test]
+fn test_scrubbers_applied_in_order() {
+    let mut collector = TestLogCollector::new()
+        .with_replacement("2024-01-01T00:00:00Z", "<TS>")
+        .with_scrubber(|line| line.to_uppercase());
+    writeln!(collector, "at 2024-01-01T00:00:00Z ready").unwrap();
+    write!(collector, "partial").unwrap();
+    collector.flush().unwrap();
+
+    assert_eq!(collector.clone_lines(), vec!["AT <TS> READY", "PARTIAL"]);
+}
+
+#[test]
+fn test_lines_tagged_with_writing_thread() {
+    let log_collector = TestLogCollector::new_shared();
+
+    let handles: Vec<_> = ["worker-a", "worker-b"]
+        .into_iter()
+        .map(|name| {
+            let collector = log_collector.clone();
+            thread::Builder::new()
+                .name(name.to_string())
+                .spawn(move || {
+                    let mut collector = collector.lock().unwrap();
+                    writeln!(collector, "hello from {}", name).unwrap();
+                })
+                .unwrap()
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    let collector = log_collector.lock().unwrap();
+    assert_eq!(
+        collector.lines_from_thread("worker-a"),
+        vec!["hello from worker-a"]
+    );
+    assert_eq!(
+        collector.lines_from_thread("worker-b"),
+        vec!["hello from worker-b"]
+    );
+    assert!(collector.lines_from_thread("worker-c").is_empty());
+    assert!(collector
+        .lines_tagged()
+        .iter()
+        .all(|(tag, line)| line.ends_with(tag.name().unwrap())));
+}
+
+#[derive(Debug)]
+struct WrappedError {
+    message: &'static str,
+    source: std::io::Error,
+}
+
+impl std::fmt::Display for WrappedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.message)
+    }
+}
+
+impl std::error::Error for WrappedError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+#[test]
+fn test_record_error_chain() {
+    let mut collector = TestLogCollector::new();
+    let error = WrappedError {
+        message: "failed to fetch config",
+        source: std::io::Error::other("connection refused"),
+    };
+    collector.record_error(&error);
+
+    let report = &collector.error_reports()[0];
+    assert_eq!(
+        report.causes(),
+        ["failed to fetch config", "connection refused"]
+    );
+    assert_eq!(report.root_cause(), "connection refused");
+    collector.assert_error_chain_contains("connection refused");
+    collector.assert_error_chain_contains("fetch config");
+    assert_eq!(collector.count(), 0);
+
+    collector.clear();
+    assert!(collector.error_reports().is_empty());
+}
+
+#[test]
+#[should_panic(expected = "no recorded error chain contains")]
+fn test_assert_error_chain_contains_fails() {
+    let collector = TestLogCollector::new();
+    collector.assert_error_chain_contains("timeout");
+}
+
+#[test]
+fn test_interleave_orders_by_sequence() {
+    let mut stdout = TestLogCollector::new();
+    let mut stderr = TestLogCollector::new();
+    let mut component = TestLogCollector::new();
+
+    writeln!(stderr, "1").unwrap();
+    write!(stdout, "2").unwrap();
+    writeln!(component, "3").unwrap();
+    writeln!(stdout).unwrap();
+    writeln!(stderr, "4").unwrap();
+
+    assert_eq!(
+        TestLogCollector::interleave(&[&stdout, &stderr, &component]),
+        vec!["1", "3", "2", "4"]
+    );
+
+    let seqs: Vec<u64> = stderr.lines_sequenced().iter().map(|(s, _)| *s).collect();
+    assert!(seqs[0] < seqs[1]);
+}
+
+#[test]
+fn test_line_hashes_and_duplicate_groups() {
+    let mut collector = TestLogCollector::new();
+    for line in ["a", "b", "a", "c", "b", "a"] {
+        writeln!(collector, "{}", line).unwrap();
+    }
+
+    let hashes = collector.line_hashes();
+    assert_eq!(hashes.len(), 6);
+    assert_eq!(hashes[0], hashes[5]);
+    assert_ne!(hashes[0], hashes[1]);
+    assert_eq!(
+        collector.duplicate_groups(),
+        vec![vec![0, 2, 5], vec![1, 4]]
+    );
+
+    // FNV-1a is stable, so hashes can be compared across runs.
+    let mut other = TestLogCollector::new();
+    writeln!(other, "a").unwrap();
+    assert_eq!(other.line_hashes(), vec![0xaf63dc4c8601ec8c]);
+}
+
+#[test]
+fn test_search_with_and_without_index() {
+    for threshold in [0, usize::MAX] {
+        let mut collector = TestLogCollector::new().with_index_threshold(threshold);
+        writeln!(collector, "request 1 ok").unwrap();
+        writeln!(collector, "request 2 failed: db timeout").unwrap();
+        writeln!(collector, "request 3 ok").unwrap();
+        writeln!(collector, "db_pool ready").unwrap();
+
+        assert!(collector.contains("2 failed"));
+        assert!(collector.contains("quest 3 o"));
+        assert!(!collector.contains("request 4"));
+        assert_eq!(
+            collector.matching_lines("request 1 ok"),
+            vec!["request 1 ok"]
+        );
+        assert_eq!(
+            collector.matching_lines(" ok"),
+            vec!["request 1 ok", "request 3 ok"]
+        );
+        assert_eq!(collector.lines_with_tokens(&["db", "timeout"]), vec![1]);
+        assert_eq!(collector.lines_with_tokens(&["db_pool"]), vec![3]);
+        assert!(collector.lines_with_tokens(&["missing"]).is_empty());
+    }
+}
+
+#[test]
+fn test_index_built_after_threshold_and_reset_on_clear() {
+    let mut collector = TestLogCollector::new().with_index_threshold(100);
+    for i in 0..1_000 {
+        writeln!(collector, "event id={} status={}", i, i % 7).unwrap();
+    }
+
+    assert_eq!(collector.matching_lines("id=500 ").len(), 1);
+    assert_eq!(collector.lines_with_tokens(&["status", "3"]).len(), 143);
+
+    collector.clear();
+    writeln!(collector, "event id=1").unwrap();
+    assert_eq!(collector.matching_lines("id=1"), vec!["event id=1"]);
+}
+
+#[test]
+fn test_index_follows_max_lines_eviction() {
+    let mut collector = TestLogCollector::new()
+        .with_index_threshold(2)
+        .with_max_lines(5);
+    for i in 0..100 {
+        writeln!(collector, "event id={} status={}", i, i % 3).unwrap();
+    }
+
+    assert_eq!(collector.lines_with_tokens(&["status", "0"]), vec![1, 4]);
+    assert_eq!(
+        collector.matching_lines("id=97 "),
+        vec!["event id=97 status=1"]
+    );
+    assert!(!collector.contains("id=94 "));
+    assert!(collector.contains_ignore_case("EVENT ID=95 "));
+}
+
+#[test]
+fn test_write_spy_records_call_granularity() {
+    let collector = TestLogCollector::new().with_write_spy();
+    let mut writer = std::io::LineWriter::new(collector);
+    writer.write_all(b"first line\nsecond ").unwrap();
+    writer.write_all(b"half\n").unwrap();
+    let Ok(mut collector) = writer.into_inner() else {
+        panic!("flush failed");
+    };
+
+    assert_eq!(
+        collector.write_calls(),
+        [b"first line\n".to_vec(), b"second half\n".to_vec()]
+    );
+    assert_eq!(collector.clone_lines(), vec!["first line", "second half"]);
+
+    collector.flush().unwrap();
+    assert!(collector.flush_count() >= 1);
+    collector.clear();
+    assert!(collector.write_calls().is_empty());
+    assert_eq!(collector.flush_count(), 0);
+}
+
+#[test]
+fn test_write_spy_disabled_by_default() {
+    let mut collector = TestLogCollector::new();
+    writeln!(collector, "line").unwrap();
+
+    assert!(collector.write_calls().is_empty());
+    assert_eq!(collector.flush_count(), 0);
+}
+
+#[test]
+fn test_extend_from_output_collects_both_streams() {
+    use std::process::{ExitStatus, Output};
+
+    let output = Output {
+        status: ExitStatus::default(),
+        stdout: b"out 1\nout 2".to_vec(),
+        stderr: b"ERROR err 1\n".to_vec(),
+    };
+    let mut collector = TestLogCollector::new();
+    writeln!(collector, "in-process").unwrap();
+    collector.extend_from_output(&output);
+
+    assert_eq!(
+        collector.clone_lines(),
+        vec!["in-process", "out 1", "out 2", "ERROR err 1"]
+    );
+    assert_eq!(
+        collector
+            .lines_at_level(test_log_collector::Level::Error)
+            .len(),
+        1
+    );
+    assert_eq!(TestLogCollector::from(output).count(), 3);
+}
+
+#[test]
+fn test_from_command_output() {
+    let output = std::process::Command::new(env!("CARGO"))
+        .arg("--version")
+        .output()
+        .unwrap();
+    let collector = TestLogCollector::from(&output);
+    assert!(collector.contains("cargo"));
+}
+
+#[test]
+fn test_as_reader_replays_content() {
+    use std::io::{BufRead, Read};
+
+    let mut collector = TestLogCollector::new();
+    write!(collector, "alpha\n\nbeta\ngam").unwrap();
+
+    let mut all = String::new();
+    collector.as_reader().read_to_string(&mut all).unwrap();
+    assert_eq!(all, "alpha\n\nbeta\ngam");
+
+    let mut reader = collector.as_reader();
+    let mut small = [0u8; 3];
+    assert_eq!(reader.read(&mut small).unwrap(), 3);
+    assert_eq!(&small, b"alp");
+    let mut rest = String::new();
+    reader.read_line(&mut rest).unwrap();
+    assert_eq!(rest, "ha\n");
+
+    let empty = TestLogCollector::new();
+    assert_eq!(empty.as_reader().lines().count(), 0);
+}
+
+#[test]
+fn test_replay_into_another_collector() {
+    let mut collector = TestLogCollector::new();
+    writeln!(collector, "one").unwrap();
+    write!(collector, "unfinished").unwrap();
+
+    let mut target = TestLogCollector::new();
+    collector.replay_into(&mut target).unwrap();
+    assert_eq!(target.clone_lines(), vec!["one"]);
+}
+
+#[test]
+fn test_replay_with_timing_preserves_gaps() {
+    use std::time::{Duration, Instant};
+
+    let mut collector = TestLogCollector::new();
+    writeln!(collector, "before").unwrap();
+    thread::sleep(Duration::from_millis(100));
+    writeln!(collector, "after").unwrap();
+
+    let start = Instant::now();
+    let mut out = Vec::new();
+    collector.replay_with_timing(&mut out, 2.0).unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(50));
+    assert_eq!(out, b"before\nafter\n");
+}
+
+#[test]
+#[should_panic(expected = "replay speed must be positive and finite, got 0")]
+fn test_replay_with_timing_rejects_zero_speed() {
+    TestLogCollector::new()
+        .replay_with_timing(&mut Vec::new(), 0.0)
+        .unwrap();
+}
+
+#[test]
+fn test_take_drain_and_pop_keep_metadata_in_sync() {
+    let mut collector = TestLogCollector::new().with_index_threshold(2);
+    for line in ["ERROR one", "two", "ERROR three", "four", "five"] {
+        writeln!(collector, "{}", line).unwrap();
+    }
+    write!(collector, "partial").unwrap();
+
+    assert_eq!(collector.pop_front(1), vec!["ERROR one"]);
+    assert_eq!(collector.drain(1..=2), vec!["ERROR three", "four"]);
+    assert_eq!(collector.clone_lines(), vec!["two", "five"]);
+    assert_eq!(collector.lines_with_tokens(&["five"]), vec![1]);
+    assert!(collector
+        .lines_at_level(test_log_collector::Level::Error)
+        .is_empty());
+
+    assert_eq!(collector.take_lines(), vec!["two", "five"]);
+    assert!(collector.line_hashes().is_empty());
+    collector.flush().unwrap();
+    assert_eq!(collector.clone_lines(), vec!["partial"]);
+}
+
+#[test]
+fn test_pending_partial_line() {
+    let mut collector = TestLogCollector::new();
+    assert_eq!(collector.pending(), "");
+    assert!(!collector.has_pending());
+
+    write!(collector, "first\nsec").unwrap();
+    assert_eq!(collector.pending(), "sec");
+    write!(collector, "ond").unwrap();
+    assert_eq!(collector.pending(), "second");
+    assert!(collector.has_pending());
+
+    writeln!(collector).unwrap();
+    assert!(!collector.has_pending());
+    assert_eq!(collector.clone_lines(), vec!["first", "second"]);
+}
+
+#[test]
+fn test_iteration_and_indexing() {
+    let mut collector = TestLogCollector::new();
+    for line in ["a", "b", "c"] {
+        writeln!(collector, "{}", line).unwrap();
+    }
+    write!(collector, "partial").unwrap();
+
+    assert_eq!(collector.iter().len(), 3);
+    assert_eq!(
+        collector.iter().rev().collect::<Vec<_>>(),
+        vec!["c", "b", "a"]
+    );
+    let mut seen = Vec::new();
+    for line in &collector {
+        seen.push(line);
+    }
+    assert_eq!(seen, vec!["a", "b", "c"]);
+
+    assert_eq!(&collector[1], "b");
+    assert_eq!(&collector[1..], ["b", "c"]);
+    assert_eq!(&collector[..=1], ["a", "b"]);
+    assert_eq!(collector[..].len(), 3);
+
+    let owned: Vec<String> = collector.into_iter().collect();
+    assert_eq!(owned, vec!["a", "b", "c"]);
+}
+
+#[test]
+#[should_panic(expected = "index out of bounds")]
+fn test_index_out_of_bounds_panics() {
+    let collector = TestLogCollector::new();
+    let _ = &collector[0];
+}
+
+#[test]
+fn test_display_and_string_rendering() {
+    let mut collector = TestLogCollector::new();
+    assert_eq!(collector.to_string(), "");
+    write!(collector, "only pending").unwrap();
+    assert_eq!(collector.to_string_lossy_with_pending(), "only pending");
+
+    writeln!(collector).unwrap();
+    writeln!(collector).unwrap();
+    write!(collector, "tail").unwrap();
+    assert_eq!(collector.to_string(), "only pending\n");
+    assert_eq!(
+        collector.to_string_lossy_with_pending(),
+        "only pending\n\ntail"
+    );
+    assert_eq!(collector.into_string(), "only pending\n");
+}
+
+#[test]
+fn test_partial_eq_against_strings() {
+    let mut collector = TestLogCollector::new();
+    writeln!(collector, "a").unwrap();
+    writeln!(collector, "b").unwrap();
+
+    assert_eq!(collector, ["a", "b"]);
+    assert_eq!(collector, vec!["a", "b"]);
+    assert_eq!(collector, vec!["a".to_string(), "b".to_string()]);
+    let slice: &[&str] = &["a", "b"];
+    assert_eq!(collector, slice);
+    assert_ne!(collector, ["a"]);
+    assert_ne!(collector, ["a", "c"]);
+    assert_eq!(
+        format!("{:?}", collector),
+        "TestLogCollector { lines: [\"a\", \"b\"], pending: \"\", .. }"
+    );
+}
+
+#[test]
+#[should_panic(
+    expected = "collected lines differ from expected (-expected +collected):\n  start\n- middle\n+ changed\n  end\n+ extra\n"
+)]
+fn test_assert_lines_eq_shows_diff() {
+    std::env::set_var("NO_COLOR", "1");
+    let mut collector = TestLogCollector::new();
+    for line in ["start", "changed", "end", "extra"] {
+        writeln!(collector, "{}", line).unwrap();
+    }
+    collector.assert_lines_eq(&["start", "middle", "end"]);
+}
+
+#[test]
+fn test_extend_and_collect_prerecorded_lines() {
+    let mut collector = TestLogCollector::new().with_replacement("secret", "***");
+    collector.expect_count("***", 1);
+    write!(collector, "partial").unwrap();
+    collector.extend(["token secret", "plain"]);
+    collector.extend(vec!["owned".to_string()]);
+
+    assert_eq!(collector, ["token ***", "plain", "owned"]);
+    assert_eq!(collector.pending(), "partial");
+    collector.verify();
+
+    let collected: TestLogCollector = (1..=3).map(|n| format!("line {}", n)).collect();
+    assert_eq!(collected, ["line 1", "line 2", "line 3"]);
+}
+
+#[test]
+fn test_fmt_write_matches_io_write() {
+    fn render(out: &mut impl std::fmt::Write) -> std::fmt::Result {
+        write!(out, "a{}", 1)?;
+        out.write_str("\npartial")
+    }
+
+    let mut collector = TestLogCollector::new().with_write_spy();
+    render(&mut collector).unwrap();
+    assert_eq!(collector, ["a1"]);
+    assert_eq!(collector.pending(), "partial");
+    assert_eq!(collector.write_calls().concat(), b"a1\npartial");
+}
+
+#[test]
+fn test_write_splits_any_chunking_identically() {
+    let content: Vec<char> = "alpha\n\nbeta gamma\nunicode é ü\ntrailing"
+        .chars()
+        .collect();
+    for chunk_size in 1..=content.len() {
+        let mut collector = TestLogCollector::new();
+        for chunk in content.chunks(chunk_size) {
+            let chunk: String = chunk.iter().collect();
+            collector.write_all(chunk.as_bytes()).unwrap();
+        }
+        assert_eq!(
+            collector,
+            ["alpha", "", "beta gamma", "unicode é ü"],
+            "chunk size {}",
+            chunk_size
+        );
+        assert_eq!(collector.pending(), "trailing");
+    }
+}
+
+#[test]
+fn test_write_vectored_collects_all_buffers_as_one_write() {
+    use std::io::IoSlice;
+
+    let mut collector = TestLogCollector::new().with_write_spy();
+    let bytes = "caf\u{e9}\n".as_bytes();
+    let bufs = [
+        IoSlice::new(&bytes[..4]),
+        IoSlice::new(&[]),
+        IoSlice::new(&bytes[4..]),
+        IoSlice::new(b"next"),
+    ];
+    assert_eq!(collector.write_vectored(&bufs).unwrap(), bytes.len() + 4);
+    assert_eq!(collector, ["caf\u{e9}"]);
+    assert_eq!(collector.pending(), "next");
+    assert_eq!(collector.write_calls().len(), 1);
+
+    assert_eq!(
+        collector.write_vectored(&[IoSlice::new(b"!\n")]).unwrap(),
+        2
+    );
+    assert_eq!(collector.write_vectored(&[]).unwrap(), 0);
+    assert_eq!(collector, ["caf\u{e9}", "next!"]);
+}
+
+#[test]
+fn test_raw_bytes_preserved_per_line() {
+    let mut collector = TestLogCollector::new()
+        .with_raw_bytes()
+        .with_replacement("secret", "***");
+    collector.write_all(b"secret \xc3").unwrap();
+    collector
+        .write_all(b"\xa9 \xff\nnext\n\npartial \x80")
+        .unwrap();
+    collector.flush().unwrap();
+
+    assert_eq!(
+        collector.raw_lines(),
+        [
+            &b"secret \xc3\xa9 \xff"[..],
+            &b"next"[..],
+            &b""[..],
+            &b"partial \x80"[..]
+        ]
+    );
+    assert_eq!(&collector[0], "*** é \u{fffd}");
+    assert_eq!(collector.drain(..1).len(), 1);
+    assert_eq!(collector.raw_lines()[0], b"next");
+}
+
+#[test]
+fn test_strict_utf8_rejects_invalid_writes() {
+    let mut collector = TestLogCollector::new().with_strict_utf8();
+    writeln!(collector, "valid é").unwrap();
+    let error = collector.write(b"bad \xff\n").unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    assert_eq!(error.to_string(), "invalid UTF-8 at byte 4");
+    assert_eq!(collector, ["valid é"]);
+    assert!(!collector.has_pending());
+}
+
+#[test]
+fn test_multibyte_characters_split_across_writes() {
+    let text = "emoji 🦀 and CJK 日本語\n";
+    let bytes = text.as_bytes();
+    for split in 0..=bytes.len() {
+        let mut collector = TestLogCollector::new();
+        collector.write_all(&bytes[..split]).unwrap();
+        collector.write_all(&bytes[split..]).unwrap();
+        assert_eq!(collector, ["emoji 🦀 and CJK 日本語"], "split at {}", split);
+    }
+
+    let mut collector = TestLogCollector::new();
+    for byte in bytes {
+        collector.write_all(&[*byte]).unwrap();
+    }
+    assert_eq!(collector, ["emoji 🦀 and CJK 日本語"]);
+}
+
+#[test]
+fn test_incomplete_utf8_at_flush() {
+    let mut collector = TestLogCollector::new();
+    collector
+        .write_all("ok 🦀".as_bytes().split_last().unwrap().1)
+        .unwrap();
+    assert_eq!(collector.pending(), "ok ");
+    collector.flush().unwrap();
+    assert_eq!(collector, ["ok \u{fffd}"]);
+
+    let mut strict = TestLogCollector::new().with_strict_utf8();
+    strict.write_all(&"日".as_bytes()[..2]).unwrap();
+    strict.write_all(&"日".as_bytes()[2..]).unwrap();
+    strict.write_all(&"本".as_bytes()[..1]).unwrap();
+    let error = strict.flush().unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    assert_eq!(strict, ["日"]);
+}
+
+#[test]
+fn test_newline_policies() {
+    use test_log_collector::NewlinePolicy;
+
+    let input = "a\r\nb\rc\r\r\nd\r";
+    let expected: [(NewlinePolicy, &[&str], &str); 3] = [
+        (NewlinePolicy::Keep, &["a\r", "b\rc\r\r"], "d\r"),
+        (NewlinePolicy::StripTrailingCr, &["a", "b\rc\r"], "d\r"),
+        (NewlinePolicy::CrTerminates, &["a", "b", "c", "", "d"], ""),
+    ];
+    for (policy, lines, pending) in expected {
+        for split in 0..=input.len() {
+            let mut collector = TestLogCollector::new()
+                .with_newline_policy(policy)
+                .with_raw_bytes();
+            collector.write_all(&input.as_bytes()[..split]).unwrap();
+            collector.write_all(&input.as_bytes()[split..]).unwrap();
+            assert_eq!(collector, lines, "{:?} split at {}", policy, split);
+            assert_eq!(
+                collector.pending(),
+                pending,
+                "{:?} split at {}",
+                policy,
+                split
+            );
+            let raw: Vec<&[u8]> = lines.iter().map(|line| line.as_bytes()).collect();
+            if policy != NewlinePolicy::StripTrailingCr {
+                assert_eq!(
+                    collector.raw_lines(),
+                    raw,
+                    "{:?} split at {}",
+                    policy,
+                    split
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_cr_terminates_then_newline_in_next_write_after_content() {
+    use test_log_collector::NewlinePolicy;
+
+    let mut collector = TestLogCollector::new().with_newline_policy(NewlinePolicy::CrTerminates);
+    write!(collector, "one\r").unwrap();
+    write!(collector, "two\n\n").unwrap();
+    assert_eq!(collector, ["one", "two", ""]);
+}
+
+#[test]
+fn test_custom_delimiters() {
+    let mut collector = TestLogCollector::new()
+        .with_delimiter(b'\0')
+        .with_raw_bytes();
+    for chunk in [&b"a\nb\0c"[..], b"\0\0d\xff", b"\0tail\n"] {
+        collector.write_all(chunk).unwrap();
+    }
+    assert_eq!(collector, ["a\nb", "c", "", "d\u{fffd}"]);
+    assert_eq!(collector.raw_lines()[3], b"d\xff");
+    assert_eq!(collector.pending(), "tail\n");
+}
+
+#[test]
+#[should_panic(expected = "delimiter 0xff is not ASCII")]
+fn test_non_ascii_delimiter_rejected() {
+    let _ = TestLogCollector::new().with_delimiter(0xff);
+}
+
+#[test]
+fn test_max_line_len_caps_pending_line() {
+    let mut collector = TestLogCollector::new().with_max_line_len(5);
+    for _ in 0..1_000 {
+        collector.write_all(&[b'a'; 1_000]).unwrap();
+    }
+    assert_eq!(collector.pending(), "aaaaa");
+    collector.write_all("é\nok\n".as_bytes()).unwrap();
+
+    assert_eq!(collector, ["aaaaa…[truncated 999997 bytes]", "ok"]);
+    assert_eq!(collector.original_len(0), Some(1_000_002));
+    assert_eq!(collector.original_len(1), None);
+}
+
+#[test]
+fn test_max_line_len_respects_char_boundaries() {
+    let mut collector = TestLogCollector::new().with_max_line_len(3);
+    writeln!(collector, "ééé").unwrap();
+    assert_eq!(collector, ["é…[truncated 4 bytes]"]);
+}
+
+#[test]
+fn test_records_group_continuation_lines() {
+    let mut collector = TestLogCollector::new();
+    write!(
+        collector,
+        "  orphan continuation\nError: boom\n    at a\n    at b\nnext\n"
+    )
+    .unwrap();
+
+    assert_eq!(
+        collector.records(),
+        [
+            "  orphan continuation",
+            "Error: boom\n    at a\n    at b",
+            "next"
+        ]
+    );
+}
+
+#[test]
+fn test_builder_combines_options() {
+    let mut collector = TestLogCollector::builder()
+        .max_lines(2)
+        .max_line_len(12)
+        .strip_ansi(true)
+        .scrubber(|line| line.to_uppercase())
+        .build();
+    write!(
+        collector,
+        "\x1b]8;;https://example.com\x1b\\link\x1b]8;;\x07\nfirst\n\x1b[1mbold\x1b[0m\na much longer line\n"
+    )
+    .unwrap();
+
+    assert_eq!(collector, ["BOLD", "A MUCH LONGE…[TRUNCATED 6 BYTES]"]);
+}
+
+#[test]
+fn test_collapsed_duplicates() {
+    let mut collector = TestLogCollector::new().with_collapsed_duplicates();
+    write!(collector, "a\na\nb\na\na\na\n").unwrap();
+
+    assert_eq!(collector, ["a", "b", "a"]);
+    assert_eq!(
+        (0..3)
+            .map(|i| collector.repeat_count(i))
+            .collect::<Vec<_>>(),
+        [2, 1, 3]
+    );
+    assert_eq!(collector.expanded_lines().len(), 6);
+    assert_eq!(collector.duplicates(4), [("a", 5)]);
+}
+
+#[test]
+fn test_retain_and_map_in_place_keep_metadata_in_sync() {
+    let mut collector = TestLogCollector::new();
+    write!(collector, "INFO keep\nDEBUG drop\nINFO error soon\n").unwrap();
+
+    collector.retain(|line| !line.starts_with("DEBUG"));
+    collector.map_in_place(|line| line.replace("INFO error", "ERROR"));
+
+    assert_eq!(collector, ["INFO keep", "ERROR soon"]);
+    assert_eq!(
+        collector.line_level(1),
+        Some(test_log_collector::Level::Error)
+    );
+    assert_eq!(collector.line_hashes()[1], {
+        let mut other = TestLogCollector::new();
+        writeln!(other, "ERROR soon").unwrap();
+        other.line_hashes()[0]
+    });
+    assert_eq!(collector.matching_lines("soon"), ["ERROR soon"]);
+}
+
+#[test]
+fn test_grep_uses_token_index() {
+    let mut collector = TestLogCollector::new().with_index_threshold(2);
+    for i in 0..5 {
+        writeln!(
+            collector,
+            "request {} {}",
+            i,
+            if i % 2 == 0 { "ok" } else { "failed" }
+        )
+        .unwrap();
+    }
+
+    assert_eq!(collector.grep("1 failed"), [(1, "request 1 failed")]);
+    assert_eq!(collector.grep(" ok").len(), 3);
+    assert_eq!(
+        collector.grep_matching(test_log_collector::Matcher::ends_with("failed")),
+        [(1, "request 1 failed"), (3, "request 3 failed")]
+    );
+}
+
+#[test]
+fn test_positional_accessors() {
+    let mut collector = TestLogCollector::new();
+    assert_eq!(collector.last(), None);
+    assert!(collector.tail(3).is_empty());
+
+    write!(collector, "boot\nserve\nshutdown complete\n").unwrap();
+    assert_eq!(collector.first(), Some("boot"));
+    assert_eq!(collector.last(), Some("shutdown complete"));
+    assert_eq!(collector.nth(1), Some("serve"));
+    assert_eq!(collector.tail(0), [] as [&str; 0]);
+    assert_eq!(collector.tail(1), ["shutdown complete"]);
+}
+
+#[test]
+fn test_contains_ignore_case_is_unicode_aware() {
+    let mut collector = TestLogCollector::new();
+    writeln!(collector, "ÉCHEC de la connexion").unwrap();
+
+    assert!(collector.contains_ignore_case("échec"));
+    assert!(!collector.contains_ignore_case("succès"));
+    assert_eq!(
+        collector
+            .grep_matching(test_log_collector::Matcher::contains_ignore_case(
+                "Connexion"
+            ))
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn test_contains_ignore_case_uses_index() {
+    let mut collector = TestLogCollector::new().with_index_threshold(2);
+    write!(
+        collector,
+        "GET /health 200\nPOST /Users 500 Internal\nÉCHEC total\nGET /health 200\n"
+    )
+    .unwrap();
+
+    assert!(collector.contains_ignore_case("post /users 500 internal"));
+    assert!(collector.contains_ignore_case("échec TOTAL"));
+    assert!(collector.contains_ignore_case("ealth 20"));
+    assert!(!collector.contains_ignore_case("get /users 500"));
+}
+
+#[test]
+fn test_sections_apply_when_lines_complete() {
+    let mut collector = TestLogCollector::new();
+    collector.begin_section("setup");
+    write!(collector, "started in setup").unwrap();
+    collector.begin_section("exercise");
+    writeln!(collector, ", finished in exercise").unwrap();
+    collector.retain(|_| true);
+
+    assert!(collector.section_lines("setup").is_empty());
+    assert_eq!(collector.line_section(0), Some("exercise"));
+}
+
+#[test]
+fn test_pause_applies_to_lines_completed_while_paused() {
+    let mut collector = TestLogCollector::new().with_max_level(test_log_collector::Level::Info);
+    write!(collector, "started before pause").unwrap();
+    collector.pause();
+    write!(collector, ", ended while paused\nDEBUG filtered anyway\n").unwrap();
+    collector.resume();
+    writeln!(collector, "after").unwrap();
+
+    assert_eq!(collector, ["after"]);
+    assert_eq!(collector.suppressed_count(), 1);
+    assert_eq!(collector.unfiltered_count(), 3);
+}
+
+#[test]
+fn test_reservoir_sampling_is_ordered_and_uniform() {
+    let mut collector =
+        TestLogCollector::new().with_sampling(test_log_collector::Sampling::Reservoir(1_000));
+    for i in 0..20_000 {
+        writeln!(collector, "{}", i).unwrap();
+    }
+
+    let sample: Vec<usize> = collector.iter().map(|line| line.parse().unwrap()).collect();
+    assert_eq!(sample.len(), 1_000);
+    assert!(sample.windows(2).all(|pair| pair[0] < pair[1]));
+    let first_half = sample.iter().filter(|&&n| n < 10_000).count();
+    assert!((400..600).contains(&first_half), "{}", first_half);
+    assert_eq!(collector.total_written(), 20_000);
+}
+
+#[test]
+fn test_reservoir_sampling_keeps_index_and_shared_lines_current() {
+    let mut collector = TestLogCollector::new()
+        .with_sampling(test_log_collector::Sampling::Reservoir(50))
+        .with_index_threshold(10);
+    let shared = collector.shared_lines();
+    assert!(shared.is_empty());
+    for i in 0..5_000 {
+        writeln!(collector, "line {}", i).unwrap();
+    }
+    let _ = collector.shared_lines();
+    for i in 5_000..10_000 {
+        writeln!(collector, "line {}", i).unwrap();
+    }
+
+    let lines = collector.clone_lines();
+    let numbers: Vec<usize> = lines
+        .iter()
+        .map(|line| line[5..].parse().unwrap())
+        .collect();
+    assert!(numbers.windows(2).all(|pair| pair[0] < pair[1]));
+    let shared: Vec<String> = collector
+        .shared_lines()
+        .iter()
+        .map(|line| line.to_string())
+        .collect();
+    assert_eq!(shared, lines);
+    assert_eq!(collector.lines_with_tokens(&["line"]).len(), 50);
+    for (position, line) in lines.iter().enumerate() {
+        assert_eq!(collector.lines_with_tokens(&[&line[5..]]), [position]);
+    }
+}
+
+#[test]
+fn test_disk_archive_round_trips_and_clears() {
+    let mut collector = TestLogCollector::new().with_disk_archive(4).unwrap();
+    writeln!(collector, "back\\slash").unwrap();
+    collector.extend(["multi\nline", "plain", "a", "b", "c"]);
+
+    assert_eq!(collector.archived_count(), 3);
+    let all: Vec<String> = collector.all_lines().unwrap().map(Result::unwrap).collect();
+    assert_eq!(all, ["back\\slash", "multi\nline", "plain", "a", "b", "c"]);
+
+    collector.clear();
+    writeln!(collector, "fresh").unwrap();
+    assert_eq!(collector.archived_count(), 0);
+    assert_eq!(collector.grep_all("").unwrap(), [(0, "fresh".to_string())]);
+}
+
+#[test]
+fn test_shrink_to_fit_after_clear() {
+    let mut collector = TestLogCollector::with_capacity(1_000, 64);
+    for i in 0..1_000 {
+        writeln!(collector, "line {}", i).unwrap();
+    }
+    collector.clear();
+    collector.shrink_to_fit();
+    writeln!(collector, "reused").unwrap();
+
+    assert_eq!(collector, ["reused"]);
+}
+
+#[test]
+fn test_shared_lines_follow_changes() {
+    let mut collector = TestLogCollector::new();
+    write!(collector, "a\nb\n").unwrap();
+    let before = collector.shared_lines();
+
+    writeln!(collector, "c").unwrap();
+    let appended = collector.shared_lines();
+    assert!(std::sync::Arc::ptr_eq(&before[1], &appended[1]));
+    assert_eq!(appended.len(), 3);
+    assert!(std::sync::Arc::ptr_eq(&appended, &collector.shared_lines()));
+
+    collector.map_in_place(str::to_uppercase);
+    assert_eq!(&*collector.shared_lines()[0], "A");
+    collector.pop_front(1);
+    assert_eq!(&*collector.shared_lines()[0], "B");
+    collector.clear();
+    assert!(collector.shared_lines().is_empty());
+}
+
+#[test]
+fn test_clone_is_independent_and_debug_is_bounded() {
+    let mut collector = TestLogCollector::default();
+    for i in 0..12 {
+        writeln!(collector, "line {i}").unwrap();
+    }
+    write!(collector, "partial").unwrap();
+
+    let mut copy = collector.clone();
+    writeln!(copy, " done").unwrap();
+    assert_eq!(copy.count(), 13);
+    assert_eq!(collector.count(), 12);
+    assert_eq!(collector.pending(), "partial");
+
+    let debug = format!("{:?}", collector);
+    assert!(debug.starts_with("TestLogCollector { count: 12, last_lines: [\"line 2\""));
+    assert!(!debug.contains("\"line 1\""));
+    assert!(debug.ends_with("\"line 11\"], pending: \"partial\", .. }"));
+}
+
+#[test]
+fn test_on_line_hooks_run_in_order_until_removed() {
+    use std::sync::{Arc, Mutex};
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let mut collector = TestLogCollector::new().with_max_level(test_log_collector::Level::Info);
+    let first = {
+        let seen = Arc::clone(&seen);
+        collector.on_line(move |line| seen.lock().unwrap().push(format!("first {line}")))
+    };
+    {
+        let seen = Arc::clone(&seen);
+        collector.on_line(move |line| seen.lock().unwrap().push(format!("second {line}")));
+    }
+
+    write!(collector, "INFO one\nDEBUG hidden\n").unwrap();
+    assert!(collector.remove_hook(first));
+    writeln!(collector, "INFO two").unwrap();
+
+    assert_eq!(
+        *seen.lock().unwrap(),
+        ["first INFO one", "second INFO one", "second INFO two"]
+    );
+}
+
+#[test]
+fn test_subscribe_streams_lines_to_each_receiver() {
+    let mut collector = TestLogCollector::new();
+    writeln!(collector, "before").unwrap();
+    let first = collector.subscribe();
+    let second = collector.subscribe();
+    drop(second);
+
+    write!(collector, "one\ntwo").unwrap();
+    assert_eq!(first.try_iter().collect::<Vec<_>>(), ["one"]);
+    collector.flush().unwrap();
+    assert_eq!(first.try_iter().collect::<Vec<_>>(), ["two"]);
+    assert_eq!(collector, ["before", "one", "two"]);
+}
+
+#[test]
+fn test_tail_iter_ends_when_collector_is_dropped() {
+    let collector = TestLogCollector::new_shared();
+    let tail = collector.lock().unwrap().tail_iter();
+
+    let worker = thread::spawn(move || {
+        writeln!(collector.lock().unwrap(), "first").unwrap();
+        writeln!(collector.lock().unwrap(), "last").unwrap();
+    });
+
+    assert_eq!(tail.collect::<Vec<_>>(), ["first", "last"]);
+    worker.join().unwrap();
+}
+
+#[test]
+fn test_clone_does_not_share_hooks() {
+    let mut collector = TestLogCollector::new();
+    let lines = collector.subscribe();
+    let mut clone = collector.clone();
+    writeln!(clone, "from the clone").unwrap();
+    drop(clone);
+    writeln!(collector, "from the original").unwrap();
+    drop(collector);
+
+    assert_eq!(lines.iter().collect::<Vec<_>>(), ["from the original"]);
+}
+
+#[test]
+fn test_merge_interleaves_by_completion_order() {
+    let mut a = TestLogCollector::new();
+    let mut b = TestLogCollector::new();
+    writeln!(b, "b1").unwrap();
+    writeln!(a, "a1").unwrap();
+    writeln!(b, "b2").unwrap();
+    writeln!(a, "WARN a2").unwrap();
+
+    a.merge(&b);
+    assert_eq!(a, ["b1", "a1", "b2", "WARN a2"]);
+    assert_eq!(
+        a.lines_at_level(test_log_collector::Level::Warn),
+        ["WARN a2"]
+    );
+    assert_eq!(b.count(), 2);
+}
+
+#[test]
+fn test_diff_reports_added_removed_and_changed() {
+    let mut ours = TestLogCollector::new();
+    let mut theirs = TestLogCollector::new();
+    write!(ours, "start\nold\nonly ours\nend\n").unwrap();
+    write!(theirs, "start\nnew\nend\nextra\n").unwrap();
+
+    let diff = ours.diff(&theirs);
+    assert_eq!(diff.changed(), [("old", "new")]);
+    assert_eq!(diff.removed(), ["only ours"]);
+    assert_eq!(diff.added(), ["extra"]);
+    assert!(ours.diff(&ours.clone()).is_empty());
+}
+
+#[test]
+#[should_panic(expected = "collected lines are 0.50 similar to expected, below 0.80")]
+fn test_assert_similar_fails_below_threshold() {
+    let mut collector = TestLogCollector::new();
+    write!(collector, "a\nb\nx\ny\n").unwrap();
+    collector.assert_similar(&["a", "b", "c", "d"], 0.8);
+}
+
+#[test]
+#[should_panic(
+    expected = "collected lines differ from expected in any order:\nmissing: b\nextra: c\nextra: a\nextra: a\n"
+)]
+fn test_assert_same_lines_any_order_reports_missing_and_extra() {
+    let mut collector = TestLogCollector::new();
+    write!(collector, "c\na\nb\na\na\n").unwrap();
+    collector.assert_same_lines_any_order(&["a", "b", "b"]);
+}
+
+#[test]
+fn test_normalization_presets_respect_word_boundaries() {
+    use test_log_collector::{Preset, Presets};
+
+    let durations = Presets::from(Preset::Durations);
+    assert_eq!(
+        durations.normalize("took 250µs, 1.5s and 2min; x86 5mb v2s"),
+        "took <DURATION>, <DURATION> and <DURATION>; x86 5mb v2s"
+    );
+    assert_eq!(
+        Presets::from(Preset::Addresses).normalize("ptr=0x7ffd5e8a1b20 flags=0x1f"),
+        "ptr=<ADDR> flags=0x1f"
+    );
+    assert_eq!(
+        Presets::from(Preset::Timestamps)
+            .normalize("at 2024-01-15 10:30:00+02:00, 2024-01-15T10:30:00.123456Z and 2024-01-15"),
+        "at <TIMESTAMP>, <TIMESTAMP> and 2024-01-15"
+    );
+
+    let mut collector =
+        TestLogCollector::new().with_normalization(Preset::Timestamps | Preset::Durations);
+    writeln!(collector, "2024-01-15T10:30:00Z done in 12.3ms").unwrap();
+    assert_eq!(collector, ["<TIMESTAMP> done in <DURATION>"]);
+    assert_eq!(
+        collector.provenance(0).unwrap(),
+        ["scrubbed by normalize timestamps, durations"]
+    );
+}
+
+#[test]
+fn test_byte_offsets_follow_written_bytes() {
+    let mut collector = TestLogCollector::new().with_max_line_len(3);
+    collector.write_all(b"ab\xff\ncrlf\r\n").unwrap();
+    collector.write_all(b"par").unwrap();
+    collector.write_all(b"tial\nlast").unwrap();
+    collector.flush().unwrap();
+    collector.extend(["extended".to_string()]);
+
+    let offsets: Vec<_> = (0..collector.count())
+        .map(|i| collector.byte_offset(i))
+        .collect();
+    assert_eq!(offsets, [Some(0), Some(4), Some(10), Some(18), None]);
+
+    collector.clear();
+    writeln!(collector, "again").unwrap();
+    assert_eq!(collector.byte_offset(0), Some(0));
+}
+
+#[test]
+fn test_raw_line_keeps_text_before_scrubbing() {
+    let mut collector = TestLogCollector::new()
+        .with_ansi_stripping()
+        .with_scrubber(|line: &str| line.replace("secret", "***"));
+    writeln!(collector, "\x1b[1msecret\x1b[0m value").unwrap();
+    writeln!(collector, "plain").unwrap();
+
+    assert_eq!(collector, ["*** value", "plain"]);
+    assert_eq!(collector.raw_line(0), Some("\x1b[1msecret\x1b[0m value"));
+    assert_eq!(collector.raw_line(1), Some("plain"));
+    assert_eq!(collector.raw_line(2), None);
+}
+
+#[test]
+fn test_context_merges_overlapping_windows() {
+    let mut collector = TestLogCollector::new();
+    for i in 0..12 {
+        writeln!(
+            collector,
+            "{} {}",
+            if i == 3 || i == 5 || i == 11 {
+                "hit"
+            } else {
+                "miss"
+            },
+            i
+        )
+        .unwrap();
+    }
+    assert_eq!(
+        collector.context("hit", 1, 1),
+        ["miss 2", "hit 3", "miss 4", "hit 5", "miss 6", "miss 10", "hit 11"]
+    );
+    assert_eq!(collector.context("hit", 0, 0), ["hit 3", "hit 5", "hit 11"]);
+    assert!(collector.context("absent", 2, 2).is_empty());
+}
@@ -0,0 +1,86 @@
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+use test_log_collector::{SyncPolicy, TestLogCollector};
+
+const CHILD_ENV_VAR: &str = "TEST_LOG_COLLECTOR_WRITE_AHEAD_CHILD";
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "test_log_collector_{}_{}.log",
+        name,
+        std::process::id()
+    ))
+}
+
+/// Runs as the child of `test_capture_survives_abort` and aborts mid-test.
+#[test]
+fn crashing_child() {
+    let Some(path) = std::env::var_os(CHILD_ENV_VAR) else {
+        return;
+    };
+    let mut collector = TestLogCollector::new()
+        .with_write_ahead(path, SyncPolicy::EveryLine)
+        .unwrap();
+    writeln!(collector, "step 1").unwrap();
+    writeln!(collector, "step 2").unwrap();
+    write!(collector, "never finished").unwrap();
+    std::process::abort();
+}
+
+#[test]
+fn test_capture_survives_abort() {
+    let path = temp_path("abort");
+    let status = Command::new(std::env::current_exe().unwrap())
+        .args(["--exact", "crashing_child", "--nocapture"])
+        .env(CHILD_ENV_VAR, &path)
+        .status()
+        .unwrap();
+    assert!(!status.success());
+
+    let recovered = TestLogCollector::recover(&path).unwrap();
+    assert_eq!(recovered.clone_lines(), vec!["step 1", "step 2"]);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_write_ahead_recovers_torn_line_as_partial() {
+    let path = temp_path("torn");
+    fs::write(&path, "complete\ntorn").unwrap();
+
+    let mut recovered = TestLogCollector::recover(&path).unwrap();
+    assert_eq!(recovered.clone_lines(), vec!["complete"]);
+    recovered.flush().unwrap();
+    assert_eq!(recovered.clone_lines(), vec!["complete", "torn"]);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_write_ahead_records_stored_lines_only() {
+    let path = temp_path("filtered");
+    let mut collector = TestLogCollector::new()
+        .with_replacement("secret", "<REDACTED>")
+        .with_max_level(test_log_collector::Level::Info)
+        .with_write_ahead(&path, SyncPolicy::OnFlush)
+        .unwrap();
+    writeln!(collector, "INFO secret").unwrap();
+    writeln!(collector, "DEBUG dropped").unwrap();
+    write!(collector, "tail").unwrap();
+    collector.flush().unwrap();
+
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "INFO <REDACTED>\ntail\n"
+    );
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_write_ahead_create_error() {
+    let missing = temp_path("missing_dir").join("wal.log");
+    let Err(error) = TestLogCollector::new().with_write_ahead(&missing, SyncPolicy::Never) else {
+        panic!("creating a file in a missing directory should fail");
+    };
+    assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+}
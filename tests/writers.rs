@@ -0,0 +1,251 @@
+use std::io::{self, ErrorKind, Write};
+use test_log_collector::{CollectingWriter, FaultyCollector, Tee, TestLogCollector};
+
+#[test]
+fn test_tee_forwards_and_collects() {
+    let mut tee = TestLogCollector::tee(Vec::new());
+    writeln!(tee, "Line 1").unwrap();
+    write!(tee, "Partial").unwrap();
+
+    assert_eq!(tee.collector().clone_lines(), vec!["Line 1"]);
+    assert_eq!(tee.inner(), b"Line 1\nPartial");
+
+    tee.flush().unwrap();
+    let (collector, forwarded) = tee.into_parts();
+    assert_eq!(collector.clone_lines(), vec!["Line 1", "Partial"]);
+    assert_eq!(forwarded, b"Line 1\nPartial");
+}
+
+#[test]
+fn test_tee_propagates_inner_errors() {
+    let inner = FaultyCollector::new().fail_on_write(2, ErrorKind::BrokenPipe);
+    let mut tee = Tee::new(TestLogCollector::new(), inner);
+
+    writeln!(tee, "kept").unwrap();
+    let error = writeln!(tee, "dropped").unwrap_err();
+
+    assert_eq!(error.kind(), ErrorKind::BrokenPipe);
+    assert_eq!(tee.collector().clone_lines(), vec!["kept", "dropped"]);
+    assert_eq!(tee.inner().collector().clone_lines(), vec!["kept"]);
+}
+
+#[test]
+fn test_tee_forwards_what_a_short_write_collected() {
+    let mut tee = Tee::new(TestLogCollector::new().with_short_writes(3), Vec::new());
+
+    assert_eq!(tee.write(b"hello\n").unwrap(), 3);
+    tee.write_all(b"lo\nworld\n").unwrap();
+
+    assert_eq!(tee.inner(), b"hello\nworld\n");
+    assert_eq!(tee.collector().clone_lines(), vec!["hello", "world"]);
+}
+
+#[test]
+fn test_tee_to_sink() {
+    let mut tee = Tee::new(TestLogCollector::new(), io::sink());
+    writeln!(tee, "only collected").unwrap();
+    tee.collector_mut().clear();
+
+    assert_eq!(tee.collector().count(), 0);
+}
+
+#[test]
+fn test_no_faults_behaves_like_collector() {
+    let mut writer = FaultyCollector::new();
+    writeln!(writer, "Line 1").unwrap();
+    write!(writer, "Partial").unwrap();
+    writer.flush().unwrap();
+
+    assert_eq!(writer.into_inner().clone_lines(), vec!["Line 1", "Partial"]);
+}
+
+#[test]
+fn test_fail_on_nth_write_keeps_earlier_lines() {
+    let mut writer = FaultyCollector::new()
+        .fail_on_write(2, ErrorKind::BrokenPipe)
+        .fail_on_write(4, ErrorKind::PermissionDenied);
+
+    assert!(writer.write(b"one\n").is_ok());
+    assert_eq!(
+        writer.write(b"two\n").unwrap_err().kind(),
+        ErrorKind::BrokenPipe
+    );
+    assert!(writer.write(b"three\n").is_ok());
+    assert_eq!(
+        writer.write(b"four\n").unwrap_err().kind(),
+        ErrorKind::PermissionDenied
+    );
+
+    assert_eq!(writer.write_count(), 4);
+    assert_eq!(writer.collector().clone_lines(), vec!["one", "three"]);
+}
+
+#[test]
+fn test_transient_errors_surface_on_raw_write() {
+    let mut writer = FaultyCollector::new().fail_every(3, ErrorKind::WouldBlock);
+
+    let results: Vec<_> = (0..6)
+        .map(|_| writer.write(b"x").map_err(|e| e.kind()))
+        .collect();
+    assert_eq!(
+        results,
+        vec![
+            Ok(1),
+            Ok(1),
+            Err(ErrorKind::WouldBlock),
+            Ok(1),
+            Ok(1),
+            Err(ErrorKind::WouldBlock)
+        ]
+    );
+
+    writer.flush().unwrap();
+    assert_eq!(writer.collector().clone_lines(), vec!["xxxx"]);
+}
+
+#[test]
+fn test_short_writes_reconstruct_lines() {
+    let mut writer = FaultyCollector::new().short_writes(3);
+    writer.write_all(b"Hello\nWorld\n").unwrap();
+
+    assert_eq!(writer.write_count(), 4);
+    assert_eq!(writer.collector().clone_lines(), vec!["Hello", "World"]);
+}
+
+#[test]
+fn test_collecting_writer_preserves_short_writes() {
+    let inner = FaultyCollector::new().short_writes(4);
+    let mut writer = CollectingWriter::wrap(inner);
+
+    assert_eq!(writer.write(b"Hello\n").unwrap(), 4);
+    writer.write_all(b"o\nWorld\n").unwrap();
+
+    let (collector, inner) = writer.into_parts();
+    assert_eq!(collector.clone_lines(), vec!["Hello", "World"]);
+    assert_eq!(inner.collector().clone_lines(), vec!["Hello", "World"]);
+}
+
+#[test]
+fn test_collecting_writer_preserves_errors() {
+    let inner = FaultyCollector::new().fail_on_write(1, ErrorKind::ConnectionReset);
+    let mut writer = CollectingWriter::wrap(inner);
+
+    let error = writer.write(b"lost\n").unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::ConnectionReset);
+    writeln!(writer, "sent").unwrap();
+
+    assert_eq!(writer.collector().clone_lines(), vec!["sent"]);
+    assert_eq!(writer.inner().write_count(), 2);
+}
+
+#[test]
+fn test_collecting_writer_flush_completes_partial_line() {
+    let mut writer = CollectingWriter::wrap(Vec::new());
+    write!(writer, "partial").unwrap();
+    assert_eq!(writer.collector().count(), 0);
+
+    writer.flush().unwrap();
+    assert_eq!(writer.collector().clone_lines(), vec!["partial"]);
+    assert_eq!(writer.inner(), b"partial");
+}
+
+#[test]
+fn test_provenance_records_handles_and_scrubbers() {
+    let collector = TestLogCollector::new()
+        .with_replacement("secret", "***")
+        .with_scrubber(|line| line.to_uppercase());
+    let mut faulty = FaultyCollector::wrap(collector);
+    writeln!(faulty, "token secret").unwrap();
+    writeln!(faulty, "PLAIN").unwrap();
+    let mut collector = faulty.into_inner();
+    writeln!(collector, "direct").unwrap();
+
+    assert_eq!(
+        collector.provenance(0).unwrap(),
+        [
+            "via faulty collector",
+            "scrubbed by replace \"secret\" with \"***\"",
+            "scrubbed by scrubber #2",
+        ]
+    );
+    assert_eq!(collector.provenance(1).unwrap(), ["via faulty collector"]);
+    assert_eq!(
+        collector.provenance(2).unwrap(),
+        ["scrubbed by scrubber #2"]
+    );
+    assert!(collector.provenance(3).is_none());
+}
+
+#[test]
+fn test_provenance_names_pipeline_producers() {
+    use std::time::Duration;
+    use test_log_collector::Pipeline;
+
+    let mut pipeline = Pipeline::new();
+    let mut worker = pipeline.writer("worker");
+    write!(worker, "partial").unwrap();
+    drop(worker);
+
+    let collector = pipeline.shutdown(Duration::from_secs(5)).unwrap();
+    assert_eq!(
+        collector.provenance(0).unwrap(),
+        ["via pipeline producer \"worker\""]
+    );
+}
+
+#[test]
+fn test_collector_short_writes() {
+    let mut collector = TestLogCollector::new().with_short_writes(3);
+    assert_eq!(collector.write(b"abcdef\n").unwrap(), 3);
+    assert_eq!(collector.write(b"d").unwrap(), 1);
+    assert_eq!(collector.pending(), "abcd");
+
+    let mut writer = io::BufWriter::new(collector);
+    writeln!(writer, "ef").unwrap();
+    writeln!(writer, "second line").unwrap();
+    let Ok(collector) = writer.into_inner() else {
+        panic!("flush failed");
+    };
+    assert_eq!(collector, ["abcdef", "second line"]);
+}
+
+#[test]
+#[should_panic(expected = "with_short_writes requires a non-zero limit")]
+fn test_collector_short_writes_rejects_zero() {
+    let _ = TestLogCollector::new().with_short_writes(0);
+}
+
+#[test]
+fn test_record_per_write() {
+    let mut collector = TestLogCollector::new().with_record_per_write();
+    collector.write_all(b"one").unwrap();
+    collector.write_all(b"two\n").unwrap();
+    collector.write_all(b"caf\xc3").unwrap();
+    collector.write_all(b"\xa9").unwrap();
+    assert_eq!(collector, ["one", "two", "caf", "\u{e9}"]);
+    assert_eq!(collector.pending(), "");
+}
+
+#[test]
+fn test_auto_flush_on_drop_runs_hooks() {
+    let mut collector = TestLogCollector::new().with_auto_flush_on_drop();
+    let lines = collector.subscribe();
+    write!(collector, "last words").unwrap();
+    drop(collector);
+    assert_eq!(lines.iter().collect::<Vec<_>>(), ["last words"]);
+
+    let mut collector = TestLogCollector::new();
+    let lines = collector.subscribe();
+    write!(collector, "never completed").unwrap();
+    drop(collector);
+    assert_eq!(lines.iter().count(), 0);
+}
+
+#[test]
+fn test_terminal_claim() {
+    assert!(!TestLogCollector::new().is_terminal());
+    let collector = TestLogCollector::new().with_terminal(true);
+    assert!(collector.is_terminal());
+    assert!(collector.clone().is_terminal());
+    assert!(!collector.with_terminal(false).is_terminal());
+}